@@ -0,0 +1,49 @@
+use cdbc::arguments::Arguments;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::types::Type;
+use crate::{Any, AnyValueKind};
+
+/// Implementation of [`Arguments`] for the [`Any`] driver.
+///
+/// Values are kept in an owned, driver-neutral form and are encoded again by the
+/// underlying driver when the query is executed.
+#[derive(Default, Debug, Clone)]
+pub struct AnyArguments {
+    pub values: Vec<AnyValueKind>,
+}
+
+impl AnyArguments {
+    pub fn add<'q, T>(&mut self, value: T)
+    where
+        T: Encode<'q, Any> + Type<Any>,
+    {
+        let ty = value.produces().unwrap_or_else(T::type_info);
+
+        if let IsNull::Yes = value.encode(&mut self.values) {
+            self.values.push(AnyValueKind::Null(ty.0));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<'q> Arguments<'q> for AnyArguments {
+    type Database = Any;
+
+    fn reserve(&mut self, len: usize, _size_hint: usize) {
+        self.values.reserve(len);
+    }
+
+    fn add<T>(&mut self, value: T)
+    where
+        T: 'q + Encode<'q, Self::Database> + Type<Any>,
+    {
+        self.add(value)
+    }
+}
@@ -0,0 +1,73 @@
+//! Describes the sql read from stdin against the database on `DATABASE_URL` and prints it as json.
+//!
+//! Run by the checked `query!`,`query_as!`,`query_scalar!` macros of `cdbc-macro` at build time.
+use std::io::Read;
+
+use cdbc::column::Column;
+use cdbc::connection::Connection;
+use cdbc::database::Database;
+use cdbc::describe::Describe;
+use cdbc::executor::Executor;
+use cdbc::type_info::TypeInfo;
+use cdbc_any::AnyConnection;
+use either::Either;
+use serde_json::{json, Value};
+
+fn to_json<DB: Database>(db: &str, describe: Describe<DB>) -> Value {
+    let columns: Vec<Value> = describe
+        .columns
+        .iter()
+        .map(|c| json!({"name": c.name(), "type_info": c.type_info().name()}))
+        .collect();
+    let parameters = match describe.parameters {
+        Some(Either::Left(v)) => {
+            json!({"Left": v.iter().map(|t| t.name()).collect::<Vec<_>>()})
+        }
+        Some(Either::Right(n)) => json!({ "Right": n }),
+        None => Value::Null,
+    };
+    json!({
+        "db": db,
+        "describe": {
+            "columns": columns,
+            "parameters": parameters,
+            "nullable": describe.nullable,
+        }
+    })
+}
+
+fn describe(url: &str, sql: &str) -> cdbc::Result<Value> {
+    let conn = AnyConnection::connect(url)?;
+    Ok(match conn {
+        #[cfg(feature = "postgres")]
+        AnyConnection::Postgres(mut conn) => to_json("postgres", conn.describe(sql)?),
+        #[cfg(feature = "mysql")]
+        AnyConnection::MySql(mut conn) => to_json("mysql", conn.describe(sql)?),
+        #[cfg(feature = "sqlite")]
+        AnyConnection::Sqlite(mut conn) => to_json("sqlite", conn.describe(sql)?),
+        #[cfg(feature = "mssql")]
+        AnyConnection::Mssql(mut conn) => to_json("mssql", conn.describe(sql)?),
+    })
+}
+
+fn main() {
+    let url = match std::env::var("DATABASE_URL") {
+        Ok(v) => v,
+        Err(_) => {
+            eprintln!("`DATABASE_URL` must be set");
+            std::process::exit(1);
+        }
+    };
+    let mut sql = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut sql) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    match describe(&url, &sql) {
+        Ok(v) => println!("{}", v),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use cdbc::column::Column;
+use cdbc::utils::ustr::UStr;
+use crate::{Any, AnyTypeInfo};
+
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AnyColumn {
+    pub name: UStr,
+    pub ordinal: usize,
+    pub type_info: AnyTypeInfo,
+}
+
+impl Column for AnyColumn {
+    type Database = Any;
+
+    fn ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    fn name(&self) -> &str {
+        &*self.name
+    }
+
+    fn type_info(&self) -> &AnyTypeInfo {
+        &self.type_info
+    }
+}
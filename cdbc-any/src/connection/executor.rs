@@ -0,0 +1,293 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use cdbc::database::{Database, HasArguments, HasStatement};
+
+use cdbc::describe::Describe;
+use cdbc::error::Error;
+use cdbc::executor::{Execute, Executor};
+use cdbc::io::chan_stream::ChanStream;
+use either::Either;
+use crate::connection::map_statement;
+use crate::placeholder;
+use crate::{Any, AnyColumn, AnyConnection, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo};
+
+#[cfg(feature = "postgres")]
+use cdbc_pg::PgArguments;
+
+#[cfg(feature = "mysql")]
+use cdbc_mysql::MySqlArguments;
+
+#[cfg(feature = "sqlite")]
+use cdbc_sqlite::SqliteArguments;
+
+#[cfg(feature = "mssql")]
+use cdbc_mssql::MssqlArguments;
+
+/// Converts the results of the underlying driver as they arrive.
+fn into_any_stream<'e, R, Q>(s: ChanStream<'e, Either<R, Q>>) -> ChanStream<'e, Either<AnyQueryResult, AnyRow>>
+    where
+        R: Into<AnyQueryResult> + Send + 'e,
+        Q: Send + 'e,
+        AnyRow: TryFrom<Q, Error = Error>,
+{
+    s.try_map(|v| {
+        Ok(match v {
+            Either::Left(result) => Either::Left(result.into()),
+            Either::Right(row) => Either::Right(AnyRow::try_from(row)?),
+        })
+    })
+}
+
+/// The query of the underlying driver, keeps the timeout of the `Any` query.
+struct DriverQuery<'q, DB: Database> {
+    sql: &'q str,
+    arguments: Option<<DB as HasArguments<'q>>::Arguments>,
+    timeout: Option<Duration>,
+}
+
+impl<'q, DB: Database> DriverQuery<'q, DB> {
+    fn new(
+        sql: &'q str,
+        arguments: Option<<DB as HasArguments<'q>>::Arguments>,
+        timeout: Option<Duration>,
+    ) -> Self {
+        Self { sql, arguments, timeout }
+    }
+}
+
+impl<'q, DB: Database> Execute<'q, DB> for DriverQuery<'q, DB> {
+    #[inline]
+    fn sql(&self) -> &str {
+        self.sql
+    }
+
+    #[inline]
+    fn statement(&self) -> Option<&<DB as HasStatement>::Statement> {
+        None
+    }
+
+    #[inline]
+    fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
+        self.arguments.take()
+    }
+
+    #[inline]
+    fn persistent(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+impl Executor for AnyConnection {
+    type Database = Any;
+
+    fn default_timeout(&self) -> Option<Duration> {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => conn.default_timeout(),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => conn.default_timeout(),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => conn.default_timeout(),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => conn.default_timeout(),
+        }
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
+        mut query: E,
+    ) -> ChanStream<'e, Either<AnyQueryResult, AnyRow>>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let arguments = query.take_arguments();
+        let timeout = query.timeout();
+        let sql = query.sql();
+
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => {
+                let sql = placeholder::numbered(sql, "$").into_owned();
+                ChanStream::owned(sql, move |sql, _| {
+                    into_any_stream(Executor::fetch_many(
+                        conn,
+                        DriverQuery::new(sql, arguments.map(PgArguments::from), timeout),
+                    ))
+                })
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => {
+                ChanStream::owned(sql.to_owned(), move |sql, _| {
+                    into_any_stream(Executor::fetch_many(
+                        conn,
+                        DriverQuery::new(sql, arguments.map(MySqlArguments::from), timeout),
+                    ))
+                })
+            },
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => {
+                ChanStream::owned(sql.to_owned(), move |sql, _| {
+                    into_any_stream(Executor::fetch_many(
+                        conn,
+                        DriverQuery::new(sql, arguments.map(SqliteArguments::from), timeout),
+                    ))
+                })
+            },
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => {
+                let arguments = match arguments.map(MssqlArguments::try_from).transpose() {
+                    Ok(arguments) => arguments,
+                    Err(e) => return ChanStream::new(|_| Err(e)),
+                };
+                let sql = placeholder::numbered(sql, "@p").into_owned();
+                ChanStream::owned(sql, move |sql, _| {
+                    into_any_stream(Executor::fetch_many(conn, DriverQuery::new(sql, arguments, timeout)))
+                })
+            }
+        }
+    }
+
+    fn fetch_optional<'q, E: 'q>(
+        &mut self,
+        mut query: E,
+    ) -> Result<Option<AnyRow>, Error>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let arguments = query.take_arguments();
+        let timeout = query.timeout();
+        let sql = query.sql();
+
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => {
+                let sql = placeholder::numbered(sql, "$");
+                Executor::fetch_optional(conn, DriverQuery::new(&sql, arguments.map(PgArguments::from), timeout))?
+                    .map(AnyRow::try_from)
+                    .transpose()
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => {
+                Executor::fetch_optional(conn, DriverQuery::new(sql, arguments.map(MySqlArguments::from), timeout))?
+                    .map(AnyRow::try_from)
+                    .transpose()
+            }
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => {
+                Executor::fetch_optional(conn, DriverQuery::new(sql, arguments.map(SqliteArguments::from), timeout))?
+                    .map(AnyRow::try_from)
+                    .transpose()
+            }
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => {
+                let arguments = arguments.map(MssqlArguments::try_from).transpose()?;
+                let sql = placeholder::numbered(sql, "@p");
+                Executor::fetch_optional(conn, DriverQuery::new(&sql, arguments, timeout))?
+                    .map(AnyRow::try_from)
+                    .transpose()
+            }
+        }
+    }
+
+    fn prepare_with<'q>(
+        &mut self,
+        sql: &'q str,
+        _parameters: &[AnyTypeInfo],
+    ) -> Result<AnyStatement, Error> {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => {
+                let statement = Executor::prepare_with(conn, &placeholder::numbered(sql, "$"), &[])?;
+                map_statement(sql, &statement, |c| AnyColumn::try_from(c), |t| AnyTypeInfo::try_from(t))
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => {
+                let statement = Executor::prepare_with(conn, sql, &[])?;
+                map_statement(sql, &statement, |c| AnyColumn::try_from(c), |t| AnyTypeInfo::try_from(t))
+            }
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => {
+                let statement = Executor::prepare_with(conn, sql, &[])?;
+                map_statement(sql, &statement, |c| Ok(AnyColumn::from(c)), |t| Ok(AnyTypeInfo::from(t)))
+            }
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => {
+                let statement = Executor::prepare_with(conn, &placeholder::numbered(sql, "@p"), &[])?;
+                map_statement(sql, &statement, |c| AnyColumn::try_from(c), |t| AnyTypeInfo::try_from(t))
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    fn describe(&mut self, sql: &str) -> Result<Describe<Any>, Error> {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => {
+                crate::postgres::describe(Executor::describe(conn, &placeholder::numbered(sql, "$"))?)
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => crate::mysql::describe(Executor::describe(conn, sql)?),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => crate::sqlite::describe(Executor::describe(conn, sql)?),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => {
+                crate::mssql::describe(Executor::describe(conn, &placeholder::numbered(sql, "@p"))?)
+            }
+        }
+    }
+}
+
+impl Executor for &mut AnyConnection {
+    type Database = Any;
+
+    fn default_timeout(&self) -> Option<Duration> {
+        AnyConnection::default_timeout(self)
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(&'e mut self, query: E) -> ChanStream<'e, Either<AnyQueryResult, AnyRow>>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        AnyConnection::fetch_many(self, query)
+    }
+
+    fn fetch_optional<'q, E: 'q>(&mut self, query: E) -> Result<Option<AnyRow>, Error>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        AnyConnection::fetch_optional(self, query)
+    }
+
+    fn prepare_with<'q>(
+        &mut self,
+        sql: &'q str,
+        parameters: &'q [AnyTypeInfo],
+    ) -> Result<AnyStatement, Error> {
+        AnyConnection::prepare_with(self, sql, parameters)
+    }
+
+    fn describe(&mut self, sql: &str) -> Result<Describe<Any>, Error> {
+        AnyConnection::describe(self, sql)
+    }
+}
@@ -0,0 +1,72 @@
+use std::str::FromStr;
+use std::time::Duration;
+use cdbc::error::Error;
+use cdbc::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::{Any, AnyConnection, AnyKind};
+
+// dispatch a `MigrateDatabase` function to the database of the url
+macro_rules! delegate_url {
+    ($method:ident($url:ident)) => {
+        match AnyKind::from_str($url)? {
+            #[cfg(feature = "postgres")]
+            AnyKind::Postgres => cdbc_pg::Postgres::$method($url),
+
+            #[cfg(feature = "mysql")]
+            AnyKind::MySql => cdbc_mysql::MySql::$method($url),
+
+            #[cfg(feature = "sqlite")]
+            AnyKind::Sqlite => cdbc_sqlite::Sqlite::$method($url),
+
+            #[cfg(feature = "mssql")]
+            AnyKind::Mssql => cdbc_mssql::Mssql::$method($url),
+        }
+    };
+}
+
+impl MigrateDatabase for Any {
+    fn create_database(url: &str) -> Result<(), Error> {
+        delegate_url!(create_database(url))
+    }
+
+    fn database_exists(url: &str) -> Result<bool, Error> {
+        delegate_url!(database_exists(url))
+    }
+
+    fn drop_database(url: &str) -> Result<(), Error> {
+        delegate_url!(drop_database(url))
+    }
+
+    fn force_drop_database(url: &str) -> Result<(), Error> {
+        delegate_url!(force_drop_database(url))
+    }
+}
+
+impl Migrate for AnyConnection {
+    fn ensure_migrations_table(&mut self) -> Result<(), MigrateError> {
+        delegate_to!(self.ensure_migrations_table())
+    }
+
+    fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError> {
+        delegate_to!(self.dirty_version())
+    }
+
+    fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError> {
+        delegate_to!(self.list_applied_migrations())
+    }
+
+    fn lock(&mut self) -> Result<(), MigrateError> {
+        delegate_to!(self.lock())
+    }
+
+    fn unlock(&mut self) -> Result<(), MigrateError> {
+        delegate_to!(self.unlock())
+    }
+
+    fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        delegate_to!(self.apply(migration))
+    }
+
+    fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        delegate_to!(self.revert(migration))
+    }
+}
@@ -0,0 +1,254 @@
+use cdbc::connection::Connection;
+use cdbc::database::Database;
+use cdbc::describe::Describe;
+use cdbc::error::Error;
+use cdbc::statement::Statement;
+use cdbc::transaction::{Transaction, TransactionCallbacks, TransactionOptions};
+use either::Either;
+use std::time::Duration;
+use crate::{Any, AnyColumn, AnyConnectOptions, AnyKind, AnyStatement, AnyTypeInfo};
+
+#[cfg(feature = "postgres")]
+use cdbc_pg::PgConnection;
+
+#[cfg(feature = "mysql")]
+use cdbc_mysql::MySqlConnection;
+
+#[cfg(feature = "sqlite")]
+use cdbc_sqlite::SqliteConnection;
+
+#[cfg(feature = "mssql")]
+use cdbc_mssql::MssqlConnection;
+
+mod executor;
+
+/// A connection to _any_ SQLx database.
+///
+/// The database driver used is determined by the scheme
+/// of the connection url.
+///
+/// ```text
+/// postgres://postgres@localhost/test
+/// sqlite://a.sqlite
+/// ```
+#[derive(Debug)]
+pub enum AnyConnection {
+    #[cfg(feature = "postgres")]
+    Postgres(PgConnection),
+
+    #[cfg(feature = "mysql")]
+    MySql(MySqlConnection),
+
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteConnection),
+
+    #[cfg(feature = "mssql")]
+    Mssql(MssqlConnection),
+}
+
+// dispatch a method call to the connection of the underlying driver
+macro_rules! delegate_to {
+    ($self:ident.$method:ident($($arg:ident),*)) => {
+        match $self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => conn.$method($($arg),*),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => conn.$method($($arg),*),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => conn.$method($($arg),*),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => conn.$method($($arg),*),
+        }
+    };
+}
+
+mod migrate;
+
+impl AnyConnection {
+    pub fn kind(&self) -> AnyKind {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(_) => AnyKind::Postgres,
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(_) => AnyKind::MySql,
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(_) => AnyKind::Sqlite,
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(_) => AnyKind::Mssql,
+        }
+    }
+}
+
+impl Connection for AnyConnection {
+    type Options = AnyConnectOptions;
+
+    fn close(self) -> Result<(), Error> {
+        delegate_to!(self.close())
+    }
+
+    fn ping(&mut self) -> Result<(), Error> {
+        delegate_to!(self.ping())
+    }
+
+    fn is_broken(&self) -> bool {
+        delegate_to!(self.is_broken())
+    }
+
+    fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        delegate_to!(self.set_default_timeout(timeout))
+    }
+
+    fn begin(&mut self) -> Result<Transaction<'_, Self::Database>, Error>
+        where
+            Self: Sized,
+    {
+        Transaction::begin(self)
+    }
+
+    fn begin_with(&mut self, options: TransactionOptions) -> Result<Transaction<'_, Self::Database>, Error>
+        where
+            Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
+    fn cached_statements_size(&self) -> usize {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => conn.cached_statements_size(),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => conn.cached_statements_size(),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => conn.cached_statements_size(),
+
+            // no cache
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(_) => 0,
+        }
+    }
+
+    fn clear_cached_statements(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => conn.clear_cached_statements(),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => conn.clear_cached_statements(),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => conn.clear_cached_statements(),
+
+            // no cache
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(_) => Ok(()),
+        }
+    }
+
+    #[doc(hidden)]
+    fn flush(&mut self) -> Result<(), Error> {
+        delegate_to!(self.flush())
+    }
+
+    #[doc(hidden)]
+    fn should_flush(&self) -> bool {
+        delegate_to!(self.should_flush())
+    }
+
+    fn transaction_callbacks(&mut self) -> Option<&mut TransactionCallbacks> {
+        delegate_to!(self.transaction_callbacks())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<PgConnection> for AnyConnection {
+    fn from(conn: PgConnection) -> Self {
+        AnyConnection::Postgres(conn)
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl From<MySqlConnection> for AnyConnection {
+    fn from(conn: MySqlConnection) -> Self {
+        AnyConnection::MySql(conn)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<SqliteConnection> for AnyConnection {
+    fn from(conn: SqliteConnection) -> Self {
+        AnyConnection::Sqlite(conn)
+    }
+}
+
+#[cfg(feature = "mssql")]
+impl From<MssqlConnection> for AnyConnection {
+    fn from(conn: MssqlConnection) -> Self {
+        AnyConnection::Mssql(conn)
+    }
+}
+
+/// Converts the description of a query by the underlying driver.
+pub(crate) fn map_describe<DB: Database>(
+    describe: Describe<DB>,
+    column: impl Fn(&DB::Column) -> Result<AnyColumn, Error>,
+    type_info: impl Fn(&DB::TypeInfo) -> Result<AnyTypeInfo, Error>,
+) -> Result<Describe<Any>, Error> {
+    let columns = describe
+        .columns
+        .iter()
+        .map(column)
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let parameters = match describe.parameters {
+        Some(Either::Left(parameters)) => Some(Either::Left(
+            parameters
+                .iter()
+                .map(type_info)
+                .collect::<Result<Vec<_>, Error>>()?,
+        )),
+        Some(Either::Right(count)) => Some(Either::Right(count)),
+        None => None,
+    };
+
+    Ok(Describe {
+        columns,
+        parameters,
+        nullable: describe.nullable,
+    })
+}
+
+/// Converts a statement prepared by the underlying driver, `sql` is the original query text.
+pub(crate) fn map_statement<S: Statement>(
+    sql: &str,
+    statement: &S,
+    column: impl Fn(&<S::Database as Database>::Column) -> Result<AnyColumn, Error>,
+    type_info: impl Fn(&<S::Database as Database>::TypeInfo) -> Result<AnyTypeInfo, Error>,
+) -> Result<AnyStatement, Error> {
+    let columns = statement
+        .columns()
+        .iter()
+        .map(column)
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let parameters = match statement.parameters() {
+        Some(Either::Left(parameters)) => Some(Either::Left(
+            parameters
+                .iter()
+                .map(type_info)
+                .collect::<Result<Vec<_>, Error>>()?,
+        )),
+        Some(Either::Right(count)) => Some(Either::Right(count)),
+        None => None,
+    };
+
+    Ok(AnyStatement::new(sql, parameters, columns))
+}
+
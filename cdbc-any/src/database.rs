@@ -0,0 +1,53 @@
+use cdbc::database::{Database, HasArguments, HasStatement, HasStatementCache, HasValueRef};
+use crate::{
+    AnyArguments, AnyColumn, AnyConnection, AnyQueryResult, AnyRow, AnyStatement,
+    AnyTransactionManager, AnyTypeInfo, AnyValue, AnyValueKind, AnyValueRef,
+};
+
+/// Opaque database driver. Capable of being used in place of any SQLx database driver. The actual
+/// driver used will be selected at runtime, from the connection url.
+#[derive(Debug)]
+pub struct Any;
+
+impl Database for Any {
+    type Connection = AnyConnection;
+
+    type TransactionManager = AnyTransactionManager;
+
+    type Row = AnyRow;
+
+    type QueryResult = AnyQueryResult;
+
+    type Column = AnyColumn;
+
+    type TypeInfo = AnyTypeInfo;
+
+    type Value = AnyValue;
+
+    /// queries are written with `?` and rewritten to the placeholder style of the driver in use
+    fn holder() -> &'static str {
+        "?"
+    }
+}
+
+impl<'r> HasValueRef<'r> for Any {
+    type Database = Any;
+
+    type ValueRef = AnyValueRef<'r>;
+}
+
+impl<'q> HasArguments<'q> for Any {
+    type Database = Any;
+
+    type Arguments = AnyArguments;
+
+    type ArgumentBuffer = Vec<AnyValueKind>;
+}
+
+impl HasStatement for Any {
+    type Database = Any;
+
+    type Statement = AnyStatement;
+}
+
+impl HasStatementCache for Any {}
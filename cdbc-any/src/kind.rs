@@ -0,0 +1,79 @@
+use std::str::FromStr;
+
+use cdbc::error::Error;
+
+/// The database driver behind an [`AnyConnection`](crate::AnyConnection), selected from the
+/// scheme of the connection url.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnyKind {
+    #[cfg(feature = "postgres")]
+    Postgres,
+
+    #[cfg(feature = "mysql")]
+    MySql,
+
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+
+    #[cfg(feature = "mssql")]
+    Mssql,
+}
+
+impl FromStr for AnyKind {
+    type Err = Error;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        match url {
+            #[cfg(feature = "postgres")]
+            _ if url.starts_with("postgres:") || url.starts_with("postgresql:") => {
+                Ok(AnyKind::Postgres)
+            }
+
+            #[cfg(not(feature = "postgres"))]
+            _ if url.starts_with("postgres:") || url.starts_with("postgresql:") => {
+                Err(Error::Configuration("database URL has the scheme of a PostgreSQL database but the `postgres` feature is not enabled".into()))
+            }
+
+            #[cfg(feature = "mysql")]
+            _ if url.starts_with("mysql:") || url.starts_with("mariadb:") => Ok(AnyKind::MySql),
+
+            #[cfg(not(feature = "mysql"))]
+            _ if url.starts_with("mysql:") || url.starts_with("mariadb:") => {
+                Err(Error::Configuration("database URL has the scheme of a MySQL database but the `mysql` feature is not enabled".into()))
+            }
+
+            #[cfg(feature = "sqlite")]
+            _ if url.starts_with("sqlite:") => Ok(AnyKind::Sqlite),
+
+            #[cfg(not(feature = "sqlite"))]
+            _ if url.starts_with("sqlite:") => {
+                Err(Error::Configuration("database URL has the scheme of a SQLite database but the `sqlite` feature is not enabled".into()))
+            }
+
+            #[cfg(feature = "mssql")]
+            _ if url.starts_with("mssql:") || url.starts_with("sqlserver:") => Ok(AnyKind::Mssql),
+
+            #[cfg(not(feature = "mssql"))]
+            _ if url.starts_with("mssql:") || url.starts_with("sqlserver:") => {
+                Err(Error::Configuration("database URL has the scheme of a MSSQL database but the `mssql` feature is not enabled".into()))
+            }
+
+            _ => Err(Error::Configuration(format!("unrecognized database url: {:?}", url).into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::AnyKind;
+
+    #[test]
+    fn test_parse_kind() {
+        assert_eq!(AnyKind::Postgres, "postgres://root@localhost/db".parse().unwrap());
+        assert_eq!(AnyKind::Postgres, "postgresql://root@localhost/db".parse().unwrap());
+        assert_eq!(AnyKind::MySql, "mysql://root@localhost/db".parse().unwrap());
+        assert_eq!(AnyKind::Sqlite, "sqlite://target/sqlite.db".parse().unwrap());
+        assert_eq!(AnyKind::Mssql, "mssql://sa@localhost/db".parse().unwrap());
+        assert!("oracle://root@localhost/db".parse::<AnyKind>().is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! **Any** database driver: the driver is selected at runtime from the scheme of the
+//! connection url (`postgres://`, `mysql://`, `sqlite://`, `mssql://`).
+//!
+//! Each driver is behind a cargo feature of the same name (`postgres`, `mysql`, `sqlite`,
+//! `mssql`), all of them are enabled by default.
+//!
+//! Queries are written with `?` placeholders, which are rewritten to the native style of the
+//! driver in use before they are sent.
+
+#[macro_use]
+pub extern crate cdbc;
+
+use cdbc::executor::Executor;
+
+mod arguments;
+mod column;
+mod connection;
+mod database;
+mod kind;
+mod options;
+mod placeholder;
+mod query_result;
+mod row;
+mod statement;
+mod transaction;
+mod type_info;
+pub mod types;
+mod value;
+
+#[cfg(feature = "postgres")]
+mod postgres;
+
+#[cfg(feature = "mysql")]
+mod mysql;
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "mssql")]
+mod mssql;
+
+pub use arguments::AnyArguments;
+pub use column::AnyColumn;
+pub use connection::AnyConnection;
+pub use database::Any;
+pub use kind::AnyKind;
+pub use options::AnyConnectOptions;
+pub use query_result::AnyQueryResult;
+pub use row::AnyRow;
+pub use statement::AnyStatement;
+pub use transaction::AnyTransactionManager;
+pub use type_info::{AnyTypeInfo, AnyTypeInfoKind};
+pub use value::{AnyValue, AnyValueKind, AnyValueRef};
+
+/// An alias for [`Pool`][cdbc::pool::Pool], specialized for the **Any** driver.
+pub type AnyPool = cdbc::pool::Pool<Any>;
+
+/// An alias for [`PoolOptions`][cdbc::pool::PoolOptions], specialized for the **Any** driver.
+pub type AnyPoolOptions = cdbc::pool::PoolOptions<Any>;
+
+/// An alias for [`Executor<'_, Database = Any>`][Executor].
+pub trait AnyExecutor<'c>: Executor<Database = Any> {}
+impl<'c, T: Executor<Database = Any>> AnyExecutor<'c> for T {}
+
+// NOTE: required due to the lack of lazy normalization
+impl_into_arguments_for_arguments!(AnyArguments);
+impl_column_index_for_row!(AnyRow);
+impl_column_index_for_statement!(AnyStatement);
+impl_into_maybe_pool!(Any, AnyConnection);
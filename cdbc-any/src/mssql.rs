@@ -0,0 +1,152 @@
+use std::convert::TryFrom;
+
+use cdbc::arguments::Arguments;
+use cdbc::describe::Describe;
+use cdbc::error::Error;
+use cdbc::row::Row;
+use cdbc::type_info::TypeInfo;
+use cdbc::value::ValueRef;
+use cdbc_mssql::{Mssql, MssqlArguments, MssqlColumn, MssqlQueryResult, MssqlRow, MssqlTypeInfo};
+use crate::{
+    Any, AnyArguments, AnyColumn, AnyQueryResult, AnyRow, AnyTypeInfo, AnyTypeInfoKind,
+    AnyValueKind,
+};
+
+impl TryFrom<&MssqlTypeInfo> for AnyTypeInfo {
+    type Error = Error;
+
+    fn try_from(ty: &MssqlTypeInfo) -> Result<Self, Error> {
+        Ok(AnyTypeInfo(match ty.name() {
+            "NULL" => AnyTypeInfoKind::Null,
+            "BIT" => AnyTypeInfoKind::Bool,
+            "TINYINT" | "SMALLINT" => AnyTypeInfoKind::SmallInt,
+            "INT" => AnyTypeInfoKind::Integer,
+            "BIGINT" => AnyTypeInfoKind::BigInt,
+            "REAL" => AnyTypeInfoKind::Real,
+            "FLOAT" => AnyTypeInfoKind::Double,
+            "VARCHAR" | "NVARCHAR" | "BIGVARCHAR" | "CHAR" | "BIGCHAR" | "NCHAR" => {
+                AnyTypeInfoKind::Text
+            }
+            _ => {
+                return Err(Error::TypeNotFound {
+                    type_name: ty.name().to_owned(),
+                })
+            }
+        }))
+    }
+}
+
+impl TryFrom<&MssqlColumn> for AnyColumn {
+    type Error = Error;
+
+    fn try_from(column: &MssqlColumn) -> Result<Self, Error> {
+        Ok(AnyColumn {
+            name: column.name.clone(),
+            ordinal: column.ordinal,
+            type_info: AnyTypeInfo::try_from(&column.type_info)?,
+        })
+    }
+}
+
+impl TryFrom<MssqlRow> for AnyRow {
+    type Error = Error;
+
+    fn try_from(row: MssqlRow) -> Result<Self, Error> {
+        let mut columns = Vec::with_capacity(row.len());
+        let mut values = Vec::with_capacity(row.len());
+
+        for (index, column) in row.columns().iter().enumerate() {
+            let any_column = AnyColumn::try_from(column)?;
+            let kind = any_column.type_info.0;
+
+            let value = if row.try_get_raw(index)?.is_null() {
+                AnyValueKind::Null(kind)
+            } else if column.type_info.name() == "TINYINT" {
+                // TINYINT is unsigned in MSSQL
+                AnyValueKind::SmallInt(row.try_get_unchecked::<i8, _>(index)? as u8 as i16)
+            } else {
+                match kind {
+                    AnyTypeInfoKind::Null => AnyValueKind::Null(kind),
+                    AnyTypeInfoKind::Bool => AnyValueKind::Bool(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::SmallInt => AnyValueKind::SmallInt(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Integer => AnyValueKind::Integer(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::BigInt => AnyValueKind::BigInt(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Real => AnyValueKind::Real(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Double => AnyValueKind::Double(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Text => AnyValueKind::Text(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Blob => {
+                        return Err(Error::TypeNotFound {
+                            type_name: column.type_info.name().to_owned(),
+                        })
+                    }
+                }
+            };
+
+            columns.push(any_column);
+            values.push(value);
+        }
+
+        Ok(AnyRow::new(columns, values))
+    }
+}
+
+impl TryFrom<AnyArguments> for MssqlArguments {
+    type Error = Error;
+
+    fn try_from(arguments: AnyArguments) -> Result<Self, Error> {
+        let mut args = MssqlArguments::default();
+        Arguments::reserve(&mut args, arguments.len(), 0);
+
+        for value in arguments.values {
+            match value {
+                AnyValueKind::Null(kind) => match kind {
+                    AnyTypeInfoKind::Bool => Arguments::add(&mut args, Option::<bool>::None),
+                    AnyTypeInfoKind::SmallInt => Arguments::add(&mut args, Option::<i16>::None),
+                    AnyTypeInfoKind::Integer => Arguments::add(&mut args, Option::<i32>::None),
+                    AnyTypeInfoKind::BigInt => Arguments::add(&mut args, Option::<i64>::None),
+                    AnyTypeInfoKind::Real => Arguments::add(&mut args, Option::<f32>::None),
+                    AnyTypeInfoKind::Double => Arguments::add(&mut args, Option::<f64>::None),
+                    AnyTypeInfoKind::Null | AnyTypeInfoKind::Text => {
+                        Arguments::add(&mut args, Option::<String>::None)
+                    }
+                    AnyTypeInfoKind::Blob => {
+                        return Err(Error::TypeNotFound {
+                            type_name: "BLOB".to_owned(),
+                        })
+                    }
+                },
+                AnyValueKind::Bool(v) => Arguments::add(&mut args, v),
+                AnyValueKind::SmallInt(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Integer(v) => Arguments::add(&mut args, v),
+                AnyValueKind::BigInt(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Real(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Double(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Text(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Blob(_) => {
+                    return Err(Error::TypeNotFound {
+                        type_name: "BLOB".to_owned(),
+                    })
+                }
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+impl From<MssqlQueryResult> for AnyQueryResult {
+    fn from(result: MssqlQueryResult) -> Self {
+        AnyQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: None,
+        }
+    }
+}
+
+pub(crate) fn describe(describe: Describe<Mssql>) -> Result<Describe<Any>, Error> {
+    crate::connection::map_describe(
+        describe,
+        |c| AnyColumn::try_from(c),
+        |t| AnyTypeInfo::try_from(t),
+    )
+}
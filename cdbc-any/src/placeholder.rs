@@ -0,0 +1,98 @@
+use std::borrow::Cow;
+
+use cdbc::utils::sql::literal_end;
+
+/// Rewrites the `?` placeholders of a query into numbered ones, e.g. `$1, $2` for PostgreSQL
+/// (`prefix = "$"`) or `@p1, @p2` for MSSQL (`prefix = "@p"`).
+///
+/// a `?` inside a string literal, a quoted identifier, a comment or a Postgres dollar-quoted
+/// string is left untouched, as are the Postgres json operators `?|`, `?&` and a `?` followed
+/// by a string literal(`data ? 'key'`). `??` is written as a single `?`, for a `?` operator
+/// with a bind argument: `data ?? ?` is `data ? $1`.
+pub(crate) fn numbered<'a>(sql: &'a str, prefix: &str) -> Cow<'a, str> {
+    if !sql.contains('?') {
+        return Cow::Borrowed(sql);
+    }
+
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len() + 8);
+    let mut index = 0;
+    let mut last = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if let Some(end) = literal_end(sql, i) {
+            i = end;
+            continue;
+        }
+        if bytes[i] != b'?' {
+            i += 1;
+            continue;
+        }
+        let next = bytes.get(i + 1).copied();
+        match next {
+            Some(b'?') => {
+                out.push_str(&sql[last..=i]);
+                last = i + 2;
+                i += 2;
+            }
+            // `?||`/`?&&` are a placeholder and the `||`/`&&` operator
+            Some(op @ (b'|' | b'&')) if bytes.get(i + 2) != Some(&op) => i += 2,
+            _ if sql[i + 1..].trim_start().starts_with('\'') => i += 1,
+            _ => {
+                out.push_str(&sql[last..i]);
+                index += 1;
+                out.push_str(prefix);
+                out.push_str(&index.to_string());
+                last = i + 1;
+                i += 1;
+            }
+        }
+    }
+    out.push_str(&sql[last..]);
+
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::placeholder::numbered;
+
+    #[test]
+    fn test_numbered() {
+        assert_eq!(
+            numbered("select * from biz where id = ? and name = ?", "$"),
+            "select * from biz where id = $1 and name = $2"
+        );
+        assert_eq!(
+            numbered("insert into biz (id, name) values (?, ?)", "@p"),
+            "insert into biz (id, name) values (@p1, @p2)"
+        );
+    }
+
+    #[test]
+    fn test_numbered_skip_quoted() {
+        assert_eq!(
+            numbered("select '?', \"a?\", 'it''s ?' from biz where id = ?", "$"),
+            "select '?', \"a?\", 'it''s ?' from biz where id = $1"
+        );
+        assert_eq!(
+            numbered("select 1 -- why?\nwhere a = ? /* or ? */ and b = ?", "$"),
+            "select 1 -- why?\nwhere a = $1 /* or ? */ and b = $2"
+        );
+        assert_eq!(
+            numbered("select E'\\' ?', $$ ? $$, $tag$ ?' $tag$ where a = ?", "$"),
+            "select E'\\' ?', $$ ? $$, $tag$ ?' $tag$ where a = $1"
+        );
+        assert_eq!(numbered("select 1", "$"), "select 1");
+    }
+
+    #[test]
+    fn test_numbered_skip_operators() {
+        assert_eq!(
+            numbered("select * from t where data ? 'a' and data ?| array['b'] and data ?& ? and c = ?", "$"),
+            "select * from t where data ? 'a' and data ?| array['b'] and data ?& $1 and c = $2"
+        );
+        assert_eq!(numbered("select ? || 'x', data ?? ? from t", "$"), "select $1 || 'x', data ? $2 from t");
+    }
+}
@@ -0,0 +1,135 @@
+use std::convert::TryFrom;
+
+use cdbc::arguments::Arguments;
+use cdbc::column::Column;
+use cdbc::describe::Describe;
+use cdbc::error::Error;
+use cdbc::row::Row;
+use cdbc::type_info::TypeInfo;
+use cdbc::value::ValueRef;
+use cdbc_pg::{PgArguments, PgColumn, PgQueryResult, PgRow, PgTypeInfo, Postgres};
+use crate::{
+    Any, AnyArguments, AnyColumn, AnyQueryResult, AnyRow, AnyTypeInfo, AnyTypeInfoKind,
+    AnyValueKind,
+};
+
+impl TryFrom<&PgTypeInfo> for AnyTypeInfo {
+    type Error = Error;
+
+    fn try_from(ty: &PgTypeInfo) -> Result<Self, Error> {
+        Ok(AnyTypeInfo(match ty.name() {
+            "VOID" | "UNKNOWN" => AnyTypeInfoKind::Null,
+            "BOOL" => AnyTypeInfoKind::Bool,
+            "INT2" => AnyTypeInfoKind::SmallInt,
+            "INT4" => AnyTypeInfoKind::Integer,
+            "INT8" => AnyTypeInfoKind::BigInt,
+            "FLOAT4" => AnyTypeInfoKind::Real,
+            "FLOAT8" => AnyTypeInfoKind::Double,
+            "TEXT" | "VARCHAR" | "CHAR" | "NAME" => AnyTypeInfoKind::Text,
+            "BYTEA" => AnyTypeInfoKind::Blob,
+            _ => {
+                return Err(Error::TypeNotFound {
+                    type_name: ty.name().to_owned(),
+                })
+            }
+        }))
+    }
+}
+
+impl TryFrom<&PgColumn> for AnyColumn {
+    type Error = Error;
+
+    fn try_from(column: &PgColumn) -> Result<Self, Error> {
+        Ok(AnyColumn {
+            name: column.name().to_owned().into(),
+            ordinal: column.ordinal(),
+            type_info: AnyTypeInfo::try_from(column.type_info())?,
+        })
+    }
+}
+
+impl TryFrom<PgRow> for AnyRow {
+    type Error = Error;
+
+    fn try_from(row: PgRow) -> Result<Self, Error> {
+        let mut columns = Vec::with_capacity(row.len());
+        let mut values = Vec::with_capacity(row.len());
+
+        for (index, column) in row.columns().iter().enumerate() {
+            let column = AnyColumn::try_from(column)?;
+            let kind = column.type_info.0;
+
+            let value = if row.try_get_raw(index)?.is_null() {
+                AnyValueKind::Null(kind)
+            } else {
+                match kind {
+                    AnyTypeInfoKind::Null => AnyValueKind::Null(kind),
+                    AnyTypeInfoKind::Bool => AnyValueKind::Bool(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::SmallInt => AnyValueKind::SmallInt(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Integer => AnyValueKind::Integer(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::BigInt => AnyValueKind::BigInt(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Real => AnyValueKind::Real(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Double => AnyValueKind::Double(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Text => AnyValueKind::Text(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Blob => AnyValueKind::Blob(row.try_get_unchecked(index)?),
+                }
+            };
+
+            columns.push(column);
+            values.push(value);
+        }
+
+        Ok(AnyRow::new(columns, values))
+    }
+}
+
+impl From<AnyArguments> for PgArguments {
+    fn from(arguments: AnyArguments) -> Self {
+        let mut args = PgArguments::default();
+        Arguments::reserve(&mut args, arguments.len(), 0);
+
+        for value in arguments.values {
+            match value {
+                AnyValueKind::Null(kind) => match kind {
+                    AnyTypeInfoKind::Bool => Arguments::add(&mut args, Option::<bool>::None),
+                    AnyTypeInfoKind::SmallInt => Arguments::add(&mut args, Option::<i16>::None),
+                    AnyTypeInfoKind::Integer => Arguments::add(&mut args, Option::<i32>::None),
+                    AnyTypeInfoKind::BigInt => Arguments::add(&mut args, Option::<i64>::None),
+                    AnyTypeInfoKind::Real => Arguments::add(&mut args, Option::<f32>::None),
+                    AnyTypeInfoKind::Double => Arguments::add(&mut args, Option::<f64>::None),
+                    AnyTypeInfoKind::Blob => Arguments::add(&mut args, Option::<Vec<u8>>::None),
+                    AnyTypeInfoKind::Null | AnyTypeInfoKind::Text => {
+                        Arguments::add(&mut args, Option::<String>::None)
+                    }
+                },
+                AnyValueKind::Bool(v) => Arguments::add(&mut args, v),
+                AnyValueKind::SmallInt(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Integer(v) => Arguments::add(&mut args, v),
+                AnyValueKind::BigInt(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Real(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Double(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Text(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Blob(v) => Arguments::add(&mut args, v),
+            }
+        }
+
+        args
+    }
+}
+
+impl From<PgQueryResult> for AnyQueryResult {
+    fn from(result: PgQueryResult) -> Self {
+        AnyQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: None,
+        }
+    }
+}
+
+pub(crate) fn describe(describe: Describe<Postgres>) -> Result<Describe<Any>, Error> {
+    crate::connection::map_describe(
+        describe,
+        |c| AnyColumn::try_from(c),
+        |t| AnyTypeInfo::try_from(t),
+    )
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use cdbc::column::ColumnIndex;
+use cdbc::error::Error;
+use cdbc::row::Row;
+use cdbc::utils::ustr::UStr;
+use cdbc::HashMap;
+use crate::{Any, AnyColumn, AnyValueKind, AnyValueRef};
+
+/// Implementation of [`Row`] for the [`Any`] driver.
+///
+/// The row is fully decoded when it is received from the underlying driver.
+#[derive(Debug)]
+pub struct AnyRow {
+    pub values: Vec<AnyValueKind>,
+    pub columns: Arc<Vec<AnyColumn>>,
+    pub column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl AnyRow {
+    pub(crate) fn new(columns: Vec<AnyColumn>, values: Vec<AnyValueKind>) -> Self {
+        let column_names = columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ordinal))
+            .collect();
+
+        Self {
+            values,
+            columns: Arc::new(columns),
+            column_names: Arc::new(column_names),
+        }
+    }
+}
+
+impl Row for AnyRow {
+    type Database = Any;
+
+    fn columns(&self) -> &[AnyColumn] {
+        &self.columns
+    }
+
+    fn try_get_raw<I>(&self, index: I) -> Result<AnyValueRef<'_>, Error>
+        where
+            I: ColumnIndex<Self>,
+    {
+        let index = index.index(self)?;
+        Ok(AnyValueRef {
+            kind: &self.values[index],
+        })
+    }
+}
+
+impl ColumnIndex<AnyRow> for &'_ str {
+    fn index(&self, row: &AnyRow) -> Result<usize, Error> {
+        row.column_names
+            .get(*self)
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+            .map(|v| *v)
+    }
+}
@@ -0,0 +1,122 @@
+use std::convert::TryFrom;
+
+use cdbc::arguments::Arguments;
+use cdbc::column::Column;
+use cdbc::describe::Describe;
+use cdbc::error::Error;
+use cdbc::row::Row;
+use cdbc::type_info::TypeInfo;
+use cdbc::value::ValueRef;
+use cdbc_sqlite::{Sqlite, SqliteArguments, SqliteColumn, SqliteQueryResult, SqliteRow, SqliteTypeInfo};
+use crate::{
+    Any, AnyArguments, AnyColumn, AnyQueryResult, AnyRow, AnyTypeInfo, AnyTypeInfoKind,
+    AnyValueKind,
+};
+
+impl From<&SqliteTypeInfo> for AnyTypeInfo {
+    fn from(ty: &SqliteTypeInfo) -> Self {
+        AnyTypeInfo(match ty.name() {
+            "BOOLEAN" => AnyTypeInfoKind::Bool,
+            "INTEGER" => AnyTypeInfoKind::BigInt,
+            "REAL" | "NUMERIC" => AnyTypeInfoKind::Double,
+            "BLOB" => AnyTypeInfoKind::Blob,
+            "TEXT" | "DATE" | "TIME" | "DATETIME" => AnyTypeInfoKind::Text,
+            _ => AnyTypeInfoKind::Null,
+        })
+    }
+}
+
+impl From<&SqliteColumn> for AnyColumn {
+    fn from(column: &SqliteColumn) -> Self {
+        AnyColumn {
+            name: column.name.clone(),
+            ordinal: column.ordinal(),
+            type_info: AnyTypeInfo::from(column.type_info()),
+        }
+    }
+}
+
+impl TryFrom<SqliteRow> for AnyRow {
+    type Error = Error;
+
+    fn try_from(row: SqliteRow) -> Result<Self, Error> {
+        let mut columns = Vec::with_capacity(row.len());
+        let mut values = Vec::with_capacity(row.len());
+
+        for (index, column) in row.columns().iter().enumerate() {
+            let column = AnyColumn::from(column);
+            let value = row.try_get_raw(index)?;
+
+            let value = if value.is_null() {
+                AnyValueKind::Null(column.type_info.0)
+            } else {
+                // SQLite is dynamically typed, the storage class of the value wins over the
+                // declared type of the column except for booleans which are stored as integers
+                let kind = match AnyTypeInfo::from(&*value.type_info()).0 {
+                    kind if kind.is_integer() && column.type_info.0 == AnyTypeInfoKind::Bool => {
+                        AnyTypeInfoKind::Bool
+                    }
+                    kind => kind,
+                };
+
+                match kind {
+                    AnyTypeInfoKind::Null => AnyValueKind::Null(column.type_info.0),
+                    AnyTypeInfoKind::Bool => AnyValueKind::Bool(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::SmallInt => AnyValueKind::SmallInt(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Integer => AnyValueKind::Integer(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::BigInt => AnyValueKind::BigInt(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Real => AnyValueKind::Real(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Double => AnyValueKind::Double(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Text => AnyValueKind::Text(row.try_get_unchecked(index)?),
+                    AnyTypeInfoKind::Blob => AnyValueKind::Blob(row.try_get_unchecked(index)?),
+                }
+            };
+
+            columns.push(column);
+            values.push(value);
+        }
+
+        Ok(AnyRow::new(columns, values))
+    }
+}
+
+impl<'q> From<AnyArguments> for SqliteArguments<'q> {
+    fn from(arguments: AnyArguments) -> Self {
+        let mut args = SqliteArguments::default();
+        Arguments::reserve(&mut args, arguments.len(), 0);
+
+        for value in arguments.values {
+            match value {
+                // SQLite does not care about the type of a NULL
+                AnyValueKind::Null(_) => Arguments::add(&mut args, Option::<i32>::None),
+                AnyValueKind::Bool(v) => Arguments::add(&mut args, v),
+                AnyValueKind::SmallInt(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Integer(v) => Arguments::add(&mut args, v),
+                AnyValueKind::BigInt(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Real(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Double(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Text(v) => Arguments::add(&mut args, v),
+                AnyValueKind::Blob(v) => Arguments::add(&mut args, v),
+            }
+        }
+
+        args
+    }
+}
+
+impl From<SqliteQueryResult> for AnyQueryResult {
+    fn from(result: SqliteQueryResult) -> Self {
+        AnyQueryResult {
+            rows_affected: result.rows_affected(),
+            last_insert_id: Some(result.last_insert_rowid()),
+        }
+    }
+}
+
+pub(crate) fn describe(describe: Describe<Sqlite>) -> Result<Describe<Any>, Error> {
+    crate::connection::map_describe(
+        describe,
+        |c| Ok(AnyColumn::from(c)),
+        |t| Ok(AnyTypeInfo::from(t)),
+    )
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use cdbc::column::ColumnIndex;
+use cdbc::error::Error;
+use cdbc::statement::Statement;
+use cdbc::utils::ustr::UStr;
+use cdbc::HashMap;
+use either::Either;
+use crate::{Any, AnyArguments, AnyColumn, AnyTypeInfo};
+
+#[derive(Debug, Clone)]
+pub struct AnyStatement {
+    pub sql: String,
+    pub parameters: Option<Either<Vec<AnyTypeInfo>, usize>>,
+    pub columns: Arc<Vec<AnyColumn>>,
+    pub column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl AnyStatement {
+    pub(crate) fn new(
+        sql: &str,
+        parameters: Option<Either<Vec<AnyTypeInfo>, usize>>,
+        columns: Vec<AnyColumn>,
+    ) -> Self {
+        let column_names = columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ordinal))
+            .collect();
+
+        Self {
+            sql: sql.to_owned(),
+            parameters,
+            columns: Arc::new(columns),
+            column_names: Arc::new(column_names),
+        }
+    }
+}
+
+impl Statement for AnyStatement {
+    type Database = Any;
+
+    fn to_owned(&self) -> AnyStatement {
+        self.clone()
+    }
+
+    fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    fn sql_mut(&mut self) -> &mut String {
+        &mut self.sql
+    }
+
+    fn parameters(&self) -> Option<Either<&[AnyTypeInfo], usize>> {
+        self.parameters.as_ref().map(|p| match p {
+            Either::Left(params) => Either::Left(&**params),
+            Either::Right(count) => Either::Right(*count),
+        })
+    }
+
+    fn columns(&self) -> &[AnyColumn] {
+        &self.columns
+    }
+
+    impl_statement_query!(AnyArguments);
+}
+
+impl ColumnIndex<AnyStatement> for &'_ str {
+    fn index(&self, statement: &AnyStatement) -> Result<usize, Error> {
+        statement
+            .column_names
+            .get(*self)
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+            .map(|v| *v)
+    }
+}
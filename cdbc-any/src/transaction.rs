@@ -0,0 +1,135 @@
+use cdbc::error::Error;
+use cdbc::transaction::{TransactionManager, TransactionOptions};
+use crate::{Any, AnyConnection};
+
+#[cfg(feature = "postgres")]
+use cdbc_pg::PgTransactionManager;
+
+#[cfg(feature = "mysql")]
+use cdbc_mysql::MySqlTransactionManager;
+
+#[cfg(feature = "sqlite")]
+use cdbc_sqlite::SqliteTransactionManager;
+
+#[cfg(feature = "mssql")]
+use cdbc_mssql::MssqlTransactionManager;
+
+/// Implementation of [`TransactionManager`] for the [`Any`] driver, delegating to the
+/// transaction manager of the underlying driver.
+pub struct AnyTransactionManager;
+
+impl TransactionManager for AnyTransactionManager {
+    type Database = Any;
+
+    fn begin_with(conn: &mut AnyConnection, options: &TransactionOptions) -> Result<(), Error> {
+        match conn {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => PgTransactionManager::begin_with(conn, options),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => MySqlTransactionManager::begin_with(conn, options),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => SqliteTransactionManager::begin_with(conn, options),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => MssqlTransactionManager::begin_with(conn, options),
+        }
+    }
+
+    fn commit(conn: &mut AnyConnection) -> Result<(), Error> {
+        match conn {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => PgTransactionManager::commit(conn),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => MySqlTransactionManager::commit(conn),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => SqliteTransactionManager::commit(conn),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => MssqlTransactionManager::commit(conn),
+        }
+    }
+
+    fn rollback(conn: &mut AnyConnection) -> Result<(), Error> {
+        match conn {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => PgTransactionManager::rollback(conn),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => MySqlTransactionManager::rollback(conn),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => SqliteTransactionManager::rollback(conn),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => MssqlTransactionManager::rollback(conn),
+        }
+    }
+
+    fn start_rollback(conn: &mut AnyConnection) {
+        match conn {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => PgTransactionManager::start_rollback(conn),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => MySqlTransactionManager::start_rollback(conn),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => SqliteTransactionManager::start_rollback(conn),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => MssqlTransactionManager::start_rollback(conn),
+        }
+    }
+
+    fn savepoint(conn: &mut AnyConnection, name: &str) -> Result<(), Error> {
+        match conn {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => PgTransactionManager::savepoint(conn, name),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => MySqlTransactionManager::savepoint(conn, name),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => SqliteTransactionManager::savepoint(conn, name),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => MssqlTransactionManager::savepoint(conn, name),
+        }
+    }
+
+    fn release_savepoint(conn: &mut AnyConnection, name: &str) -> Result<(), Error> {
+        match conn {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => PgTransactionManager::release_savepoint(conn, name),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => MySqlTransactionManager::release_savepoint(conn, name),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => SqliteTransactionManager::release_savepoint(conn, name),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => MssqlTransactionManager::release_savepoint(conn, name),
+        }
+    }
+
+    fn rollback_to_savepoint(conn: &mut AnyConnection, name: &str) -> Result<(), Error> {
+        match conn {
+            #[cfg(feature = "postgres")]
+            AnyConnection::Postgres(conn) => PgTransactionManager::rollback_to_savepoint(conn, name),
+
+            #[cfg(feature = "mysql")]
+            AnyConnection::MySql(conn) => MySqlTransactionManager::rollback_to_savepoint(conn, name),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnection::Sqlite(conn) => SqliteTransactionManager::rollback_to_savepoint(conn, name),
+
+            #[cfg(feature = "mssql")]
+            AnyConnection::Mssql(conn) => MssqlTransactionManager::rollback_to_savepoint(conn, name),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt::{self, Display, Formatter};
+
+use cdbc::type_info::TypeInfo;
+
+/// The subset of SQL types that can be moved through the [`Any`](crate::Any) driver.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum AnyTypeInfoKind {
+    Null,
+    Bool,
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    Double,
+    Text,
+    Blob,
+}
+
+/// Type information for the [`Any`](crate::Any) driver.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AnyTypeInfo(pub AnyTypeInfoKind);
+
+impl AnyTypeInfo {
+    pub fn kind(&self) -> AnyTypeInfoKind {
+        self.0
+    }
+}
+
+impl Display for AnyTypeInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl TypeInfo for AnyTypeInfo {
+    fn is_null(&self) -> bool {
+        matches!(self.0, AnyTypeInfoKind::Null)
+    }
+
+    fn name(&self) -> &str {
+        match self.0 {
+            AnyTypeInfoKind::Null => "NULL",
+            AnyTypeInfoKind::Bool => "BOOLEAN",
+            AnyTypeInfoKind::SmallInt => "SMALLINT",
+            AnyTypeInfoKind::Integer => "INTEGER",
+            AnyTypeInfoKind::BigInt => "BIGINT",
+            AnyTypeInfoKind::Real => "REAL",
+            AnyTypeInfoKind::Double => "DOUBLE",
+            AnyTypeInfoKind::Text => "TEXT",
+            AnyTypeInfoKind::Blob => "BLOB",
+        }
+    }
+}
+
+impl AnyTypeInfoKind {
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            AnyTypeInfoKind::SmallInt | AnyTypeInfoKind::Integer | AnyTypeInfoKind::BigInt
+        )
+    }
+}
@@ -0,0 +1,36 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{Any, AnyTypeInfo, AnyTypeInfoKind, AnyValueKind, AnyValueRef};
+
+impl Type<Any> for bool {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::Bool)
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        // SQLite and MSSQL hand booleans back as integers
+        ty.0 == AnyTypeInfoKind::Bool || ty.0.is_integer()
+    }
+}
+
+impl<'q> Encode<'q, Any> for bool {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Bool(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for bool {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.kind() {
+            AnyValueKind::Bool(v) => Ok(*v),
+            AnyValueKind::SmallInt(v) => Ok(*v != 0),
+            AnyValueKind::Integer(v) => Ok(*v != 0),
+            AnyValueKind::BigInt(v) => Ok(*v != 0),
+            other => Err(other.mismatch("bool")),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{Any, AnyTypeInfo, AnyTypeInfoKind, AnyValueKind, AnyValueRef};
+
+impl Type<Any> for [u8] {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::Blob)
+    }
+}
+
+impl<'q> Encode<'q, Any> for &'q [u8] {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Blob(self.to_vec()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for &'r [u8] {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.kind() {
+            AnyValueKind::Blob(v) => Ok(v.as_slice()),
+            other => Err(other.mismatch("&[u8]")),
+        }
+    }
+}
+
+impl Type<Any> for Vec<u8> {
+    fn type_info() -> AnyTypeInfo {
+        <&[u8] as Type<Any>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Any> for Vec<u8> {
+    fn encode(self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Blob(self));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Blob(self.clone()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for Vec<u8> {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        <&[u8] as Decode<Any>>::decode(value).map(ToOwned::to_owned)
+    }
+}
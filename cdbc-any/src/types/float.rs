@@ -0,0 +1,61 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{Any, AnyTypeInfo, AnyTypeInfoKind, AnyValueKind, AnyValueRef};
+
+impl Type<Any> for f32 {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::Real)
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        matches!(ty.0, AnyTypeInfoKind::Real | AnyTypeInfoKind::Double)
+    }
+}
+
+impl<'q> Encode<'q, Any> for f32 {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Real(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for f32 {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.kind() {
+            AnyValueKind::Real(v) => Ok(*v),
+            AnyValueKind::Double(v) => Ok(*v as f32),
+            other => Err(other.mismatch("f32")),
+        }
+    }
+}
+
+impl Type<Any> for f64 {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::Double)
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        matches!(ty.0, AnyTypeInfoKind::Real | AnyTypeInfoKind::Double)
+    }
+}
+
+impl<'q> Encode<'q, Any> for f64 {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Double(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for f64 {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.kind() {
+            AnyValueKind::Real(v) => Ok(*v as f64),
+            AnyValueKind::Double(v) => Ok(*v),
+            other => Err(other.mismatch("f64")),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::convert::TryInto;
+
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{Any, AnyTypeInfo, AnyTypeInfoKind, AnyValueKind, AnyValueRef};
+
+fn int_decode(value: &AnyValueKind, rust_type: &str) -> Result<i64, BoxDynError> {
+    match value {
+        AnyValueKind::SmallInt(v) => Ok(*v as i64),
+        AnyValueKind::Integer(v) => Ok(*v as i64),
+        AnyValueKind::BigInt(v) => Ok(*v),
+        AnyValueKind::Bool(v) => Ok(*v as i64),
+        other => Err(other.mismatch(rust_type)),
+    }
+}
+
+impl Type<Any> for i16 {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::SmallInt)
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        ty.0.is_integer()
+    }
+}
+
+impl<'q> Encode<'q, Any> for i16 {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::SmallInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for i16 {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(int_decode(value.kind(), "i16")?.try_into()?)
+    }
+}
+
+impl Type<Any> for i32 {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::Integer)
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        ty.0.is_integer()
+    }
+}
+
+impl<'q> Encode<'q, Any> for i32 {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Integer(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for i32 {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(int_decode(value.kind(), "i32")?.try_into()?)
+    }
+}
+
+impl Type<Any> for i64 {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::BigInt)
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        ty.0.is_integer()
+    }
+}
+
+impl<'q> Encode<'q, Any> for i64 {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::BigInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for i64 {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        int_decode(value.kind(), "i64")
+    }
+}
@@ -0,0 +1,68 @@
+//! Conversions between Rust and SQL types for the [`Any`](crate::Any) driver.
+//!
+//! Only the types every supported driver can represent are available.
+//!
+//! | Rust type                             | SQL type(s)                                          |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `bool`                                | BOOLEAN                                              |
+//! | `i16`                                 | SMALLINT                                             |
+//! | `i32`                                 | INTEGER                                              |
+//! | `i64`                                 | BIGINT                                               |
+//! | `f32`                                 | REAL                                                 |
+//! | `f64`                                 | DOUBLE                                               |
+//! | `&str`, `String`                      | TEXT                                                 |
+//! | `&[u8]`, `Vec<u8>`                    | BLOB (not supported by MSSQL)                        |
+//!
+//! ### Nullable
+//!
+//! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
+//! a potentially `NULL` value from the database.
+
+mod bool;
+mod bytes;
+mod float;
+mod int;
+mod str;
+
+#[cfg(all(test, feature = "sqlite"))]
+mod test {
+    use cdbc::connection::Connection;
+    use cdbc::decode::Decode;
+    use cdbc::encode::Encode;
+    use cdbc::types::Type;
+    use cdbc::{query, query_scalar};
+    use crate::{Any, AnyConnection};
+
+    /// bind `value`, select it back and decode it as the same type
+    fn round_trip<T>(conn: &mut AnyConnection, value: T)
+        where T: 'static + Send + Clone + PartialEq + std::fmt::Debug + Type<Any> + for<'q> Encode<'q, Any> + for<'r> Decode<'r, Any>
+    {
+        let decoded: T = query_scalar("select ?").bind(value.clone()).fetch_one(&mut *conn).unwrap();
+        assert_eq!(decoded, value);
+        let decoded: Option<T> = query_scalar("select ?").bind(Some(value.clone())).fetch_one(&mut *conn).unwrap();
+        assert_eq!(decoded, Some(value));
+        let decoded: Option<T> = query_scalar("select ?").bind(None::<T>).fetch_one(&mut *conn).unwrap();
+        assert_eq!(decoded, None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut conn = AnyConnection::connect("sqlite::memory:").unwrap();
+        round_trip(&mut conn, true);
+        round_trip(&mut conn, false);
+        round_trip(&mut conn, i16::MIN);
+        round_trip(&mut conn, i32::MAX);
+        round_trip(&mut conn, i64::MIN);
+        round_trip(&mut conn, 1.5f32);
+        round_trip(&mut conn, -2.25f64);
+        round_trip(&mut conn, "tom".to_string());
+        round_trip(&mut conn, vec![0u8, 1, 255]);
+
+        query("create table t(s text, b blob)").execute(&mut conn).unwrap();
+        query("insert into t values (?, ?)").bind("it's").bind(&[1u8, 2][..]).execute(&mut conn).unwrap();
+        let row: (String, Vec<u8>) = cdbc::query_as("select s, b from t").fetch_one(&mut conn).unwrap();
+        assert_eq!(row, ("it's".to_string(), vec![1, 2]));
+        // out of range for i16
+        assert!(query_scalar::<_, i16>("select 70000").fetch_one(&mut conn).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{Any, AnyTypeInfo, AnyTypeInfoKind, AnyValueKind, AnyValueRef};
+
+impl Type<Any> for str {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo(AnyTypeInfoKind::Text)
+    }
+}
+
+impl<'q> Encode<'q, Any> for &'q str {
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Text((*self).to_owned()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for &'r str {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.kind() {
+            AnyValueKind::Text(v) => Ok(v.as_str()),
+            other => Err(other.mismatch("&str")),
+        }
+    }
+}
+
+impl Type<Any> for String {
+    fn type_info() -> AnyTypeInfo {
+        <&str as Type<Any>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Any> for String {
+    fn encode(self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Text(self));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<AnyValueKind>) -> IsNull {
+        buf.push(AnyValueKind::Text(self.clone()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Any> for String {
+    fn decode(value: AnyValueRef<'r>) -> Result<Self, BoxDynError> {
+        <&str as Decode<Any>>::decode(value).map(ToOwned::to_owned)
+    }
+}
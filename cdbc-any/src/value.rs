@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+
+use cdbc::error::{BoxDynError, UnexpectedNullError};
+use cdbc::value::{Value, ValueRef};
+use crate::{Any, AnyTypeInfo, AnyTypeInfoKind};
+
+/// An owned value decoded by the underlying driver.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyValueKind {
+    Null(AnyTypeInfoKind),
+    Bool(bool),
+    SmallInt(i16),
+    Integer(i32),
+    BigInt(i64),
+    Real(f32),
+    Double(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl AnyValueKind {
+    pub fn type_info(&self) -> AnyTypeInfo {
+        AnyTypeInfo(match self {
+            AnyValueKind::Null(kind) => *kind,
+            AnyValueKind::Bool(_) => AnyTypeInfoKind::Bool,
+            AnyValueKind::SmallInt(_) => AnyTypeInfoKind::SmallInt,
+            AnyValueKind::Integer(_) => AnyTypeInfoKind::Integer,
+            AnyValueKind::BigInt(_) => AnyTypeInfoKind::BigInt,
+            AnyValueKind::Real(_) => AnyTypeInfoKind::Real,
+            AnyValueKind::Double(_) => AnyTypeInfoKind::Double,
+            AnyValueKind::Text(_) => AnyTypeInfoKind::Text,
+            AnyValueKind::Blob(_) => AnyTypeInfoKind::Blob,
+        })
+    }
+
+    /// The error returned when this value cannot be decoded as `rust_type`.
+    pub(crate) fn mismatch(&self, rust_type: &str) -> BoxDynError {
+        match self {
+            AnyValueKind::Null(_) => UnexpectedNullError.into(),
+            _ => format!(
+                "mismatched types; Rust type `{}` is not compatible with SQL type `{}`",
+                rust_type,
+                self.type_info()
+            )
+            .into(),
+        }
+    }
+}
+
+/// Implementation of [`Value`] for the [`Any`] driver.
+#[derive(Debug, Clone)]
+pub struct AnyValue {
+    pub(crate) kind: AnyValueKind,
+}
+
+/// Implementation of [`ValueRef`] for the [`Any`] driver.
+#[derive(Debug, Clone)]
+pub struct AnyValueRef<'r> {
+    pub(crate) kind: &'r AnyValueKind,
+}
+
+impl AnyValue {
+    pub fn kind(&self) -> &AnyValueKind {
+        &self.kind
+    }
+}
+
+impl<'r> AnyValueRef<'r> {
+    pub fn kind(&self) -> &'r AnyValueKind {
+        self.kind
+    }
+}
+
+impl Value for AnyValue {
+    type Database = Any;
+
+    fn as_ref(&self) -> AnyValueRef<'_> {
+        AnyValueRef { kind: &self.kind }
+    }
+
+    fn type_info(&self) -> Cow<'_, AnyTypeInfo> {
+        Cow::Owned(self.kind.type_info())
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.kind, AnyValueKind::Null(_))
+    }
+}
+
+impl<'r> ValueRef<'r> for AnyValueRef<'r> {
+    type Database = Any;
+
+    fn to_owned(&self) -> AnyValue {
+        AnyValue {
+            kind: self.kind.clone(),
+        }
+    }
+
+    fn type_info(&self) -> Cow<'_, AnyTypeInfo> {
+        Cow::Owned(self.kind.type_info())
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.kind, AnyValueKind::Null(_))
+    }
+}
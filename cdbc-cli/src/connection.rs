@@ -0,0 +1,293 @@
+use std::str::FromStr;
+use cdbc::column::Column;
+use cdbc::connection::Connection as _;
+use cdbc::database::Database;
+use cdbc::error::Error;
+use cdbc::executor::Executor;
+use cdbc::row::Row;
+use cdbc::type_info::TypeInfo;
+use cdbc::value::ValueRef;
+use cdbc_any::AnyKind;
+use cdbc_mssql::{MssqlConnection, MssqlRow};
+use cdbc_mysql::{MySqlConnection, MySqlRow};
+use cdbc_pg::{PgConnection, PgRow};
+use cdbc_sqlite::{SqliteConnection, SqliteRow};
+use either::Either;
+use crate::output::{Cell, Output, ResultSet};
+
+/// A connection of the driver selected by the scheme of the url.
+pub enum Connection {
+    Postgres(PgConnection),
+    MySql(MySqlConnection),
+    Sqlite(SqliteConnection),
+    Mssql(MssqlConnection),
+}
+
+impl Connection {
+    pub fn connect(url: &str) -> Result<Self, Error> {
+        Ok(match AnyKind::from_str(url)? {
+            AnyKind::Postgres => Connection::Postgres(PgConnection::connect(url)?),
+            AnyKind::MySql => Connection::MySql(MySqlConnection::connect(url)?),
+            AnyKind::Sqlite => Connection::Sqlite(SqliteConnection::connect(url)?),
+            AnyKind::Mssql => Connection::Mssql(MssqlConnection::connect(url)?),
+        })
+    }
+
+    pub fn kind(&self) -> AnyKind {
+        match self {
+            Connection::Postgres(_) => AnyKind::Postgres,
+            Connection::MySql(_) => AnyKind::MySql,
+            Connection::Sqlite(_) => AnyKind::Sqlite,
+            Connection::Mssql(_) => AnyKind::Mssql,
+        }
+    }
+
+    /// run the sql, which may hold several statements, and return the output of each of them.
+    /// mssql batches are separated by `GO` lines like `sqlcmd` does.
+    pub fn run(&mut self, sql: &str) -> Result<Vec<Output>, Error> {
+        match self {
+            Connection::Postgres(conn) => run(conn, sql, |r| r.rows_affected(), pg_cell),
+            Connection::MySql(conn) => run(conn, sql, |r| r.rows_affected(), mysql_cell),
+            Connection::Sqlite(conn) => run(conn, sql, |r| r.rows_affected(), sqlite_cell),
+            Connection::Mssql(conn) => {
+                let mut outputs = vec![];
+                for batch in split_batches(sql) {
+                    outputs.extend(run(conn, &batch, |r| r.rows_affected(), mssql_cell)?);
+                }
+                Ok(outputs)
+            }
+        }
+    }
+
+    pub fn close(self) -> Result<(), Error> {
+        match self {
+            Connection::Postgres(conn) => conn.close(),
+            Connection::MySql(conn) => conn.close(),
+            Connection::Sqlite(conn) => conn.close(),
+            Connection::Mssql(conn) => conn.close(),
+        }
+    }
+}
+
+fn run<C, DB>(
+    conn: &mut C,
+    sql: &str,
+    rows_affected: fn(&DB::QueryResult) -> u64,
+    cell: fn(&DB::Row, usize) -> Result<Cell, Error>,
+) -> Result<Vec<Output>, Error>
+where
+    C: Executor<Database = DB>,
+    DB: Database,
+{
+    let mut outputs = vec![];
+    let mut rows: Option<ResultSet> = None;
+    let mut stream = conn.fetch_many(sql);
+    while let Some(item) = stream.next() {
+        match item? {
+            Either::Left(result) => match rows.take() {
+                Some(rows) => outputs.push(Output::Rows(rows)),
+                None => outputs.push(Output::Affected(rows_affected(&result))),
+            },
+            Either::Right(row) => {
+                let rows = rows.get_or_insert_with(|| ResultSet {
+                    columns: row.columns().iter().map(|c| c.name().to_string()).collect(),
+                    rows: vec![],
+                });
+                let mut cells = Vec::with_capacity(row.len());
+                for index in 0..row.len() {
+                    cells.push(cell(&row, index)?);
+                }
+                rows.rows.push(cells);
+            }
+        }
+    }
+    if let Some(rows) = rows {
+        outputs.push(Output::Rows(rows));
+    }
+    Ok(outputs)
+}
+
+/// postgres and mysql send the values of queries without arguments as text,
+/// the column type only decides how the text is shown.
+fn text_cell(text: Option<&str>, type_name: &str) -> Cell {
+    let text = match text {
+        None => return Cell::Null,
+        Some(v) => v,
+    };
+    match type_name {
+        "BOOL" | "BOOLEAN" => Cell::Bool(text == "t" || text == "1"),
+        "INT2" | "INT4" | "INT8" | "OID" | "FLOAT4" | "FLOAT8" | "TINYINT" | "SMALLINT"
+        | "MEDIUMINT" | "INT" | "BIGINT" | "FLOAT" | "DOUBLE" | "YEAR" => Cell::Number(text.to_string()),
+        "NUMERIC" | "DECIMAL" => Cell::Decimal(text.to_string()),
+        "JSON" | "JSONB" => Cell::Json(text.to_string()),
+        _ => Cell::Text(text.to_string()),
+    }
+}
+
+fn pg_cell(row: &PgRow, index: usize) -> Result<Cell, Error> {
+    let type_name = row.columns()[index].type_info().name().to_string();
+    Ok(text_cell(row.try_get_unchecked::<Option<&str>, _>(index)?, &type_name))
+}
+
+fn mysql_cell(row: &MySqlRow, index: usize) -> Result<Cell, Error> {
+    let type_name = row.columns()[index].type_info().name().to_string();
+    let type_name = type_name.trim_end_matches(" UNSIGNED");
+    Ok(text_cell(row.try_get_unchecked::<Option<&str>, _>(index)?, type_name))
+}
+
+fn sqlite_cell(row: &SqliteRow, index: usize) -> Result<Cell, Error> {
+    // sqlite is dynamically typed, the type of the value itself is used
+    let value = row.try_get_raw(index)?;
+    if value.is_null() {
+        return Ok(Cell::Null);
+    }
+    let type_name = value.type_info().name().to_string();
+    Ok(match &*type_name {
+        "INTEGER" | "REAL" => Cell::Number(row.try_get_unchecked::<String, _>(index)?),
+        "BLOB" => Cell::Text(hex(&row.try_get_unchecked::<Vec<u8>, _>(index)?)),
+        _ => Cell::Text(row.try_get_unchecked::<String, _>(index)?),
+    })
+}
+
+fn mssql_cell(row: &MssqlRow, index: usize) -> Result<Cell, Error> {
+    // mssql always sends binary values, decode with the types the driver supports
+    if row.try_get_raw(index)?.is_null() {
+        return Ok(Cell::Null);
+    }
+    if let Ok(v) = row.try_get::<i64, _>(index) {
+        return Ok(Cell::Number(v.to_string()));
+    }
+    if let Ok(v) = row.try_get::<i32, _>(index) {
+        return Ok(Cell::Number(v.to_string()));
+    }
+    if let Ok(v) = row.try_get::<i16, _>(index) {
+        return Ok(Cell::Number(v.to_string()));
+    }
+    if let Ok(v) = row.try_get::<i8, _>(index) {
+        return Ok(Cell::Number(v.to_string()));
+    }
+    if let Ok(v) = row.try_get::<f64, _>(index) {
+        return Ok(Cell::Number(v.to_string()));
+    }
+    if let Ok(v) = row.try_get::<f32, _>(index) {
+        return Ok(Cell::Number(v.to_string()));
+    }
+    if let Ok(v) = row.try_get::<bool, _>(index) {
+        return Ok(Cell::Bool(v));
+    }
+    if let Ok(v) = row.try_get::<String, _>(index) {
+        return Ok(Cell::Text(v));
+    }
+    let type_name = row.columns()[index].type_info().name().to_string();
+    Ok(Cell::Text(format!("<{}>", type_name)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + bytes.len() * 2);
+    s.push_str("\\x");
+    for b in bytes {
+        s.push_str(&format!("{:02x}", b));
+    }
+    s
+}
+
+/// split a script into the batches between `GO` lines
+pub fn split_batches(sql: &str) -> Vec<String> {
+    let mut batches = vec![];
+    let mut batch = String::new();
+    for line in sql.lines() {
+        if line.trim().eq_ignore_ascii_case("go") {
+            if !batch.trim().is_empty() {
+                batches.push(std::mem::take(&mut batch));
+            }
+        } else if !batch.is_empty() || !line.trim().is_empty() {
+            batch.push_str(line);
+            batch.push('\n');
+        }
+    }
+    if !batch.trim().is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+/// `true` if the input ends with a `;` outside of quotes and comments (or a `GO` line for mssql),
+/// the shell reads more lines until it is.
+pub fn is_complete(sql: &str, kind: AnyKind) -> bool {
+    if kind == AnyKind::Mssql {
+        if let Some(last) = sql.lines().last() {
+            if last.trim().eq_ignore_ascii_case("go") {
+                return true;
+            }
+        }
+    }
+    let mut last = None;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                let mut closed = false;
+                while let Some(n) = chars.next() {
+                    if n == c {
+                        closed = true;
+                        break;
+                    }
+                }
+                if !closed {
+                    return false;
+                }
+                last = Some(c);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                while let Some(n) = chars.next() {
+                    if n == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                let mut closed = false;
+                while let Some(n) = chars.next() {
+                    if prev == '*' && n == '/' {
+                        closed = true;
+                        break;
+                    }
+                    prev = n;
+                }
+                if !closed {
+                    return false;
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => last = Some(c),
+        }
+    }
+    last == Some(';')
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc_any::AnyKind;
+    use crate::connection::{is_complete, split_batches};
+
+    #[test]
+    fn test_is_complete() {
+        assert!(is_complete("select 1;", AnyKind::Postgres));
+        assert!(is_complete("select 1; -- done\n", AnyKind::Postgres));
+        assert!(!is_complete("select 1", AnyKind::Postgres));
+        assert!(!is_complete("select ';", AnyKind::Postgres));
+        assert!(!is_complete("select 1 /* ; */", AnyKind::Postgres));
+        assert!(!is_complete("select 1\n", AnyKind::Mssql));
+        assert!(is_complete("select 1\ngo", AnyKind::Mssql));
+    }
+
+    #[test]
+    fn test_split_batches() {
+        assert_eq!(
+            split_batches("create table a(id int)\nGO\n\ninsert into a values (1)\n go \n"),
+            vec!["create table a(id int)\n".to_string(), "insert into a values (1)\n".to_string()]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! `cdbc`, a database command-line tool built on the cdbc drivers.
+//!
+//! ```text
+//! cdbc [-D <url>] database create|drop|exists
+//! cdbc [-D <url>] run <file.sql>...
+//! cdbc [-D <url>] shell
+//! cdbc [-D <url>] export [--format csv|json] [-o <file>] <sql>
+//! cdbc [-D <url>] prepare [<cargo check args>...]
+//! ```
+//! the url is read from `DATABASE_URL` when `-D` is not given.
+mod connection;
+mod output;
+mod shell;
+
+use std::io::{self, BufRead, Write};
+use cdbc::error::Error;
+use cdbc::migrate::MigrateDatabase;
+use cdbc_any::Any;
+use crate::connection::Connection;
+use crate::output::Output;
+
+const USAGE: &str = "\
+usage: cdbc [-D <url>] <command>
+
+commands:
+  database create              create the database of the url
+  database drop [-y] [--force] drop the database of the url, `--force` terminates other sessions (postgres)
+  database exists              print `true` or `false`, exits with 1 if it doesn't exist
+  run <file.sql>...            run sql scripts, stops at the first error
+  shell                        interactive sql shell, statements are read from stdin
+  export [--format csv|json] [-o <file>] <sql>
+                               write the rows of a query as csv (default) or json
+  prepare [<cargo check args>...]
+                               rebuild the `.cdbc` offline cache of the checked queries of the
+                               packages in the current directory, dropping the stale entries
+
+options:
+  -D, --database-url <url>     the database url, defaults to `DATABASE_URL`
+  -h, --help                   show this help
+
+urls: postgres://.., mysql://.., sqlite://.., mssql://..";
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    let code = match run(&mut args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    };
+    std::process::exit(code);
+}
+
+/// remove `name <value>` from the args
+fn take_option(args: &mut Vec<String>, names: &[&str]) -> Result<Option<String>, Error> {
+    match args.iter().position(|a| names.contains(&a.as_str())) {
+        None => Ok(None),
+        Some(i) if i + 1 < args.len() => {
+            let value = args.remove(i + 1);
+            args.remove(i);
+            Ok(Some(value))
+        }
+        Some(i) => Err(Error::Configuration(format!("{} needs a value", args[i]).into())),
+    }
+}
+
+/// remove the flag from the args, `true` if it was given
+fn take_flag(args: &mut Vec<String>, names: &[&str]) -> bool {
+    let len = args.len();
+    args.retain(|a| !names.contains(&a.as_str()));
+    args.len() != len
+}
+
+fn run(args: &mut Vec<String>) -> Result<i32, Error> {
+    let url = match take_option(args, &["-D", "--database-url"])? {
+        Some(v) => v,
+        None => std::env::var("DATABASE_URL").map_err(|_| {
+            Error::Configuration("pass the database url with `-D` or set `DATABASE_URL`".into())
+        })?,
+    };
+    let command = args.remove(0);
+    match command.as_str() {
+        "database" | "db" => database(&url, args),
+        "run" => {
+            if args.is_empty() {
+                return Err(Error::Configuration("`run` needs at least one sql file".into()));
+            }
+            let mut conn = Connection::connect(&url)?;
+            for file in args.iter() {
+                let sql = std::fs::read_to_string(file)
+                    .map_err(|e| Error::Configuration(format!("read {}: {}", file, e).into()))?;
+                let outputs = conn.run(&sql)?;
+                let affected: u64 = outputs
+                    .iter()
+                    .map(|o| match o {
+                        Output::Affected(n) => *n,
+                        Output::Rows(_) => 0,
+                    })
+                    .sum();
+                println!("{}: {} statements, {} rows affected", file, outputs.len(), affected);
+            }
+            conn.close()?;
+            Ok(0)
+        }
+        "shell" => {
+            let mut conn = Connection::connect(&url)?;
+            shell::run(&mut conn)?;
+            conn.close()?;
+            Ok(0)
+        }
+        "export" => export(&url, args),
+        "prepare" => prepare(&url, args),
+        _ => Err(Error::Configuration(format!("unknown command `{}`, see `cdbc --help`", command).into())),
+    }
+}
+
+fn database(url: &str, args: &mut Vec<String>) -> Result<i32, Error> {
+    let yes = take_flag(args, &["-y", "--yes"]);
+    let force = take_flag(args, &["--force"]);
+    match args.first().map(|a| a.as_str()) {
+        Some("create") => {
+            Any::create_database(url)?;
+            println!("created");
+            Ok(0)
+        }
+        Some("drop") => {
+            if !Any::database_exists(url)? {
+                println!("does not exist");
+                return Ok(0);
+            }
+            if !yes && !confirm(&format!("drop the database of {}? [y/N] ", url))? {
+                return Ok(1);
+            }
+            if force {
+                Any::force_drop_database(url)?;
+            } else {
+                Any::drop_database(url)?;
+            }
+            println!("dropped");
+            Ok(0)
+        }
+        Some("exists") => {
+            let exists = Any::database_exists(url)?;
+            println!("{}", exists);
+            Ok(if exists { 0 } else { 1 })
+        }
+        _ => Err(Error::Configuration("expected `database create|drop|exists`".into())),
+    }
+}
+
+fn confirm(question: &str) -> Result<bool, Error> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn export(url: &str, args: &mut Vec<String>) -> Result<i32, Error> {
+    let format = take_option(args, &["-f", "--format"])?.unwrap_or_else(|| "csv".to_string());
+    let file = take_option(args, &["-o", "--output"])?;
+    let sql = match args.as_slice() {
+        [sql] => sql.clone(),
+        _ => return Err(Error::Configuration("`export` needs exactly one query".into())),
+    };
+    let mut conn = Connection::connect(url)?;
+    let outputs = conn.run(&sql)?;
+    conn.close()?;
+    // the last result set, so setup statements may come before the query
+    let rows = outputs
+        .into_iter()
+        .rev()
+        .find_map(|o| match o {
+            Output::Rows(rows) => Some(rows),
+            Output::Affected(_) => None,
+        })
+        .unwrap_or_default();
+    let text = match format.as_str() {
+        "csv" => rows.to_csv(),
+        "json" => rows.to_json() + "\n",
+        _ => return Err(Error::Configuration(format!("unknown format `{}`, expected csv or json", format).into())),
+    };
+    match file {
+        Some(file) => std::fs::write(&file, text)?,
+        None => io::stdout().write_all(text.as_bytes())?,
+    }
+    Ok(0)
+}
+
+/// Remove the `.cdbc/query-*.json` files of the packages of the current directory and check them
+/// again against the database, so only the queries still in the code are saved.
+fn prepare(url: &str, args: &[String]) -> Result<i32, Error> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = std::process::Command::new(&cargo)
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::Configuration(format!("cargo metadata: {}", String::from_utf8_lossy(&output.stderr).trim()).into()));
+    }
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let mut clean = std::process::Command::new(&cargo);
+    clean.arg("clean");
+    for package in metadata["packages"].as_array().into_iter().flatten() {
+        let (name, manifest) = match (package["name"].as_str(), package["manifest_path"].as_str()) {
+            (Some(name), Some(manifest)) => (name, std::path::Path::new(manifest)),
+            _ => continue,
+        };
+        clean.args(["-p", name]);
+        let dir = manifest.parent().unwrap_or(manifest).join(".cdbc");
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let file = entry.file_name().to_string_lossy().to_string();
+            if file.starts_with("query-") && file.ends_with(".json") {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    // the macros only run again for packages that are built again
+    if !clean.status()?.success() {
+        return Ok(1);
+    }
+    let status = std::process::Command::new(&cargo)
+        .args(["check", "--all-targets"])
+        .args(args.iter())
+        .env("DATABASE_URL", url)
+        .env("CDBC_OFFLINE", "false")
+        .status()?;
+    Ok(status.code().unwrap_or(1))
+}
@@ -0,0 +1,219 @@
+use std::fmt::Write;
+use serde_json::Value;
+
+/// A value of a result set, already converted to text by the driver.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Null,
+    Bool(bool),
+    /// an integer or a float
+    Number(String),
+    /// `NUMERIC`/`DECIMAL`, a string in json to keep every digit(`1.50` stays `"1.50"`)
+    Decimal(String),
+    Text(String),
+    Json(String),
+}
+
+impl Cell {
+    pub fn as_text(&self) -> &str {
+        match self {
+            Cell::Null => "NULL",
+            Cell::Bool(true) => "true",
+            Cell::Bool(false) => "false",
+            Cell::Number(v) | Cell::Decimal(v) | Cell::Text(v) | Cell::Json(v) => v,
+        }
+    }
+
+    /// the json text of the value, nothing is parsed into an `f64` on the way
+    pub fn to_json(&self) -> String {
+        match self {
+            Cell::Null => "null".to_string(),
+            Cell::Bool(v) => v.to_string(),
+            Cell::Number(v) => match number(v) {
+                Some(v) => v.to_string(),
+                // `NaN`, `Infinity` or an integer out of the range of i64 and u64
+                None => Value::String(v.clone()).to_string(),
+            },
+            // written as sent, only checked to be json, a number in it keeps its digits
+            Cell::Json(v) if serde_json::from_str::<Value>(v).is_ok() => v.clone(),
+            Cell::Decimal(v) | Cell::Text(v) | Cell::Json(v) => Value::String(v.clone()).to_string(),
+        }
+    }
+}
+
+/// a json number of the text of an integer or a float, `None` if json can't hold it exactly
+fn number(text: &str) -> Option<serde_json::Number> {
+    if !text.contains(|c| c == '.' || c == 'e' || c == 'E') {
+        return match text.parse::<i64>() {
+            Ok(v) => Some(v.into()),
+            Err(_) => text.parse::<u64>().ok().map(Into::into),
+        };
+    }
+    // the text of a float column, read back exactly
+    text.parse::<f64>().ok().and_then(serde_json::Number::from_f64)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResultSet {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+/// The output of one statement.
+#[derive(Debug, Clone)]
+pub enum Output {
+    Rows(ResultSet),
+    Affected(u64),
+}
+
+impl ResultSet {
+    /// aligned like `psql`, numbers to the right
+    pub fn to_table(&self) -> String {
+        let mut widths: Vec<usize> = self.columns.iter().map(|c| c.chars().count()).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.as_text().chars().count());
+            }
+        }
+        let line = {
+            let mut s = String::from("+");
+            for w in &widths {
+                s.push_str(&"-".repeat(w + 2));
+                s.push('+');
+            }
+            s.push('\n');
+            s
+        };
+        let mut out = line.clone();
+        out.push('|');
+        for (name, w) in self.columns.iter().zip(&widths) {
+            let _ = write!(out, " {}{} |", name, " ".repeat(w - name.chars().count()));
+        }
+        out.push('\n');
+        out.push_str(&line);
+        for row in &self.rows {
+            out.push('|');
+            for (cell, w) in row.iter().zip(&widths) {
+                let text = cell.as_text();
+                let pad = " ".repeat(w - text.chars().count());
+                match cell {
+                    Cell::Number(_) | Cell::Decimal(_) => {
+                        let _ = write!(out, " {}{} |", pad, text);
+                    }
+                    _ => {
+                        let _ = write!(out, " {}{} |", text, pad);
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        if !self.rows.is_empty() {
+            out.push_str(&line);
+        }
+        let _ = writeln!(out, "({} rows)", self.rows.len());
+        out
+    }
+
+    /// RFC 4180, a header line and `NULL` as an empty field
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        write_csv_line(&mut out, self.columns.iter().map(|c| c.as_str()));
+        for row in &self.rows {
+            write_csv_line(
+                &mut out,
+                row.iter().map(|c| match c {
+                    Cell::Null => "",
+                    _ => c.as_text(),
+                }),
+            );
+        }
+        out
+    }
+
+    /// an array with one object per row, the keys keep the order of the columns
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push('{');
+            for (j, (name, cell)) in self.columns.iter().zip(row).enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{}:{}", Value::String(name.clone()), cell.to_json());
+            }
+            out.push('}');
+        }
+        out.push(']');
+        out
+    }
+}
+
+fn write_csv_line<'a, I: Iterator<Item = &'a str>>(out: &mut String, fields: I) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod test {
+    use crate::output::{Cell, ResultSet};
+
+    fn result_set() -> ResultSet {
+        ResultSet {
+            columns: vec!["id".to_string(), "name".to_string(), "ok".to_string()],
+            rows: vec![
+                vec![Cell::Number("1".into()), Cell::Text("a, \"b\"".into()), Cell::Bool(true)],
+                vec![Cell::Number("20".into()), Cell::Null, Cell::Bool(false)],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_cell_to_json() {
+        assert_eq!(Cell::Number("-7".into()).to_json(), "-7");
+        assert_eq!(Cell::Number("18446744073709551615".into()).to_json(), "18446744073709551615");
+        assert_eq!(Cell::Number("18446744073709551616".into()).to_json(), "\"18446744073709551616\"");
+        assert_eq!(Cell::Number("0.1".into()).to_json(), "0.1");
+        assert_eq!(Cell::Number("NaN".into()).to_json(), "\"NaN\"");
+        assert_eq!(Cell::Decimal("1.50".into()).to_json(), "\"1.50\"");
+        assert_eq!(Cell::Decimal("12345678901234567890.123456789".into()).to_json(), "\"12345678901234567890.123456789\"");
+        assert_eq!(Cell::Json(r#"{"a": 12345678901234567890123}"#.into()).to_json(), r#"{"a": 12345678901234567890123}"#);
+        assert_eq!(Cell::Json("not json".into()).to_json(), "\"not json\"");
+    }
+
+    #[test]
+    fn test_to_table() {
+        assert_eq!(
+            result_set().to_table(),
+            "+----+--------+-------+\n\
+             | id | name   | ok    |\n\
+             +----+--------+-------+\n\
+             |  1 | a, \"b\" | true  |\n\
+             | 20 | NULL   | false |\n\
+             +----+--------+-------+\n\
+             (2 rows)\n"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_json() {
+        assert_eq!(result_set().to_csv(), "id,name,ok\r\n1,\"a, \"\"b\"\"\",true\r\n20,,false\r\n");
+        assert_eq!(
+            result_set().to_json(),
+            r#"[{"id":1,"name":"a, \"b\"","ok":true},{"id":20,"name":null,"ok":false}]"#
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use cdbc::error::Error;
+use crate::connection::{is_complete, Connection};
+use crate::output::Output;
+
+const HELP: &str = "\
+end a statement with `;` (or a `GO` line for mssql) to run it
+  \\q, quit, exit   leave the shell
+  \\c               clear the input
+  \\?, help         show this help";
+
+/// read statements from stdin and print their results until `\q` or the end of the input
+pub fn run(conn: &mut Connection) -> Result<(), Error> {
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        println!("connected to {:?}, `\\?` for help", conn.kind());
+    }
+    let mut buf = String::new();
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("{}", if buf.is_empty() { "cdbc> " } else { "   -> " });
+            io::stdout().flush()?;
+        }
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        if buf.is_empty() {
+            match line.trim() {
+                "" => continue,
+                "\\q" | "quit" | "exit" => return Ok(()),
+                "\\?" | "help" => {
+                    println!("{}", HELP);
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        if line.trim() == "\\c" {
+            buf.clear();
+            continue;
+        }
+        buf.push_str(&line);
+        buf.push('\n');
+        if is_complete(&buf, conn.kind()) {
+            execute(conn, &std::mem::take(&mut buf));
+        }
+    }
+    if !buf.trim().is_empty() {
+        execute(conn, &buf);
+    }
+    Ok(())
+}
+
+/// errors are printed, the shell keeps going
+fn execute(conn: &mut Connection, sql: &str) {
+    match conn.run(sql) {
+        Ok(outputs) => print_outputs(&outputs),
+        Err(e) => eprintln!("error: {}", e),
+    }
+}
+
+pub fn print_outputs(outputs: &[Output]) {
+    for output in outputs {
+        match output {
+            Output::Rows(rows) => print!("{}", rows.to_table()),
+            Output::Affected(n) => println!("OK, {} rows affected", n),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! `cdbc` against a sqlite database file.
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+struct Db {
+    dir: PathBuf,
+    url: String,
+}
+
+impl Db {
+    fn new(name: &str) -> Db {
+        let dir = std::env::temp_dir().join(format!("cdbc-cli-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = format!("sqlite://{}", dir.join("test.db").display());
+        Db { dir, url }
+    }
+
+    fn cdbc(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_cdbc"))
+            .args(["-D", &self.url])
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    /// the stdout of a successful run
+    fn ok(&self, args: &[&str]) -> String {
+        let output = self.cdbc(args);
+        assert!(output.status.success(), "{:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    }
+}
+
+impl Drop for Db {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn test_database_run_export() {
+    let db = Db::new("run");
+    assert_eq!(db.ok(&["database", "create"]), "created\n");
+    assert_eq!(db.ok(&["database", "exists"]), "true\n");
+
+    let script = db.dir.join("init.sql");
+    std::fs::write(
+        &script,
+        "create table item(id integer, name text, price real, big integer);
+         insert into item values (1, 'a, \"b\"', 1.5, 9223372036854775807), (2, null, 0.1, -1);",
+    )
+    .unwrap();
+    let script = script.to_str().unwrap();
+    assert_eq!(db.ok(&["run", script]), format!("{}: 2 statements, 2 rows affected\n", script));
+
+    let sql = "select id, name, price, big from item order by id";
+    assert_eq!(
+        db.ok(&["export", sql]),
+        "id,name,price,big\r\n1,\"a, \"\"b\"\"\",1.5,9223372036854775807\r\n2,,0.1,-1\r\n"
+    );
+    let json = db.dir.join("out.json");
+    assert_eq!(db.ok(&["export", "--format", "json", "-o", json.to_str().unwrap(), sql]), "");
+    assert_eq!(
+        std::fs::read_to_string(&json).unwrap(),
+        "[{\"id\":1,\"name\":\"a, \\\"b\\\"\",\"price\":1.5,\"big\":9223372036854775807},\
+         {\"id\":2,\"name\":null,\"price\":0.1,\"big\":-1}]\n"
+    );
+
+    // the script stops at the first error
+    std::fs::write(db.dir.join("bad.sql"), "insert into item(id) values (3); insert into missing values (1);").unwrap();
+    let output = db.cdbc(&["run", db.dir.join("bad.sql").to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no such table: missing"));
+
+    assert_eq!(db.ok(&["database", "drop", "-y"]), "dropped\n");
+    assert_eq!(db.cdbc(&["database", "exists"]).status.code(), Some(1));
+}
@@ -1,15 +1,106 @@
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 use quote::ToTokens;
-use syn::DeriveInput;
+use syn::{DeriveInput, Lit, Meta, NestedMeta, Type};
+use crate::driver::Driver;
 
-pub(crate) fn impl_crud(input: crate::proc_macro::TokenStream, db_type: Vec<Vec<TokenStream>>) -> crate::proc_macro::TokenStream {
-    let driver_token = gen_driver_token(input.to_string());
-    let ast: DeriveInput = syn::parse(input).unwrap();
+/// a field of the struct and the column it is stored in
+struct Column {
+    ident: Ident,
+    ty: Type,
+    name: String,
+    id: bool,
+}
+
+/// the `table = ".."` of `#[crud(..)]`, `db = ".."` is read by the caller
+pub(crate) fn parse_table(args: &[NestedMeta]) -> syn::Result<Option<String>> {
+    let mut table = None;
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("table") => match &nv.lit {
+                Lit::Str(s) if !s.value().is_empty() => table = Some(s.value()),
+                other => return Err(syn::Error::new_spanned(other, "expected a table name")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("db") => {}
+            other => return Err(syn::Error::new_spanned(other, "expected `db = \"..\"` or `table = \"..\"`")),
+        }
+    }
+    Ok(table)
+}
+
+/// the columns of the named fields, taking the `#[crud(id)]`, `#[crud(skip)]` and
+/// `#[crud(column = "..")]` attributes off the fields
+fn parse_columns(ast: &mut DeriveInput) -> syn::Result<Vec<Column>> {
+    let fields = match &mut ast.data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) => fields,
+        _ => return Err(syn::Error::new_spanned(&ast.ident, "#[crud] only supports structs with named fields")),
+    };
+    let mut columns = vec![];
+    for field in fields.named.iter_mut() {
+        let ident = field.ident.clone().unwrap();
+        let mut column = Column {
+            name: ident.to_string().trim_start_matches("r#").to_string(),
+            ident,
+            ty: field.ty.clone(),
+            id: false,
+        };
+        let mut skip = false;
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("crud")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                other => return Err(syn::Error::new_spanned(other, "expected `#[crud(..)]`")),
+            };
+            for meta in list.nested {
+                match meta {
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("id") => column.id = true,
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => skip = true,
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("column") => match &nv.lit {
+                        Lit::Str(s) if !s.value().is_empty() => column.name = s.value(),
+                        other => return Err(syn::Error::new_spanned(other, "expected a column name")),
+                    },
+                    other => return Err(syn::Error::new_spanned(other, "expected `id`, `skip` or `column = \"..\"`")),
+                }
+            }
+        }
+        field.attrs.retain(|a| !a.path.is_ident("crud"));
+        if skip && column.id {
+            return Err(syn::Error::new_spanned(&field, "a `#[crud(id)]` field can't be skipped"));
+        }
+        if !skip {
+            columns.push(column);
+        }
+    }
+    Ok(columns)
+}
+
+/// the `T` of `Option<T>`, the fields of a crud struct are options
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(p) => p.path.segments.last()?,
+        _ => return None,
+    };
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if segment.ident == "Option" => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// arg_drivers: the drivers of `#[crud(db = "..")]`, handed on to the generated `#[derive(cdbc::Scan)]`
+/// drivers: the drivers the `CRUD` impls are generated for
+pub(crate) fn impl_crud(mut ast: DeriveInput, arg_drivers: &[Driver], drivers: &[Driver], table: Option<String>) -> syn::Result<TokenStream> {
+    let columns = parse_columns(&mut ast)?;
+    let driver_token = gen_driver_token(ast.to_token_stream().to_string());
+    // after the derives, a helper attribute must not come before the derive declaring it
+    for driver in arg_drivers {
+        let db = driver.name();
+        ast.attrs.push(syn::parse_quote!(#[cdbc(db = #db)]));
+    }
     let name = &ast.ident;
-    let field_idents = gen_fields(&ast.data);
-    let table_name = to_snake_name(&name.to_string());
-    let columns = gen_columns(&field_idents);
+    let table_name = table.unwrap_or_else(|| to_snake_name(&name.to_string()));
+    let column_names = columns.iter().map(|c| &c.name);
     let mut stream = quote! {
         #driver_token
         #ast
@@ -19,17 +110,74 @@ pub(crate) fn impl_crud(input: crate::proc_macro::TokenStream, db_type: Vec<Vec<
             }
 
             fn columns() -> &'static [&'static str] {
-                &[#columns]
+                &[#(#column_names),*]
+            }
+        }
+    };
+    let vis = &ast.vis;
+    let fields = columns.iter().enumerate().map(|(i, column)| {
+        let ident = &column.ident;
+        let ty = option_inner(&column.ty).unwrap_or(&column.ty);
+        quote! {
+            #vis fn #ident() -> cdbc::crud::Field<#name, #ty> {
+                cdbc::crud::Field::new(#i)
             }
         }
+    });
+    // associated fns named like the fields, a method of the same name on the struct(e.g. a
+    // getter `fn name(&self)`) is a duplicate definition, rename it or the field with `column`
+    stream = quote! {
+        #stream
+        /// the fields of the conditions of `CRUD`
+        impl #name {
+            #(#fields)*
+        }
     };
-    for types in db_type {
-        for t in types {
-            let mut crud = do_impl_curd(name, &t, &field_idents);
-            stream = quote! {#stream #crud};
+    let ids: Vec<&Column> = columns.iter().filter(|c| c.id).collect();
+    if !ids.is_empty() {
+        let mut id_types = vec![];
+        for id in &ids {
+            match option_inner(&id.ty) {
+                Some(ty) => id_types.push(ty),
+                None => return Err(syn::Error::new_spanned(&id.ty, "a `#[crud(id)]` field must be an `Option`")),
+            }
+        }
+        let id_type = match id_types.as_slice() {
+            [ty] => quote!(#ty),
+            types => quote!((#(#types),*)),
+        };
+        let id_names = ids.iter().map(|c| &c.name);
+        stream = quote! {
+            #stream
+            impl cdbc::crud::TableId for #name {
+                type Id = #id_type;
+
+                fn id_columns() -> &'static [&'static str] {
+                    &[#(#id_names),*]
+                }
+            }
+        };
+    }
+    for driver in drivers {
+        let (crud, by_id) = do_impl_curd(name, *driver, &table_name, &columns);
+        for t in driver.executors() {
+            stream = quote! {
+                #stream
+                impl cdbc::crud::CRUD<#name> for #t {
+                    #crud
+                }
+            };
+            if let Some(by_id) = &by_id {
+                stream = quote! {
+                    #stream
+                    impl cdbc::crud::CRUDById<#name> for #t {
+                        #by_id
+                    }
+                };
+            }
         }
     }
-    stream.into()
+    Ok(stream)
 }
 
 fn to_snake_name(name: &str) -> String {
@@ -51,34 +199,6 @@ fn to_snake_name(name: &str) -> String {
     return new_name;
 }
 
-fn gen_columns(fields: &Vec<Ident>) -> TokenStream {
-    let mut s = quote! {};
-    for x in fields {
-        s = quote! { #s stringify!(#x),}
-    }
-    s
-}
-
-fn gen_fields(data: &syn::Data) -> Vec<Ident> {
-    let mut fields: Vec<Ident> = vec![];
-    match &data {
-        syn::Data::Struct(s) => {
-            for field in &s.fields {
-                match &field.ident {
-                    None => {}
-                    Some(v) => {
-                        fields.push(v.clone());
-                    }
-                }
-            }
-        }
-        _ => {
-            panic!("[rbatis] #[crud_table] only support struct for crud_table's macro!")
-        }
-    }
-    fields
-}
-
 fn gen_driver_token(mut token_string: String) -> proc_macro2::TokenStream {
     if token_string.contains("#[derive(") {
         token_string = (&token_string[token_string.find("#[derive(").unwrap() as usize..token_string.len()]).to_string();
@@ -95,133 +215,377 @@ fn gen_driver_token(mut token_string: String) -> proc_macro2::TokenStream {
     return driver_token;
 }
 
-///t:cdbc_sqlite::SqlitePool
-/// name:table
-fn do_impl_curd(name: &Ident, t: &TokenStream, fields: &Vec<Ident>) -> TokenStream {
-    let mut log_format="arg=> ".to_string();
-    let mut log_info = quote!{};
-    let mut bind_arg = quote!{};
-    let mut bind_arg_if_some = quote!{};
-    let mut idx=0;
-    for item in fields{
-        if idx==0{
-            bind_arg = quote!{q=q};
-        }
-        log_info=quote!{#log_info arg.#item,};
+/// the sql the methods of `CRUD` start with, the identifiers quoted for the driver
+struct Sql {
+    columns: Vec<String>,
+    insert: String,
+    update: String,
+    select: String,
+    delete: String,
+    count: String,
+    /// the sql of `exists` up to the where clause and after it
+    exists: (String, String),
+    /// the ` order by` of the key columns, the default order of `find_page`
+    order: Option<String>,
+    /// `"id" = ` of every key column, joined by ` and ` when pushed
+    ids: Vec<String>,
+}
+
+fn gen_sql(driver: Driver, table: &str, columns: &[Column]) -> Sql {
+    let table = driver.quote(table);
+    let names = columns.iter().map(|c| driver.quote(&c.name)).collect::<Vec<_>>();
+    let selects = columns
+        .iter()
+        .map(|c| {
+            let field = c.ident.to_string().trim_start_matches("r#").to_string();
+            if field == c.name {
+                driver.quote(&c.name)
+            } else {
+                format!("{} as {}", driver.quote(&c.name), driver.quote(&field))
+            }
+        })
+        .collect::<Vec<_>>();
+    let keys = columns.iter().filter(|c| c.id).map(|c| driver.quote(&c.name)).collect::<Vec<_>>();
+    Sql {
+        insert: format!("insert into {} ({}) ", table, names.join(",")),
+        columns: names,
+        update: format!("update {} set ", table),
+        select: format!("select {} from {}", selects.join(","), table),
+        delete: format!("delete from {}", table),
+        count: match driver {
+            // `count(*)` is an `int` on MSSQL
+            Driver::Mssql => format!("select count_big(*) from {}", table),
+            _ => format!("select count(*) from {}", table),
+        },
+        exists: match driver {
+            Driver::Mssql => (format!("select top 1 1 from {}", table), String::new()),
+            _ => (format!("select 1 from {}", table), " limit 1".to_string()),
+        },
+        order: (!keys.is_empty()).then(|| format!(" order by {}", keys.join(","))),
+        ids: keys.iter().map(|key| format!("{} = ", key)).collect(),
+    }
+}
+
+/// the methods of `CRUD<name>` for the executors of `driver`, and of `CRUDById<name>` if the
+/// table has `#[crud(id)]` fields
+fn do_impl_curd(name: &Ident, driver: Driver, table: &str, columns: &[Column]) -> (TokenStream, Option<TokenStream>) {
+    let db = driver.database();
+    let Sql { columns: quoted, insert, update, select, delete, count, exists: (exists, exists_limit), order, ids } = gen_sql(driver, table, columns);
+
+    let mut log_format = "arg=> ".to_string();
+    let mut log_info = quote! {};
+    let mut bind_arg = quote! {};
+    for item in columns.iter().map(|c| &c.ident) {
+        log_info = quote! {#log_info arg.#item,};
         log_format.push_str("{:?},");
-        bind_arg=quote!{#bind_arg.bind(arg.#item)};
+        bind_arg = quote! {#bind_arg b.push_bind(arg.#item);};
+    }
 
-        let item_name = item.to_string();
-        bind_arg_if_some = quote!{
-                #bind_arg_if_some
+    // the `"column" = ?` of the fields that are `Some`, key fields too unless `skip_ids`
+    let bind_sets = |skip_ids: bool| {
+        let mut sets = quote! {};
+        for column in columns.iter().filter(|c| !(skip_ids && c.id)) {
+            let item = &column.ident;
+            let set = format!("{} = ", driver.quote(&column.name));
+            sets = quote! {
+                #sets
                 if arg.#item.is_some() {
-                    sets.push_str(#item_name);
-                    sets.push_str(" = ");
-                    sets.push_str(&#name::p("?", &mut arg_idx));
-                    sets.push_str(",");
-                    q = q.bind(arg.#item);
+                    if sets != 0 {
+                        q.push(",");
+                    }
+                    q.push(#set);
+                    q.push_bind(arg.#item);
+                    sets += 1;
                 }
-        };
-
-        idx+=1;
-    }
+            };
+        }
+        sets
+    };
+    let bind_sets_all = bind_sets(false);
 
-    let mut data = quote!(
-      impl cdbc::crud::CRUD<#name> for #t {
+    // ` where ..` of the `Condition`, the values bound as the type of their field
+    let arms = columns.iter().enumerate().map(|(i, column)| {
+        let ty = option_inner(&column.ty).unwrap_or(&column.ty);
+        quote! {
+            #i => {
+                q.push_bind(*value.downcast::<#ty>().expect("a value of the type of the field"));
+            }
+        }
+    });
+    let push_where = quote! {
+        r#where.push_where(&mut q, &[#(#quoted),*], |q, column, value| match column {
+            #(#arms)*
+            _ => unreachable!(),
+        });
+    };
+    // ` order by ..` of the `Condition`, else `default`
+    let push_order = |default: &str| quote! {
+        r#where.push_order(&mut q, &[#(#quoted),*], #default);
+    };
+    let push_order_none = push_order("");
+    // a page needs an order, the key of the table if the condition has none
+    let (check_order, push_order_page) = match &order {
+        Some(order) => (quote! {}, push_order(order)),
+        None => {
+            let no_order = format!("CRUD::find_page of table {}: the table has no `#[crud(id)]` to order the pages by, order them with `Condition::order_by`", table);
+            let check = quote! {
+                if !r#where.is_ordered() {
+                    return Err(cdbc::Error::InvalidArgument(#no_order.to_string()));
+                }
+            };
+            (check, push_order_none.clone())
+        }
+    };
+    // `limit` rows after the first `offset`
+    let push_limit = |limit: TokenStream, offset: TokenStream| match driver {
+        Driver::Mssql => quote! {
+            q.push(format_args!(" offset {} rows fetch next {} rows only", #offset, #limit));
+        },
+        _ => quote! {
+            q.push(format_args!(" limit {} offset {}", #limit, #offset));
+        },
+    };
+    let push_page = push_limit(quote!(page.page_size), quote!(page.offset()));
+    let push_after = push_limit(quote!(limit), quote!(0));
+    let count_total = quote! {
+        let mut q = cdbc::QueryBuilder::<#db>::new(#count);
+        #push_where
+        log::info!("sql=> {}",q.sql());
+        let total: i64 = self.fetch_one(q.build())?.try_get(0)?;
+    };
+    let data = quote! {
         fn inserts(&mut self, arg: Vec<#name>) -> cdbc::Result<(String,u64)> where #name: Sized {
-            use cdbc::{Either, Executor, query};
-            use cdbc::scan::Scan;
+            use cdbc::Executor;
             if arg.len() == 0 {
                 return Ok((String::new(),0));
             }
-            let mut arg_idx = 1;
-            let mut sql = format!("insert into {} ({}) values ", #name::table(), #name::columns_str());
-            let mut value_num = 0;
-            for x in &arg {
-                if value_num != 0 {
-                    sql.push_str(",");
-                }
-                sql.push_str("(");
-                sql.push_str(&#name::values_str("?", &mut arg_idx));
-                sql.push_str(")");
-                value_num += 1;
-            }
-            log::info!("sql=> {}",sql);
-            let mut q = query(sql.as_str());
-            for arg in arg {
+            let mut q = cdbc::QueryBuilder::<#db>::new(#insert);
+            q.push_values(arg, |mut b, arg| {
                 log::info!(#log_format,#log_info);
-                #bind_arg;
-            }
-            self.execute(q).map(|r| {
+                #bind_arg
+            });
+            log::info!("sql=> {}",q.sql());
+            self.execute(q.build()).map(|r| {
                 (r.last_insert_id().to_string(),r.rows_affected())
             })
         }
 
-        fn updates(&mut self, args: Vec<#name>, r#where: &str) -> cdbc::Result<u64> where #name: Sized {
-            use cdbc::{Either, Executor, query};
-            use cdbc::scan::Scan;
+        fn updates(&mut self, args: Vec<#name>, r#where: cdbc::crud::Condition<#name>) -> cdbc::Result<u64> where #name: Sized {
+            use cdbc::Executor;
             let mut num = 0;
             for arg in args {
-                let mut q = query("");
-                let mut arg_idx = 1;
-                let mut sets = String::new();
-
-                #bind_arg_if_some
-                if sets.ends_with(",") {
-                    sets.pop();
+                let mut q = cdbc::QueryBuilder::<#db>::new(#update);
+                let mut sets = 0;
+                #bind_sets_all
+                if sets == 0 {
+                    continue;
                 }
-                let mut w = r#where.to_string();
-                if !w.trim().is_empty() {
-                    w.insert_str(0, "where ");
-                }
-                let mut sql = format!("update {} set {} {}", #name::table(), sets, w);
-                log::info!("sql=> {}",sql);
-                q.statement = Either::Left(sql);
-                self.execute(q).map(|r| {
-                    num += r.rows_affected();
-                })?;
+                #push_where
+                log::info!("sql=> {}",q.sql());
+                num += self.execute(q.build())?.rows_affected();
             }
             return Ok(num);
         }
 
-        fn find(&mut self, r#where: &str) -> cdbc::Result<#name> where #name: Sized {
-            use cdbc::{Either, Executor, query};
+        fn find(&mut self, r#where: cdbc::crud::Condition<#name>) -> cdbc::Result<#name> where #name: Sized {
+            use cdbc::Executor;
+            use cdbc::scan::Scan;
+            let mut q = cdbc::QueryBuilder::<#db>::new(#select);
+            #push_where
+            #push_order_none
+            log::info!("sql=> {}",q.sql());
+            self.fetch_one(q.build())?.scan()
+        }
+
+        fn finds(&mut self, r#where: cdbc::crud::Condition<#name>) -> cdbc::Result<Vec<#name>> where #name: Sized {
+            use cdbc::Executor;
             use cdbc::scan::Scan;
-            let mut w = r#where.to_string();
-            if !w.trim().is_empty() {
-                w.insert_str(0, "where ");
+            let mut q = cdbc::QueryBuilder::<#db>::new(#select);
+            #push_where
+            #push_order_none
+            log::info!("sql=> {}",q.sql());
+            self.fetch_all(q.build())?.scan()
+        }
+
+        fn delete(&mut self, r#where: cdbc::crud::Condition<#name>) -> cdbc::Result<u64> where {
+            use cdbc::Executor;
+            let mut q = cdbc::QueryBuilder::<#db>::new(#delete);
+            #push_where
+            log::info!("sql=> {}",q.sql());
+            self.execute(q.build()).map(|r| {
+                r.rows_affected()
+            })
+        }
+
+        fn count(&mut self, r#where: cdbc::crud::Condition<#name>) -> cdbc::Result<u64> {
+            use cdbc::{Executor, Row};
+            #count_total
+            Ok(total as u64)
+        }
+
+        fn exists(&mut self, r#where: cdbc::crud::Condition<#name>) -> cdbc::Result<bool> {
+            use cdbc::Executor;
+            let mut q = cdbc::QueryBuilder::<#db>::new(#exists);
+            #push_where
+            q.push(#exists_limit);
+            log::info!("sql=> {}",q.sql());
+            Ok(self.fetch_optional(q.build())?.is_some())
+        }
+
+        fn find_after<V>(&mut self, cursor: cdbc::crud::Field<#name, V>, last: Option<V>, limit: u64) -> cdbc::Result<Vec<#name>>
+            where #name: Sized,
+                  V: Clone + Send + Sync + 'static,
+        {
+            use cdbc::Executor;
+            use cdbc::scan::Scan;
+            if limit == 0 {
+                return Ok(vec![]);
             }
-            let mut sql = format!("select * from {} {} ", #name::table(), w);
-            let q = query(&sql);
-            self.fetch_one(q)?.scan()
+            let column = [#(#quoted),*][cursor.index()];
+            let r#where = match last {
+                Some(last) => cursor.gt(last),
+                None => cdbc::crud::Condition::new(),
+            };
+            let mut q = cdbc::QueryBuilder::<#db>::new(#select);
+            #push_where
+            q.push(" order by ");
+            q.push(column);
+            #push_after
+            log::info!("sql=> {}",q.sql());
+            self.fetch_all(q.build())?.scan()
         }
 
-        fn finds(&mut self, r#where: &str) -> cdbc::Result<Vec<#name>> where #name: Sized {
-            use cdbc::{Either, Executor, query};
+        fn find_page(&mut self, r#where: cdbc::crud::Condition<#name>, page: cdbc::crud::PageRequest) -> cdbc::Result<cdbc::crud::Page<#name>> {
+            use cdbc::{Executor, Row};
             use cdbc::scan::Scan;
-            let mut w = r#where.to_string();
-            if !w.trim().is_empty() {
-                w.insert_str(0, "where ");
+            #check_order
+            #count_total
+            let total = total as u64;
+            if page.page_size == 0 || page.offset() >= total {
+                return Ok(cdbc::crud::Page::new(page, total, vec![]));
             }
-            let mut sql = format!("select * from {} {} ", #name::table(), w);
-            let q = query(&sql);
-            self.fetch_all(q)?.scan()
+            let mut q = cdbc::QueryBuilder::<#db>::new(#select);
+            #push_where
+            #push_order_page
+            #push_page
+            log::info!("sql=> {}",q.sql());
+            let records = self.fetch_all(q.build())?.scan()?;
+            Ok(cdbc::crud::Page::new(page, total, records))
         }
+    };
+    if ids.is_empty() {
+        return (data, None);
+    }
 
-        fn delete(&mut self, r#where: &str) -> cdbc::Result<u64> where {
-            use cdbc::{Either, Executor, query};
+    let id_idents = columns.iter().filter(|c| c.id).map(|c| &c.ident).collect::<Vec<_>>();
+    // `q` continued with ` where "id" = ?` of the key, `ids` the values of the key columns
+    let bind_where = |values: &[TokenStream]| {
+        let mut binds = quote! {q.push(" where ");};
+        for (i, (id, value)) in ids.iter().zip(values).enumerate() {
+            let id = if i == 0 { id.clone() } else { format!(" and {}", id) };
+            binds = quote! {
+                #binds
+                q.push(#id);
+                q.push_bind(#value);
+            };
+        }
+        binds
+    };
+    let id_values = if id_idents.len() == 1 {
+        vec![quote!(id)]
+    } else {
+        (0..id_idents.len()).map(|i| {
+            let i = syn::Index::from(i);
+            quote!(id.#i)
+        }).collect()
+    };
+    let bind_id = bind_where(&id_values);
+    let bind_arg_id = bind_where(&id_idents.iter().map(|id| quote!(arg.#id)).collect::<Vec<_>>());
+    let bind_sets = bind_sets(true);
+    let table_error = format!("CRUDById::update_by_id of table {}: the key `{{}}` is None", table.replace('{', "{{").replace('}', "}}"));
+    let id_names = id_idents.iter().map(|id| id.to_string());
+    let by_id = quote! {
+        fn find_by_id(&mut self, id: <#name as cdbc::crud::TableId>::Id) -> cdbc::Result<#name> {
+            use cdbc::Executor;
             use cdbc::scan::Scan;
-            let mut w = r#where.to_string();
-            if !w.trim().is_empty() {
-                w.insert_str(0, "where ");
+            let mut q = cdbc::QueryBuilder::<#db>::new(#select);
+            #bind_id
+            log::info!("sql=> {}",q.sql());
+            self.fetch_one(q.build())?.scan()
+        }
+
+        fn update_by_id(&mut self, arg: #name) -> cdbc::Result<u64> {
+            use cdbc::Executor;
+            #(
+                if arg.#id_idents.is_none() {
+                    return Err(cdbc::Error::InvalidArgument(format!(#table_error, #id_names)));
+                }
+            )*
+            let mut q = cdbc::QueryBuilder::<#db>::new(#update);
+            let mut sets = 0;
+            #bind_sets
+            if sets == 0 {
+                return Ok(0);
             }
-            let mut sql = format!("delete from {} {} ", #name::table(), w);
-            let q = query(&sql);
-            self.execute(q).map(|r| {
-                r.rows_affected()
-            })
+            #bind_arg_id
+            log::info!("sql=> {}",q.sql());
+            self.execute(q.build()).map(|r| r.rows_affected())
+        }
+
+        fn delete_by_id(&mut self, id: <#name as cdbc::crud::TableId>::Id) -> cdbc::Result<u64> {
+            use cdbc::Executor;
+            let mut q = cdbc::QueryBuilder::<#db>::new(#delete);
+            #bind_id
+            log::info!("sql=> {}",q.sql());
+            self.execute(q.build()).map(|r| r.rows_affected())
         }
+    };
+    (data, Some(by_id))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crud::{gen_sql, parse_columns, parse_table};
+    use crate::driver::Driver;
+
+    #[test]
+    fn test_parse_columns() {
+        let mut ast: syn::DeriveInput = syn::parse_str(
+            "struct BizActivity { #[crud(id)] id: Option<String>, #[crud(column = \"remark\")] r#type: Option<String>, #[crud(skip)] cache: Option<i32> }",
+        ).unwrap();
+        let columns = parse_columns(&mut ast).unwrap();
+        assert_eq!(columns.iter().map(|c| (c.name.as_str(), c.id)).collect::<Vec<_>>(), vec![("id", true), ("remark", false)]);
+        // the attributes are taken off the fields
+        assert!(!quote::quote!(#ast).to_string().contains("crud"));
+
+        let sql = gen_sql(Driver::Postgres, "biz.activity", &columns);
+        assert_eq!(sql.insert, "insert into \"biz\".\"activity\" (\"id\",\"remark\") ");
+        assert_eq!(sql.select, "select \"id\",\"remark\" as \"type\" from \"biz\".\"activity\"");
+        assert_eq!(sql.ids, vec!["\"id\" = ".to_string()]);
+        assert_eq!(sql.order.as_deref(), Some(" order by \"id\""));
+        let sql = gen_sql(Driver::MySql, "biz_activity", &columns);
+        assert_eq!(sql.update, "update `biz_activity` set ");
+        assert_eq!(sql.delete, "delete from `biz_activity`");
+        let sql = gen_sql(Driver::Mssql, "biz_activity", &columns[1..]);
+        assert_eq!(sql.count, "select count_big(*) from [biz_activity]");
+        assert_eq!(sql.exists.0, "select top 1 1 from [biz_activity]");
+        // no key, no default order
+        assert_eq!(sql.order, None);
+
+        let mut ast: syn::DeriveInput = syn::parse_str("struct S { #[crud(id, skip)] id: Option<i32> }").unwrap();
+        assert_eq!(parse_columns(&mut ast).err().unwrap().to_string(), "a `#[crud(id)]` field can't be skipped");
+        let mut ast: syn::DeriveInput = syn::parse_str("struct S { #[crud(key)] id: Option<i32> }").unwrap();
+        assert_eq!(parse_columns(&mut ast).err().unwrap().to_string(), "expected `id`, `skip` or `column = \"..\"`");
     }
-   );
-    return data;
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_table() {
+        let args = |s: &str| match syn::parse_str::<syn::Meta>(&format!("crud({})", s)).unwrap() {
+            syn::Meta::List(list) => list.nested.into_iter().collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        assert_eq!(parse_table(&args("db = \"pg\", table = \"biz_activity\"")).unwrap(), Some("biz_activity".to_string()));
+        assert_eq!(parse_table(&args("db = \"pg\"")).unwrap(), None);
+        assert_eq!(parse_table(&args("name = \"a\"")).unwrap_err().to_string(), "expected `db = \"..\"` or `table = \"..\"`");
+    }
+}
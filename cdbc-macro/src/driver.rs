@@ -0,0 +1,173 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Attribute, Lit, Meta, NestedMeta};
+
+/// A database driver the `Scan` and `crud` impls are generated for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Driver {
+    MySql,
+    Postgres,
+    Sqlite,
+    Mssql,
+}
+
+const EXPECTED: &str = "expected one of `mysql`, `pg`(or `postgres`), `sqlite`, `mssql`";
+
+impl Driver {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "mysql" => Some(Driver::MySql),
+            "pg" | "postgres" => Some(Driver::Postgres),
+            "sqlite" => Some(Driver::Sqlite),
+            "mssql" => Some(Driver::Mssql),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Driver::MySql => "mysql",
+            Driver::Postgres => "pg",
+            Driver::Sqlite => "sqlite",
+            Driver::Mssql => "mssql",
+        }
+    }
+
+    pub(crate) fn row(&self) -> TokenStream {
+        match self {
+            Driver::MySql => quote!(cdbc_mysql::MySqlRow),
+            Driver::Postgres => quote!(cdbc_pg::PgRow),
+            Driver::Sqlite => quote!(cdbc_sqlite::SqliteRow),
+            Driver::Mssql => quote!(cdbc_mssql::MssqlRow),
+        }
+    }
+
+    pub(crate) fn database(&self) -> TokenStream {
+        self.types().0
+    }
+
+    /// quote an identifier(each part of `schema.table`) for the sql of the driver
+    pub(crate) fn quote(&self, ident: &str) -> String {
+        let (open, close) = match self {
+            Driver::MySql => ('`', '`'),
+            Driver::Mssql => ('[', ']'),
+            Driver::Postgres | Driver::Sqlite => ('"', '"'),
+        };
+        ident
+            .split('.')
+            .map(|part| format!("{}{}{}", open, part.replace(close, &format!("{}{}", close, close)), close))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    fn types(&self) -> (TokenStream, TokenStream) {
+        match self {
+            Driver::MySql => (quote!(cdbc_mysql::MySql), quote!(cdbc_mysql::MySqlConnection)),
+            Driver::Postgres => (quote!(cdbc_pg::Postgres), quote!(cdbc_pg::PgConnection)),
+            Driver::Sqlite => (quote!(cdbc_sqlite::Sqlite), quote!(cdbc_sqlite::SqliteConnection)),
+            Driver::Mssql => (quote!(cdbc_mssql::Mssql), quote!(cdbc_mssql::MssqlConnection)),
+        }
+    }
+
+    /// the pool, connection, transaction and pool connection of the driver
+    pub(crate) fn executors(&self) -> Vec<TokenStream> {
+        let (db, conn) = self.types();
+        vec![
+            quote!(cdbc::Pool<#db>),
+            conn,
+            quote!(cdbc::Transaction::<'_,#db>),
+            quote!(cdbc::PoolConnection::<#db>),
+        ]
+    }
+
+    /// the drivers enabled by the features of cdbc(`mysql`, `postgres`, `sqlite`, `mssql`)
+    fn from_features() -> Vec<Driver> {
+        let mut drivers = vec![];
+        if cfg!(feature = "mysql") {
+            drivers.push(Driver::MySql);
+        }
+        if cfg!(feature = "postgres") {
+            drivers.push(Driver::Postgres);
+        }
+        if cfg!(feature = "sqlite") {
+            drivers.push(Driver::Sqlite);
+        }
+        if cfg!(feature = "mssql") {
+            drivers.push(Driver::Mssql);
+        }
+        drivers
+    }
+}
+
+/// push the drivers of the `db = ".."` items of `metas`, other items are left to the caller
+pub(crate) fn parse_metas<'a>(metas: impl IntoIterator<Item=&'a NestedMeta>, drivers: &mut Vec<Driver>) -> syn::Result<()> {
+    for meta in metas {
+        if let NestedMeta::Meta(Meta::NameValue(nv)) = meta {
+            if nv.path.is_ident("db") {
+                let driver = match &nv.lit {
+                    Lit::Str(s) => Driver::parse(&s.value()),
+                    _ => None,
+                };
+                match driver {
+                    Some(driver) if !drivers.contains(&driver) => drivers.push(driver),
+                    Some(_) => {}
+                    None => return Err(syn::Error::new_spanned(&nv.lit, EXPECTED)),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// push the drivers of the `#[cdbc(db = "..")]` attributes
+pub(crate) fn parse_attrs(attrs: &[Attribute], drivers: &mut Vec<Driver>) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|a| a.path.is_ident("cdbc")) {
+        if let Meta::List(list) = attr.parse_meta()? {
+            parse_metas(&list.nested, drivers)?;
+        }
+    }
+    Ok(())
+}
+
+/// the drivers given explicitly, else the drivers of the cdbc features
+pub(crate) fn resolve(explicit: Vec<Driver>, span: Span, generated: &str) -> syn::Result<Vec<Driver>> {
+    if !explicit.is_empty() {
+        return Ok(explicit);
+    }
+    let drivers = Driver::from_features();
+    if drivers.is_empty() {
+        return Err(syn::Error::new(span, format!(
+            "can't determine the database of {}, add `#[cdbc(db = \"pg\")]`(or mysql, sqlite, mssql) \
+             or enable the feature of the database on cdbc, e.g. `cdbc = {{ features = [\"postgres\"] }}`",
+            generated
+        )));
+    }
+    Ok(drivers)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::driver::{parse_attrs, resolve, Driver};
+
+    #[test]
+    fn test_parse_attrs() {
+        let input: syn::DeriveInput = syn::parse_str(
+            "#[cdbc(db = \"pg\", db = \"sqlite\")] #[cdbc(rename_all = \"camelCase\", db = \"postgres\")] struct S;",
+        ).unwrap();
+        let mut drivers = vec![];
+        parse_attrs(&input.attrs, &mut drivers).unwrap();
+        assert_eq!(drivers, vec![Driver::Postgres, Driver::Sqlite]);
+        assert_eq!(resolve(drivers, proc_macro2::Span::call_site(), "`S`").unwrap().len(), 2);
+
+        let input: syn::DeriveInput = syn::parse_str("#[cdbc(db = \"oracle\")] struct S;").unwrap();
+        let err = parse_attrs(&input.attrs, &mut vec![]).unwrap_err();
+        assert_eq!(err.to_string(), "expected one of `mysql`, `pg`(or `postgres`), `sqlite`, `mssql`");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(Driver::Postgres.quote("public.biz_activity"), "\"public\".\"biz_activity\"");
+        assert_eq!(Driver::MySql.quote("a`b"), "`a``b`");
+        assert_eq!(Driver::Mssql.quote("name"), "[name]");
+    }
+}
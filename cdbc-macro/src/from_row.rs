@@ -0,0 +1,262 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Fields, Lit, Meta, NestedMeta, Type, WherePredicate};
+
+#[derive(Default)]
+struct FieldAttr {
+    rename: Option<String>,
+    default: bool,
+    skip: bool,
+    flatten: bool,
+    try_from: Option<Type>,
+}
+
+/// the `#[cdbc(..)]` attributes of the struct or a field
+fn cdbc_metas(attrs: &[syn::Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut metas = vec![];
+    for attr in attrs.iter().filter(|a| a.path.is_ident("cdbc")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            other => return Err(syn::Error::new_spanned(other, "expected `#[cdbc(..)]`")),
+        }
+    }
+    Ok(metas)
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        other => Err(syn::Error::new_spanned(other, "expected a string")),
+    }
+}
+
+fn parse_rename_all(attrs: &[syn::Attribute]) -> syn::Result<Option<String>> {
+    let mut rename_all = None;
+    for meta in cdbc_metas(attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename_all") => {
+                let style = lit_str(&nv.lit)?;
+                if rename(&style, "a").is_none() {
+                    return Err(syn::Error::new_spanned(nv.lit, "expected one of `snake_case`, `lowercase`, `UPPERCASE`, `camelCase`, `PascalCase`, `SCREAMING_SNAKE_CASE`, `kebab-case`"));
+                }
+                rename_all = Some(style);
+            }
+            // the databases of `#[derive(Scan)]`
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("db") => {}
+            other => return Err(syn::Error::new_spanned(other, "expected `rename_all = \"..\"`")),
+        }
+    }
+    Ok(rename_all)
+}
+
+fn parse_field_attr(attrs: &[syn::Attribute]) -> syn::Result<FieldAttr> {
+    let mut attr = FieldAttr::default();
+    for meta in cdbc_metas(attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                attr.rename = Some(lit_str(&nv.lit)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("try_from") => {
+                attr.try_from = Some(syn::parse_str(&lit_str(&nv.lit)?)?);
+            }
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("default") => attr.default = true,
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => attr.skip = true,
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("flatten") => attr.flatten = true,
+            other => {
+                return Err(syn::Error::new_spanned(other, "expected `rename = \"..\"`, `default`, `skip`, `flatten` or `try_from = \"..\"`"))
+            }
+        }
+    }
+    if attr.flatten && (attr.rename.is_some() || attr.try_from.is_some() || attr.skip) {
+        return Err(syn::Error::new_spanned(&attrs[0], "`flatten` can't be combined with `rename`, `try_from` or `skip`"));
+    }
+    Ok(attr)
+}
+
+/// `NULL` decodes to an `Option`, other types are checked for `NULL` first
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// the column name of the snake_case field `name` in the `rename_all` style
+fn rename(style: &str, name: &str) -> Option<String> {
+    let words = name.split('_').filter(|w| !w.is_empty());
+    let capitalize = |w: &str| {
+        let mut chars = w.chars();
+        chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+    };
+    Some(match style {
+        "snake_case" => name.to_lowercase(),
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "SCREAMING_SNAKE_CASE" => name.to_uppercase(),
+        "kebab-case" => name.to_lowercase().replace('_', "-"),
+        "PascalCase" => words.map(capitalize).collect(),
+        "camelCase" => {
+            let pascal: String = words.map(capitalize).collect();
+            let mut chars = pascal.chars();
+            chars.next().map(|c| c.to_lowercase().chain(chars).collect()).unwrap_or_default()
+        }
+        _ => return None,
+    })
+}
+
+/// `#[derive(FromRow)]` reads every field with `Row::try_get`, for any row type whose database can
+/// decode the fields. Named fields are read by column name, tuple struct fields by position.
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(s) => &s.fields,
+        _ => return Err(syn::Error::new_spanned(&input.ident, "FromRow can only be derived for structs")),
+    };
+    let rename_all = parse_rename_all(&input.attrs)?;
+
+    let mut predicates: Vec<WherePredicate> = vec![];
+    let mut reads = vec![];
+    let mut idents = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let attr = parse_field_attr(&field.attrs)?;
+        let ty = &field.ty;
+        let ident = syn::Ident::new(&format!("__field{}", i), proc_macro2::Span::call_site());
+        let index = match &field.ident {
+            Some(field_ident) => {
+                let field_name = field_ident.to_string().trim_start_matches("r#").to_string();
+                let column = match (&attr.rename, &rename_all) {
+                    (Some(rename), _) => rename.clone(),
+                    (None, Some(style)) => rename(style, &field_name).unwrap_or(field_name),
+                    (None, None) => field_name,
+                };
+                quote!(#column)
+            }
+            None => {
+                if attr.rename.is_some() || attr.flatten {
+                    return Err(syn::Error::new_spanned(field, "tuple struct fields are read by position, `rename` and `flatten` need named fields"));
+                }
+                quote!(#i)
+            }
+        };
+
+        let read = if attr.skip {
+            quote!(::std::default::Default::default())
+        } else if attr.flatten {
+            predicates.push(parse_quote!(#ty: cdbc::from_row::FromRow<'r, R>));
+            quote!(<#ty as cdbc::from_row::FromRow<'r, R>>::from_row(row)?)
+        } else {
+            let decoded = attr.try_from.as_ref().unwrap_or(ty);
+            predicates.push(parse_quote!(#decoded: cdbc::decode::Decode<'r, R::Database> + cdbc::types::Type<R::Database>));
+            let get = if is_option(decoded) {
+                quote!(row.try_get::<#decoded, _>(#index))
+            } else {
+                quote!(cdbc::from_row::try_get_not_null::<_, #decoded, _>(row, #index))
+            };
+            let get = if attr.default {
+                quote! {
+                    match #get {
+                        ::std::result::Result::Err(cdbc::Error::ColumnNotFound(_)) => ::std::default::Default::default(),
+                        value => value?,
+                    }
+                }
+            } else {
+                quote!(#get?)
+            };
+            match &attr.try_from {
+                Some(from) => {
+                    predicates.push(parse_quote!(#ty: ::std::convert::TryFrom<#from>));
+                    predicates.push(parse_quote!(<#ty as ::std::convert::TryFrom<#from>>::Error: ::std::convert::Into<cdbc::error::BoxDynError>));
+                    quote! {
+                        <#ty as ::std::convert::TryFrom<#from>>::try_from(#get).map_err(|e| cdbc::Error::ColumnDecode {
+                            index: format!("{:?}", #index),
+                            source: e.into(),
+                        })?
+                    }
+                }
+                None => get,
+            }
+        };
+        reads.push(quote!(let #ident: #ty = #read;));
+        idents.push((field.ident.clone(), ident));
+    }
+
+    let construct = match fields {
+        Fields::Named(_) => {
+            let assigns = idents.iter().map(|(field, ident)| quote!(#field: #ident));
+            quote!(#name { #(#assigns),* })
+        }
+        Fields::Unnamed(_) => {
+            let values = idents.iter().map(|(_, ident)| ident);
+            quote!(#name ( #(#values),* ))
+        }
+        Fields::Unit => quote!(#name),
+    };
+    let index_bound = match fields {
+        Fields::Named(_) => quote!(&'static str: cdbc::column::ColumnIndex<R>,),
+        _ => quote!(usize: cdbc::column::ColumnIndex<R>,),
+    };
+
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    let lifetimes: Vec<_> = generics.lifetimes().map(|l| l.lifetime.clone()).collect();
+    generics.params.insert(0, parse_quote!('r));
+    generics.params.push(parse_quote!(R: cdbc::row::Row));
+    for lifetime in lifetimes {
+        predicates.push(parse_quote!('r: #lifetime));
+    }
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let predicates_of_struct = where_clause.map(|w| {
+        let p = &w.predicates;
+        quote!(#p,)
+    });
+
+    Ok(quote! {
+        impl #impl_generics cdbc::from_row::FromRow<'r, R> for #name #ty_generics
+            where
+                #predicates_of_struct
+                #index_bound
+                #(#predicates,)*
+        {
+            fn from_row(row: &'r R) -> ::std::result::Result<Self, cdbc::Error> {
+                #[allow(unused_imports)]
+                use cdbc::row::Row as _;
+                #(#reads)*
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::from_row::{expand, rename};
+
+    #[test]
+    fn test_rename() {
+        assert_eq!(rename("camelCase", "user_id").unwrap(), "userId");
+        assert_eq!(rename("PascalCase", "user_id").unwrap(), "UserId");
+        assert_eq!(rename("SCREAMING_SNAKE_CASE", "user_id").unwrap(), "USER_ID");
+        assert_eq!(rename("kebab-case", "user_id").unwrap(), "user-id");
+        assert_eq!(rename("lowercase", "user_id").unwrap(), "user_id");
+        assert_eq!(rename("Train-Case", "user_id"), None);
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let err = |input: &str| expand(syn::parse_str(input).unwrap()).unwrap_err().to_string();
+        assert_eq!(err("enum E { A }"), "FromRow can only be derived for structs");
+        assert_eq!(
+            err("#[cdbc(rename_all = \"Train-Case\")] struct S { a: i32 }"),
+            "expected one of `snake_case`, `lowercase`, `UPPERCASE`, `camelCase`, `PascalCase`, `SCREAMING_SNAKE_CASE`, `kebab-case`"
+        );
+        assert_eq!(
+            err("struct S(#[cdbc(rename = \"a\")] i32);"),
+            "tuple struct fields are read by position, `rename` and `flatten` need named fields"
+        );
+        assert_eq!(
+            err("struct S { #[cdbc(flatten, skip)] a: Inner }"),
+            "`flatten` can't be combined with `rename`, `try_from` or `skip`"
+        );
+        assert!(expand(syn::parse_str("struct S<'a> { #[cdbc(try_from = \"i64\")] id: u32, name: &'a str }").unwrap()).is_ok());
+    }
+}
@@ -3,36 +3,27 @@ extern crate proc_macro;
 
 mod scan;
 mod crud;
-use std::fs::File;
-use std::io::Read;
+mod query;
+mod query_data;
+mod migrate;
+mod test;
+mod from_row;
+mod driver;
 use quote::quote;
 use crate::proc_macro::TokenStream;
 
-/// this Scan will find on Cargo.toml database driver to impl cdbc::impl_scan!(#db_type,#name{#fields});
-#[proc_macro_derive(Scan)]
+/// impl cdbc::impl_scan!(#db_type,#name{#fields}) for the databases given with `#[cdbc(db = "pg", db = "sqlite")]`,
+/// else for the databases enabled by the features of cdbc(`mysql`, `postgres`, `sqlite`, `mssql`)
+#[proc_macro_derive(Scan, attributes(cdbc))]
 pub fn macro_derive_scan_all(input: TokenStream) -> TokenStream {
-    let mut cargo_data = "".to_string();
-    let mut f = File::open("Cargo.lock").unwrap();
-    f.read_to_string(&mut cargo_data).unwrap();
-    drop(f);
-
-    let mut database = vec![];
-    for line in cargo_data.lines() {
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-mysql") {
-            database.push(quote!(cdbc_mysql::MySqlRow));
-        }
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-pg") {
-            database.push(quote!(cdbc_pg::PgRow));
-        }
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-sqlite") {
-            database.push(quote!(cdbc_sqlite::SqliteRow));
-        }
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-mssql") {
-            database.push(quote!(cdbc_mssql::MssqlRow));
-        }
-    }
-
-    let ast = syn::parse(input).unwrap();
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    let mut drivers = vec![];
+    let drivers = driver::parse_attrs(&ast.attrs, &mut drivers)
+        .and_then(|_| driver::resolve(drivers, ast.ident.span(), &format!("`#[derive(Scan)]` of `{}`", ast.ident)));
+    let database: Vec<_> = match drivers {
+        Ok(drivers) => drivers.iter().map(|d| d.row()).collect(),
+        Err(e) => return e.to_compile_error().into(),
+    };
     let stream = scan::impl_scan(&ast, &database);
     #[cfg(feature = "debug_mode")]
     {
@@ -91,44 +82,146 @@ pub fn macro_derive_scan_pg(input: TokenStream) -> TokenStream {
 }
 
 
+/// Implement `cdbc::from_row::FromRow` for a struct, for the rows of every database that can
+/// decode its fields. Named fields are read by column name and tuple struct fields by position,
+/// a missing column or a `NULL` in a non-`Option` field is an error.
+///
+/// `#[cdbc(rename_all = "camelCase")]` on the struct, and on the fields `#[cdbc(rename = "..")]`,
+/// `#[cdbc(default)]`(a missing column is `Default::default()`), `#[cdbc(skip)]`,
+/// `#[cdbc(flatten)]`(a nested `FromRow` struct read from the same row) and
+/// `#[cdbc(try_from = "i64")]`(decode an `i64` and convert it with `TryFrom`).
+/// ```ignore
+/// #[derive(cdbc::FromRow)]
+/// #[cdbc(rename_all = "camelCase")]
+/// struct User {
+///     id: i64,
+///     #[cdbc(rename = "name")]
+///     user_name: String,
+///     #[cdbc(try_from = "i64")]
+///     age: u8,
+///     #[cdbc(default)]
+///     remark: Option<String>,
+///     #[cdbc(flatten)]
+///     address: Address,
+/// }
+/// ```
+#[proc_macro_derive(FromRow, attributes(cdbc))]
+pub fn macro_derive_from_row(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match from_row::expand(input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// impl cdbc::crud::CRUD for the pool, connection and transaction of the databases given with
+/// `#[crud(db = "pg")]` or `#[cdbc(db = "pg")]` on the struct, else of the databases enabled by the
+/// features of cdbc(`mysql`, `postgres`, `sqlite`, `mssql`).
+/// The table is `#[crud(table = "..")]`(default the snake_case name of the struct), fields take
+/// `#[crud(id)]`(the primary key, also implementing `cdbc::crud::CRUDById` with `find_by_id`,
+/// `update_by_id`, `delete_by_id`, and the default order of `find_page`), `#[crud(skip)]` and
+/// `#[crud(column = "..")]`.
+/// The conditions are built from the associated fns generated for the fields, e.g.
+/// `BizActivity::name().eq("tom")`, so the struct can't have a method named like a field.
 #[proc_macro_attribute]
 pub fn crud(args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut cargo_data = "".to_string();
-    let mut f = File::open("Cargo.lock").unwrap();
-    f.read_to_string(&mut cargo_data).unwrap();
-    drop(f);
-    let mut database = vec![];
-    for line in cargo_data.lines() {
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-mysql") {
-            database.push(vec![quote!(cdbc::Pool<cdbc_mysql::MySql>),
-                               quote!(cdbc_mysql::MySqlConnection),
-                               quote!(cdbc::Transaction::<'_,cdbc_mysql::MySql>),
-                               quote!(cdbc::PoolConnection::<cdbc_mysql::MySql>)]);
-        }
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-pg") {
-            database.push(vec![quote!(cdbc::Pool<cdbc_pg::Postgres>),
-                               quote!(cdbc_pg::PgConnection),
-                               quote!(cdbc::Transaction::<'_,cdbc_pg::Postgres>),
-                               quote!(cdbc::PoolConnection::<cdbc_pg::Postgres>)]);
-        }
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-sqlite") {
-            database.push(vec![quote!(cdbc::Pool<cdbc_sqlite::Sqlite>),
-                               quote!(cdbc_sqlite::SqliteConnection),
-                               quote!(cdbc::Transaction::<'_,cdbc_sqlite::Sqlite>),
-                               quote!(cdbc::PoolConnection::<cdbc_sqlite::Sqlite>)]);
-        }
-        if line.trim_start_matches(r#"name = ""#).starts_with("cdbc-mssql") {
-            database.push(vec![quote!(cdbc::Pool<cdbc_mssql::Mssql>),
-                               quote!(cdbc_mssql::MssqlConnection),
-                               quote!(cdbc::Transaction::<'_,cdbc_mssql::Mssql>),
-                               quote!(cdbc::PoolConnection::<cdbc_mssql::Mssql>)]);
-        }
-    }
-    let stream = crud::impl_crud(input, database);
+    let args = syn::parse_macro_input!(args as syn::AttributeArgs);
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    let mut drivers = vec![];
+    let drivers = driver::parse_metas(&args, &mut drivers)
+        .and_then(|_| {
+            let arg_drivers = drivers.clone();
+            driver::parse_attrs(&ast.attrs, &mut drivers)?;
+            let drivers = driver::resolve(drivers, ast.ident.span(), &format!("`#[crud]` of `{}`", ast.ident))?;
+            Ok((arg_drivers, drivers))
+        });
+    let stream = drivers
+        .and_then(|(arg_drivers, drivers)| {
+            let table = crud::parse_table(&args)?;
+            crud::impl_crud(ast, &arg_drivers, &drivers, table)
+        });
+    let stream: TokenStream = match stream {
+        Ok(stream) => stream.into(),
+        Err(e) => return e.to_compile_error().into(),
+    };
     #[cfg(feature = "debug_mode")]
     {
         println!("............gen crud:\n {}", stream);
         println!("............gen crud end............");
     }
     stream
-}
\ No newline at end of file
+}
+
+/// Checked query. The sql is described by the database on `DATABASE_URL` at build time (or read from
+/// `.cdbc` when offline), bind parameters and output columns are checked against it.
+/// The output row is a anonymous struct with one field per column.
+/// ```ignore
+/// let row = cdbc::checked::query!("select id, name from biz_activity where id = ?", id)
+///     .fetch_one(&mut conn)?;
+/// println!("{} {:?}", row.id, row.name);
+/// ```
+#[proc_macro]
+pub fn query(input: TokenStream) -> TokenStream {
+    query::expand_input(input, query::Output::Record)
+}
+
+/// Checked query mapping the output columns to the fields of the given struct.
+/// ```ignore
+/// let rows: Vec<BizActivity> = cdbc::checked::query_as!(BizActivity, "select id, name from biz_activity")
+///     .fetch_all(&mut conn)?;
+/// ```
+#[proc_macro]
+pub fn query_as(input: TokenStream) -> TokenStream {
+    query::expand_input(input, query::Output::As)
+}
+
+/// Checked query returning the only output column.
+/// ```ignore
+/// let count: i64 = cdbc::checked::query_scalar!("select count(1) as \"count!\" from biz_activity")
+///     .fetch_one(&mut conn)?;
+/// ```
+#[proc_macro]
+pub fn query_scalar(input: TokenStream) -> TokenStream {
+    query::expand_input(input, query::Output::Scalar)
+}
+
+/// Embed the migrations of a directory (relative to `CARGO_MANIFEST_DIR`, default `migrations`)
+/// into the binary, expands to a `cdbc::migrate::Migrator`.
+/// ```ignore
+/// cdbc::migrate!("./migrations").run(&mut conn)?;
+/// ```
+/// editing a migration rebuilds the crate, to pick up added or removed files add a `build.rs`:
+/// ```ignore
+/// fn main() {
+///     println!("cargo:rerun-if-changed=migrations");
+/// }
+/// ```
+#[proc_macro]
+pub fn migrate(input: TokenStream) -> TokenStream {
+    migrate::expand_input(input)
+}
+
+/// Run a test in a coroutine with a fresh database. The pool argument selects the database,
+/// postgres, mysql and mssql create a temporary database on the server of `DATABASE_URL`
+/// and drop it afterwards, sqlite uses a named in-memory database.
+/// `fixtures` are sql files (relative to the test file, like `include_str!`) applied before the body,
+/// `template` is the database postgres copies the test database from.
+/// ```ignore
+/// #[cdbc::test(fixtures("users.sql"))]
+/// fn test_users(pool: Pool<Postgres>) -> Result<(), cdbc::Error> {
+///     let count: i64 = cdbc::query_scalar("select count(*) from users").fetch_one(&pool)?;
+///     assert_eq!(count, 3);
+///     Ok(())
+/// }
+/// ```
+///
+/// re-exported as `cdbc::test`, the name doesn't shadow `#[test]` where `cdbc_macro` is `#[macro_use]`d
+#[proc_macro_attribute]
+pub fn cdbc_test(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(args as syn::AttributeArgs);
+    let input = syn::parse_macro_input!(input as syn::ItemFn);
+    match test::expand(args, input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::LitStr;
+
+/// `migrate!("dir")`, the dir is relative to `CARGO_MANIFEST_DIR` and defaults to `migrations`
+pub(crate) fn expand_input(input: crate::proc_macro::TokenStream) -> crate::proc_macro::TokenStream {
+    let dir = if input.is_empty() {
+        LitStr::new("migrations", proc_macro2::Span::call_site())
+    } else {
+        match syn::parse::<LitStr>(input) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error().into(),
+        }
+    };
+    match expand(&dir.value()) {
+        Ok(v) => v.into(),
+        Err(e) => syn::Error::new(dir.span(), e).to_compile_error().into(),
+    }
+}
+
+fn expand(dir: &str) -> Result<TokenStream, String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "`CARGO_MANIFEST_DIR` must be set".to_string())?;
+    let dir = PathBuf::from(manifest_dir).join(dir);
+    let entries = std::fs::read_dir(&dir).map_err(|e| format!("read {}: {}", dir.display(), e))?;
+    let mut files = vec![];
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let name = match path.file_name() {
+            Some(v) => v.to_string_lossy().into_owned(),
+            None => continue,
+        };
+        if path.is_file() && name.ends_with(".sql") {
+            files.push((name, path));
+        }
+    }
+    files.sort();
+
+    // same rules as `cdbc::migrate::parse_file_name`, checked here so a bad file is a compile error
+    let mut versions = HashSet::new();
+    for (name, _) in &files {
+        let stem = name.trim_end_matches(".sql");
+        let down = stem.ends_with(".down");
+        let version = stem.split(|c| c == '_' || c == '.').next().unwrap_or_default();
+        if version.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) || version.parse::<i64>().is_err() {
+            return Err(format!("invalid migration file name {:?}, expected `<VERSION>_<DESCRIPTION>.sql` or `<VERSION>_<DESCRIPTION>.down.sql`", name));
+        }
+        if !versions.insert((version.parse::<i64>().unwrap(), down)) {
+            return Err(format!("migration {} is defined more than once", version));
+        }
+    }
+
+    // `include_str!` makes every file a build input, so editing a migration rebuilds the crate.
+    // cargo can't be told about the directory from a proc macro: files added or removed later need a
+    // build script printing `cargo:rerun-if-changed=migrations`
+    let files = files.iter().map(|(name, path)| {
+        let path = path.to_string_lossy();
+        quote!((#name, include_str!(#path)))
+    });
+    Ok(quote! {
+        cdbc::migrate::Migrator::from_files(&[#(#files),*]).expect("invalid embedded migrations")
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::expand;
+
+    #[test]
+    fn test_expand_includes_files() {
+        let dir = std::env::temp_dir().join(format!("cdbc_migrate_macro_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("1_init.sql"), "create table a(id int);").unwrap();
+        std::fs::write(dir.join("1_init.down.sql"), "drop table a;").unwrap();
+        std::fs::write(dir.join("README.md"), "not a migration").unwrap();
+        let tokens = expand(dir.to_str().unwrap()).map(|v| v.to_string());
+        let files = ["1_init.sql", "1_init.down.sql"].map(|v| dir.join(v).to_string_lossy().into_owned());
+        std::fs::remove_dir_all(&dir).unwrap();
+        let tokens = tokens.unwrap();
+        for file in files {
+            assert!(tokens.contains(&format!("include_str ! ({:?})", file)), "{}", tokens);
+        }
+        assert!(!tokens.contains("README"), "{}", tokens);
+    }
+}
@@ -0,0 +1,360 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::{Expr, LitStr, Path, Token, Type};
+
+use crate::query_data::{ParametersData, QueryData};
+
+/// which of `query!`, `query_as!` and `query_scalar!` is expanded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Output {
+    Record,
+    As,
+    Scalar,
+}
+
+pub(crate) struct QueryInput {
+    pub record: Option<Path>,
+    pub sql: LitStr,
+    pub args: Vec<Expr>,
+}
+
+impl QueryInput {
+    fn parse(input: ParseStream, output: Output) -> syn::Result<Self> {
+        let record = if output == Output::As {
+            let path: Path = input.parse()?;
+            input.parse::<Token![,]>()?;
+            Some(path)
+        } else {
+            None
+        };
+        let sql: LitStr = input.parse()?;
+        let mut args = vec![];
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            let list = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+            args = list.into_iter().collect();
+        }
+        Ok(QueryInput { record, sql, args })
+    }
+
+    pub(crate) fn parser(output: Output) -> impl FnOnce(ParseStream) -> syn::Result<Self> {
+        move |input| Self::parse(input, output)
+    }
+}
+
+/// A output column, the sql column name may carry a override (same as sqlx):
+/// * `name!` force not null
+/// * `name?` force nullable
+/// * `name: Type` use `Type` instead of the type inferred from the database
+#[derive(Debug, PartialEq)]
+pub(crate) struct ColumnOverride {
+    pub name: String,
+    pub nullable: Option<bool>,
+    pub ty: Option<String>,
+}
+
+impl ColumnOverride {
+    pub(crate) fn parse(column: &str) -> Self {
+        let (column, ty) = match column.find(':') {
+            Some(idx) => (column[..idx].trim(), Some(column[idx + 1..].trim().to_string())),
+            None => (column.trim(), None),
+        };
+        let (name, nullable) = if let Some(name) = column.strip_suffix('!') {
+            (name, Some(false))
+        } else if let Some(name) = column.strip_suffix('?') {
+            (name, Some(true))
+        } else {
+            (column, None)
+        };
+        ColumnOverride {
+            name: name.trim().to_string(),
+            nullable,
+            ty,
+        }
+    }
+}
+
+fn db_path(db: &str) -> Result<TokenStream, String> {
+    match db {
+        "postgres" => Ok(quote!(cdbc_pg::Postgres)),
+        "mysql" => Ok(quote!(cdbc_mysql::MySql)),
+        "sqlite" => Ok(quote!(cdbc_sqlite::Sqlite)),
+        "mssql" => Ok(quote!(cdbc_mssql::Mssql)),
+        _ => Err(format!("unknown database `{}`", db)),
+    }
+}
+
+/// the rust type decoded from a output column of `type_info`
+fn column_type(db: &str, type_info: &str) -> Option<&'static str> {
+    let ty = match db {
+        "postgres" => match type_info {
+            "BOOL" => "bool",
+            "\"CHAR\"" => "i8",
+            "INT2" => "i16",
+            "INT4" => "i32",
+            "INT8" => "i64",
+            "FLOAT4" => "f32",
+            "FLOAT8" => "f64",
+            "TEXT" | "VARCHAR" | "CHAR" | "NAME" => "String",
+            "BYTEA" => "Vec<u8>",
+            _ => return None,
+        },
+        "mysql" => match type_info {
+            "BOOLEAN" => "bool",
+            "TINYINT" => "i8",
+            "SMALLINT" => "i16",
+            "INT" | "MEDIUMINT" => "i32",
+            "BIGINT" => "i64",
+            "TINYINT UNSIGNED" => "u8",
+            "SMALLINT UNSIGNED" => "u16",
+            "INT UNSIGNED" | "MEDIUMINT UNSIGNED" => "u32",
+            "BIGINT UNSIGNED" => "u64",
+            "FLOAT" => "f32",
+            "DOUBLE" => "f64",
+            "CHAR" | "VARCHAR" | "TEXT" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" => "String",
+            "BINARY" | "VARBINARY" | "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" => "Vec<u8>",
+            _ => return None,
+        },
+        "sqlite" => match type_info {
+            "BOOLEAN" => "bool",
+            "INTEGER" => "i64",
+            "REAL" | "NUMERIC" => "f64",
+            "TEXT" | "DATE" | "TIME" | "DATETIME" => "String",
+            "BLOB" => "Vec<u8>",
+            _ => return None,
+        },
+        "mssql" => match type_info {
+            "BIT" => "bool",
+            "TINYINT" => "i8",
+            "SMALLINT" => "i16",
+            "INT" => "i32",
+            "BIGINT" => "i64",
+            "REAL" => "f32",
+            "FLOAT" => "f64",
+            "CHAR" | "VARCHAR" | "NCHAR" | "NVARCHAR" | "TEXT" | "NTEXT" => "String",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(ty)
+}
+
+/// the rust type a bind parameter of `type_info` is checked against
+fn param_type(db: &str, type_info: &str) -> Option<&'static str> {
+    match column_type(db, type_info)? {
+        "String" => Some("str"),
+        "Vec<u8>" => Some("[u8]"),
+        ty => Some(ty),
+    }
+}
+
+fn parse_type(ty: &str) -> Result<Type, String> {
+    syn::parse_str(ty).map_err(|e| format!("invalid type `{}`: {}", ty, e))
+}
+
+pub(crate) fn expand_input(input: crate::proc_macro::TokenStream, output: Output) -> crate::proc_macro::TokenStream {
+    match syn::parse::Parser::parse(QueryInput::parser(output), input) {
+        Ok(input) => expand(input, output).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+pub(crate) fn expand(input: QueryInput, output: Output) -> TokenStream {
+    let span = input.sql.span();
+    let data = match QueryData::of(&input.sql.value()) {
+        Ok(v) => v,
+        Err(e) => return syn::Error::new(span, e).to_compile_error(),
+    };
+    match expand_with(input, output, &data) {
+        Ok(v) => v,
+        Err(e) => syn::Error::new(span, e).to_compile_error(),
+    }
+}
+
+pub(crate) fn expand_with(input: QueryInput, output: Output, data: &QueryData) -> Result<TokenStream, String> {
+    let db = db_path(&data.db)?;
+    let sql = &input.sql;
+    let describe = &data.describe;
+
+    // bind parameters
+    let expected = match &describe.parameters {
+        Some(ParametersData::Left(v)) => Some(v.len()),
+        Some(ParametersData::Right(n)) => Some(*n),
+        None => None,
+    };
+    if let Some(expected) = expected {
+        if expected != input.args.len() {
+            return Err(format!(
+                "expected {} parameters, got {}",
+                expected,
+                input.args.len()
+            ));
+        }
+    }
+    let mut checks = quote! {};
+    let mut binds = quote! {};
+    for (idx, arg) in input.args.iter().enumerate() {
+        // `expr as _` opts out of the type check
+        if let Expr::Cast(cast) = arg {
+            if let Type::Infer(_) = &*cast.ty {
+                let expr = &cast.expr;
+                binds = quote! { #binds .bind(#expr) };
+                continue;
+            }
+        }
+        let param_ty = match &describe.parameters {
+            Some(ParametersData::Left(v)) => param_type(&data.db, &v[idx]),
+            _ => None,
+        };
+        match param_ty {
+            Some(ty) => {
+                let ty = parse_type(ty)?;
+                checks = quote! {
+                    #checks
+                    if false {
+                        cdbc::utils::ty_match::same_as::<#ty, _>(&(#arg));
+                    }
+                };
+                binds = quote! { #binds .bind(#arg) };
+            }
+            None => {
+                binds = quote! { #binds .bind(#arg) };
+            }
+        }
+    }
+    let query = quote! { cdbc::query::<#db>(#sql) #binds };
+
+    if describe.columns.is_empty() {
+        if output != Output::Record {
+            return Err("the query returns no columns".to_string());
+        }
+        return Ok(quote! {{ #checks #query }});
+    }
+    if output == Output::Scalar && describe.columns.len() != 1 {
+        return Err(format!(
+            "query_scalar! expects exactly one column, the query returns {}",
+            describe.columns.len()
+        ));
+    }
+
+    // output columns
+    let mut fields = vec![];
+    let mut gets = vec![];
+    for (idx, column) in describe.columns.iter().enumerate() {
+        let column_override = ColumnOverride::parse(&column.name);
+        let ty = match &column_override.ty {
+            Some(ty) => ty.clone(),
+            None => match column_type(&data.db, &column.type_info) {
+                Some(ty) => ty.to_string(),
+                None => {
+                    return Err(format!(
+                        "unsupported type {} of column #{} (\"{}\"), override it with `AS \"{}: <Type>\"`",
+                        column.type_info,
+                        idx + 1,
+                        column_override.name,
+                        column_override.name
+                    ))
+                }
+            },
+        };
+        let not_null = match column_override.nullable {
+            Some(nullable) => !nullable,
+            None => describe.nullable.get(idx).cloned().flatten() == Some(false),
+        };
+        let ty = if not_null {
+            parse_type(&ty)?
+        } else {
+            parse_type(&format!("Option<{}>", ty))?
+        };
+        let name = syn::parse_str::<syn::Ident>(&column_override.name)
+            .map_err(|_| format!("column name `{}` is not a valid rust identifier, rename it with `AS`", column_override.name))?;
+        let var = format_ident!("v{}", idx, span = Span::call_site());
+        gets.push(quote! { let #var = row.try_get_unchecked::<#ty, _>(#idx)?; });
+        fields.push((name, var, ty));
+    }
+
+    let gets = quote! { #(#gets)* };
+    let mapper = match output {
+        Output::Scalar => {
+            let var = &fields[0].1;
+            quote! { #gets Ok(#var) }
+        }
+        Output::As => {
+            let record = input.record.as_ref().unwrap();
+            let inits = fields.iter().map(|(name, var, _)| quote! { #name: #var });
+            quote! { #gets Ok(#record { #(#inits),* }) }
+        }
+        Output::Record => {
+            let inits = fields.iter().map(|(name, var, _)| quote! { #name: #var });
+            quote! { #gets Ok(Record { #(#inits),* }) }
+        }
+    };
+    let record = if output == Output::Record {
+        let defs = fields.iter().map(|(name, _, ty)| quote! { pub #name: #ty });
+        quote! {
+            #[derive(Debug)]
+            struct Record {
+                #(#defs),*
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {{
+        #checks
+        #record
+        #query.try_map(|row: <#db as cdbc::database::Database>::Row| {
+            use cdbc::row::Row as _;
+            #mapper
+        })
+    }})
+}
+
+#[cfg(test)]
+mod test {
+    use crate::query::{expand_with, ColumnOverride, Output, QueryInput};
+    use crate::query_data::QueryData;
+
+    fn data(json: &str) -> QueryData {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_column_override() {
+        assert_eq!(
+            ColumnOverride::parse("id!"),
+            ColumnOverride { name: "id".to_string(), nullable: Some(false), ty: None }
+        );
+        assert_eq!(
+            ColumnOverride::parse("name?: MyName"),
+            ColumnOverride { name: "name".to_string(), nullable: Some(true), ty: Some("MyName".to_string()) }
+        );
+        assert_eq!(
+            ColumnOverride::parse("count"),
+            ColumnOverride { name: "count".to_string(), nullable: None, ty: None }
+        );
+    }
+
+    #[test]
+    fn test_check_parameters() {
+        let data = data(r#"{"db":"postgres","describe":{"columns":[{"name":"id","type_info":"INT8"}],"parameters":{"Left":["TEXT"]},"nullable":[false]}}"#);
+        let input: QueryInput = syn::parse::Parser::parse_str(QueryInput::parser(Output::Scalar), r#""select id from t where name = $1""#).unwrap();
+        let err = expand_with(input, Output::Scalar, &data).unwrap_err();
+        assert_eq!(err, "expected 1 parameters, got 0");
+
+        let input: QueryInput = syn::parse::Parser::parse_str(QueryInput::parser(Output::Scalar), r#""select id from t where name = $1", name"#).unwrap();
+        let stream = expand_with(input, Output::Scalar, &data).unwrap().to_string();
+        assert!(stream.contains("same_as :: < str , _ >"));
+        assert!(stream.contains("try_get_unchecked :: < i64 , _ >"));
+    }
+
+    #[test]
+    fn test_unsupported_column() {
+        let data = data(r#"{"db":"sqlite","describe":{"columns":[{"name":"at","type_info":"JSON"}],"parameters":{"Right":0},"nullable":[null]}}"#);
+        let input: QueryInput = syn::parse::Parser::parse_str(QueryInput::parser(Output::Record), r#""select at from t""#).unwrap();
+        assert!(expand_with(input, Output::Record, &data).is_err());
+    }
+}
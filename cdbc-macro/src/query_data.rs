@@ -0,0 +1,246 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+/// the offline cache, a directory next to `Cargo.toml` with one `query-<db>-<hash>.json` per query
+pub(crate) const DATA_DIR: &str = ".cdbc";
+
+/// The description of a query, as reported by `Executor::describe` with the types replaced
+/// by their names (so the macros do not need to link any database driver).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct QueryData {
+    /// `postgres`, `mysql`, `sqlite` or `mssql`
+    pub db: String,
+    pub describe: DescribeData,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DescribeData {
+    pub columns: Vec<ColumnData>,
+    pub parameters: Option<ParametersData>,
+    pub nullable: Vec<Option<bool>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ColumnData {
+    pub name: String,
+    pub type_info: String,
+}
+
+/// same layout as the serialized `Either<Vec<TypeInfo>, usize>` of `Describe`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ParametersData {
+    Left(Vec<String>),
+    Right(usize),
+}
+
+/// a file of the cache, the sql is kept to tell hash collisions apart
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    sql: String,
+    #[serde(flatten)]
+    data: QueryData,
+}
+
+impl QueryData {
+    /// Describes `sql`.
+    ///
+    /// When `DATABASE_URL` is set (and `CDBC_OFFLINE` is not `true`) the query is described by the
+    /// database through the `cdbc-describe` tool (`cargo install cdbc-any`, or point
+    /// `CDBC_DESCRIBE` at the binary) and the result is saved to `.cdbc/query-<db>-<hash>.json`.
+    /// Otherwise the description is read from `.cdbc`, so builds work without a database.
+    pub(crate) fn of(sql: &str) -> Result<QueryData, String> {
+        let offline = std::env::var("CDBC_OFFLINE")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        let dir = Self::data_dir()?;
+
+        match std::env::var("DATABASE_URL") {
+            Ok(url) if !offline => {
+                let bin = std::env::var("CDBC_DESCRIBE").unwrap_or_else(|_| "cdbc-describe".to_string());
+                let data = Self::describe(&bin, &url, sql)?;
+                save(&dir, sql, &data)?;
+                Ok(data)
+            }
+            url => load(&dir, sql, url.ok().as_deref().and_then(db_of_url)),
+        }
+    }
+
+    /// describe `sql` with the `bin` tool
+    fn describe(bin: &str, url: &str, sql: &str) -> Result<QueryData, String> {
+        let mut child = Command::new(bin)
+            .env("DATABASE_URL", url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => format!(
+                    "`{}` was not found, it describes the checked queries against `DATABASE_URL`: \
+                    install it with `cargo install cdbc-any` (or set `CDBC_DESCRIBE` to its path), \
+                    or set `CDBC_OFFLINE=true` to check them with the descriptions saved in {}",
+                    bin, DATA_DIR
+                ),
+                _ => format!(
+                    "failed to run `{}`: {}; install it with `cargo install cdbc-any`, \
+                    or set `CDBC_OFFLINE=true` to build from {}",
+                    bin, e, DATA_DIR
+                ),
+            })?;
+
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(sql.as_bytes())
+            .map_err(|e| e.to_string())?;
+
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(format!(
+                "error describing query: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())
+    }
+
+    fn data_dir() -> Result<PathBuf, String> {
+        let dir = std::env::var("CARGO_MANIFEST_DIR")
+            .map_err(|_| "`CARGO_MANIFEST_DIR` must be set".to_string())?;
+        Ok(PathBuf::from(dir).join(DATA_DIR))
+    }
+}
+
+/// the database of the scheme of `url`, as named by `cdbc-describe`
+fn db_of_url(url: &str) -> Option<&'static str> {
+    Some(match url.split(':').next()? {
+        "postgres" | "postgresql" => "postgres",
+        "mysql" | "mariadb" => "mysql",
+        "sqlite" => "sqlite",
+        "mssql" | "sqlserver" => "mssql",
+        _ => return None,
+    })
+}
+
+/// FNV-1a, the file names must not change between compiler versions like `DefaultHasher` may
+fn hash(sql: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in sql.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn file_name(db: &str, sql: &str) -> String {
+    format!("query-{}-{}.json", db, hash(sql))
+}
+
+fn read(path: &Path, sql: &str) -> Result<Option<QueryData>, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("failed to read {:?}: {}", path, e))?;
+    let saved: Saved = serde_json::from_str(&json).map_err(|e| format!("failed to parse {:?}: {}", path, e))?;
+    Ok(if saved.sql == sql { Some(saved.data) } else { None })
+}
+
+/// the description of `sql` for `db`, or for the only database it was described for when `db`
+/// isn't known
+fn load(dir: &Path, sql: &str, db: Option<&str>) -> Result<QueryData, String> {
+    let not_found = || {
+        format!(
+            "`DATABASE_URL` is not set and the query is not in {}; \
+            build once with `DATABASE_URL` set to save it",
+            DATA_DIR
+        )
+    };
+    if let Some(db) = db {
+        let path = dir.join(file_name(db, sql));
+        if !path.exists() {
+            return Err(not_found());
+        }
+        return read(&path, sql)?.ok_or_else(not_found);
+    }
+    let suffix = format!("-{}.json", hash(sql));
+    let mut found: Vec<QueryData> = vec![];
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("query-") && name.ends_with(&suffix) {
+                found.extend(read(&entry.path(), sql)?);
+            }
+        }
+    }
+    match found.len() {
+        0 => Err(not_found()),
+        1 => Ok(found.remove(0)),
+        _ => Err(format!(
+            "the query is described for several databases in {}, \
+            set `DATABASE_URL` to the one to check against and `CDBC_OFFLINE=true`",
+            DATA_DIR
+        )),
+    }
+}
+
+/// write the description to a temp file and rename it into place, so the lib, tests and examples
+/// of a package compiling at the same time never read a half written file
+fn save(dir: &Path, sql: &str, data: &QueryData) -> Result<(), String> {
+    let path = dir.join(file_name(&data.db, sql));
+    if path.exists() && read(&path, sql).ok().flatten().as_ref() == Some(data) {
+        return Ok(());
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("failed to create {:?}: {}", dir, e))?;
+    let saved = Saved { sql: sql.to_string(), data: data.clone() };
+    let json = serde_json::to_string_pretty(&saved).map_err(|e| e.to_string())?;
+    let tmp = dir.join(format!(".{}.{}.tmp", file_name(&data.db, sql), std::process::id()));
+    fs::write(&tmp, json).map_err(|e| format!("failed to write {:?}: {}", tmp, e))?;
+    fs::rename(&tmp, &path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("failed to write {:?}: {}", path, e)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::query_data::{file_name, load, save, QueryData};
+
+    fn data(db: &str) -> QueryData {
+        serde_json::from_str(&format!(
+            r#"{{"db":"{}","describe":{{"columns":[],"parameters":{{"Right":1}},"nullable":[]}}}}"#,
+            db
+        )).unwrap()
+    }
+
+    #[test]
+    fn test_save_load() {
+        let dir = std::env::temp_dir().join(format!("cdbc-query-data-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let sql = "select ?";
+        assert!(load(&dir, sql, None).is_err());
+
+        save(&dir, sql, &data("sqlite")).unwrap();
+        assert!(dir.join(file_name("sqlite", sql)).exists());
+        assert_eq!(load(&dir, sql, None).unwrap(), data("sqlite"));
+        assert_eq!(load(&dir, sql, Some("sqlite")).unwrap(), data("sqlite"));
+        assert!(load(&dir, sql, Some("postgres")).is_err());
+        assert!(load(&dir, "select 1", None).is_err());
+
+        // the same sql for another database is kept apart
+        save(&dir, sql, &data("postgres")).unwrap();
+        assert_eq!(load(&dir, sql, Some("postgres")).unwrap(), data("postgres"));
+        assert!(load(&dir, sql, None).unwrap_err().contains("several databases"));
+        // no temp file is left behind
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_describe_without_tool() {
+        let err = QueryData::describe("/nonexistent/cdbc-describe", "sqlite::memory:", "select 1").unwrap_err();
+        assert!(err.starts_with("`/nonexistent/cdbc-describe` was not found"), "{}", err);
+        assert!(err.contains("cargo install cdbc-any") && err.contains("CDBC_OFFLINE=true"), "{}", err);
+    }
+}
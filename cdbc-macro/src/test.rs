@@ -0,0 +1,116 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{AttributeArgs, ItemFn, Lit, Meta, NestedMeta};
+
+struct TestAttr {
+    fixtures: Vec<String>,
+    template: Option<String>,
+}
+
+fn parse_args(args: AttributeArgs) -> syn::Result<TestAttr> {
+    let mut attr = TestAttr {
+        fixtures: vec![],
+        template: None,
+    };
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("fixtures") => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Lit(Lit::Str(file)) => attr.fixtures.push(file.value()),
+                        other => {
+                            return Err(syn::Error::new_spanned(other, "expected a file name, e.g. `fixtures(\"users.sql\")`"))
+                        }
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("template") => match nv.lit {
+                Lit::Str(v) => attr.template = Some(v.value()),
+                other => return Err(syn::Error::new_spanned(other, "expected a database name")),
+            },
+            other => {
+                return Err(syn::Error::new_spanned(other, "expected `fixtures(\"..\")` or `template = \"..\"`"))
+            }
+        }
+    }
+    Ok(attr)
+}
+
+/// `#[cdbc::test]` keeps the test fn as an inner fn and calls it from a `#[test]`
+/// through `cdbc::testing::run_test`, which infers the database from the pool argument.
+pub(crate) fn expand(args: AttributeArgs, input: ItemFn) -> syn::Result<TokenStream> {
+    let attr = parse_args(args)?;
+    let name = &input.sig.ident;
+    let attrs = &input.attrs;
+    let inner = ItemFn {
+        attrs: vec![],
+        ..input.clone()
+    };
+
+    let fixtures = attr.fixtures.iter().map(|file| quote!((#file, include_str!(#file))));
+    let template = attr.template.as_ref().map(|t| quote!(.template(#t)));
+    let args = quote! {
+        cdbc::testing::TestArgs::new(concat!(module_path!(), "::", stringify!(#name)))
+            #template
+            .fixtures(&[#(#fixtures),*])
+    };
+    let run = match input.sig.inputs.len() {
+        0 => {
+            if !attr.fixtures.is_empty() || attr.template.is_some() {
+                return Err(syn::Error::new_spanned(&input.sig, "fixtures and template need a pool argument, e.g. `fn test(pool: Pool<Postgres>)`"));
+            }
+            quote!(cdbc::testing::run_test_body(#args, #name))
+        }
+        1 => quote!(cdbc::testing::run_test(#args, #name)),
+        _ => return Err(syn::Error::new_spanned(&input.sig.inputs, "expected no argument or one `Pool<DB>` argument")),
+    };
+    Ok(quote! {
+        #[::core::prelude::v1::test]
+        #(#attrs)*
+        fn #name() {
+            #inner
+            #run
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse::Parser;
+    use syn::punctuated::Punctuated;
+    use syn::{NestedMeta, Token};
+    use crate::test::expand;
+
+    fn expand_str(args: &str, input: &str) -> syn::Result<String> {
+        let args = Punctuated::<NestedMeta, Token![,]>::parse_terminated.parse_str(args).unwrap();
+        expand(args.into_iter().collect(), syn::parse_str(input).unwrap()).map(|v| v.to_string())
+    }
+
+    #[test]
+    fn test_expand_fixtures_and_template() {
+        let tokens = expand_str(
+            "fixtures(\"users.sql\", \"roles.sql\"), template = \"base\"",
+            "fn t(pool: Pool<Postgres>) {}",
+        )
+        .unwrap();
+        assert!(tokens.contains(". template (\"base\")"), "{}", tokens);
+        assert!(
+            tokens.contains(". fixtures (& [(\"users.sql\" , include_str ! (\"users.sql\")) , (\"roles.sql\" , include_str ! (\"roles.sql\"))])"),
+            "{}",
+            tokens
+        );
+        assert!(tokens.contains("cdbc :: testing :: run_test ("), "{}", tokens);
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let err = |args: &str, input: &str| expand_str(args, input).unwrap_err().to_string();
+        assert_eq!(
+            err("fixtures(\"users.sql\")", "fn t() {}"),
+            "fixtures and template need a pool argument, e.g. `fn test(pool: Pool<Postgres>)`"
+        );
+        assert_eq!(err("fixtures(users)", "fn t(pool: Pool<Postgres>) {}"), "expected a file name, e.g. `fixtures(\"users.sql\")`");
+        assert_eq!(err("template = 1", "fn t(pool: Pool<Postgres>) {}"), "expected a database name");
+        assert_eq!(err("timeout = 1", "fn t(pool: Pool<Postgres>) {}"), "expected `fixtures(\"..\")` or `template = \"..\"`");
+    }
+}
@@ -0,0 +1,47 @@
+use cdbc::arguments::Arguments;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::types::Type;
+use crate::{MockDatabase, MockValue};
+
+/// Implementation of [`Arguments`] for the [`Mock`](MockDatabase) driver.
+///
+/// The bound values are kept as [`MockValue`]s, to be matched against the arguments an
+/// [`Expectation`](crate::Expectation) was given.
+#[derive(Default, Debug, Clone)]
+pub struct MockArguments {
+    pub values: Vec<MockValue>,
+}
+
+impl MockArguments {
+    pub fn add<'q, T>(&mut self, value: T)
+    where
+        T: Encode<'q, MockDatabase> + Type<MockDatabase>,
+    {
+        if let IsNull::Yes = value.encode(&mut self.values) {
+            self.values.push(MockValue::Null);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<'q> Arguments<'q> for MockArguments {
+    type Database = MockDatabase;
+
+    fn reserve(&mut self, len: usize, _size_hint: usize) {
+        self.values.reserve(len);
+    }
+
+    fn add<T>(&mut self, value: T)
+    where
+        T: 'q + Encode<'q, Self::Database> + Type<MockDatabase>,
+    {
+        self.add(value)
+    }
+}
@@ -0,0 +1,26 @@
+use cdbc::column::Column;
+use cdbc::utils::ustr::UStr;
+use crate::{MockDatabase, MockTypeInfo};
+
+#[derive(Debug, Clone)]
+pub struct MockColumn {
+    pub name: UStr,
+    pub ordinal: usize,
+    pub type_info: MockTypeInfo,
+}
+
+impl Column for MockColumn {
+    type Database = MockDatabase;
+
+    fn ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn type_info(&self) -> &MockTypeInfo {
+        &self.type_info
+    }
+}
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use cdbc::describe::Describe;
+use cdbc::error::Error;
+use cdbc::executor::{Execute, Executor};
+use cdbc::io::chan_stream::ChanStream;
+use either::Either;
+use crate::{MockConnection, MockDatabase, MockQueryResult, MockRow, MockStatement, MockTypeInfo};
+
+/// The number of `?` placeholders of `sql`, outside of quoted strings and identifiers.
+fn count_placeholders(sql: &str) -> usize {
+    let mut quote = None;
+    let mut count = 0;
+    for c in sql.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' || c == '`' => quote = Some(c),
+            None if c == '?' => count += 1,
+            None => {}
+        }
+    }
+    count
+}
+
+impl Executor for MockConnection {
+    type Database = MockDatabase;
+
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
+        mut query: E,
+    ) -> ChanStream<'e, Either<MockQueryResult, MockRow>>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let sql = query.sql().to_owned();
+        let arguments = query.take_arguments().map(|a| a.values).unwrap_or_default();
+        let mock = self.mock.clone();
+        // the statement runs when the stream is first pulled, like it does on a database
+        chan_stream! {
+            let (rows, result) = mock.run(&sql, arguments, true)?;
+            for row in rows {
+                r#yield!(Either::Right(row));
+            }
+            r#yield!(Either::Left(result));
+            end!();
+            Ok(())
+        }
+    }
+
+    fn fetch_optional<'q, E: 'q>(
+        &mut self,
+        mut query: E,
+    ) -> Result<Option<MockRow>, Error>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let arguments = query.take_arguments().map(|a| a.values).unwrap_or_default();
+        let (rows, _) = self.mock.run(query.sql(), arguments, true)?;
+        Ok(rows.into_iter().next())
+    }
+
+    fn prepare_with<'q>(
+        &mut self,
+        sql: &'q str,
+        _parameters: &[MockTypeInfo],
+    ) -> Result<MockStatement, Error> {
+        let (columns, column_names) = self.mock.columns(sql);
+        Ok(MockStatement {
+            sql: sql.to_owned(),
+            parameters: count_placeholders(sql),
+            columns,
+            column_names,
+        })
+    }
+
+    #[doc(hidden)]
+    fn describe(&mut self, sql: &str) -> Result<Describe<MockDatabase>, Error> {
+        let (columns, _) = self.mock.columns(sql);
+        Ok(Describe {
+            nullable: vec![None; columns.len()],
+            columns: columns.to_vec(),
+            parameters: Some(Either::Right(count_placeholders(sql))),
+        })
+    }
+}
+
+impl Executor for &mut MockConnection {
+    type Database = MockDatabase;
+
+    fn default_timeout(&self) -> Option<Duration> {
+        MockConnection::default_timeout(self)
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(&'e mut self, query: E) -> ChanStream<'e, Either<MockQueryResult, MockRow>>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        MockConnection::fetch_many(self, query)
+    }
+
+    fn fetch_optional<'q, E: 'q>(&mut self, query: E) -> Result<Option<MockRow>, Error>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        MockConnection::fetch_optional(self, query)
+    }
+
+    fn prepare_with<'q>(
+        &mut self,
+        sql: &'q str,
+        parameters: &'q [MockTypeInfo],
+    ) -> Result<MockStatement, Error> {
+        MockConnection::prepare_with(self, sql, parameters)
+    }
+
+    fn describe(&mut self, sql: &str) -> Result<Describe<MockDatabase>, Error> {
+        MockConnection::describe(self, sql)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::connection::executor::count_placeholders;
+
+    #[test]
+    fn test_count_placeholders() {
+        assert_eq!(count_placeholders("select 1"), 0);
+        assert_eq!(count_placeholders("select * from t where a = ? and b in (?, ?)"), 3);
+        assert_eq!(count_placeholders("select '?', \"a?\" from t where a = ?"), 1);
+    }
+}
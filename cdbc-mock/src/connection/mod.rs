@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::transaction::{Transaction, TransactionCallbacks, TransactionOptions};
+use crate::{Mock, MockConnectOptions};
+
+mod executor;
+
+/// A connection running its statements against a [`Mock`], opened with [`Mock::connect`].
+#[derive(Debug)]
+pub struct MockConnection {
+    pub(crate) mock: Mock,
+    pub(crate) transaction_depth: usize,
+    pub(crate) default_timeout: Option<Duration>,
+    pub(crate) transaction_callbacks: TransactionCallbacks,
+}
+
+impl MockConnection {
+    pub(crate) fn new(mock: Mock) -> Self {
+        Self {
+            mock,
+            transaction_depth: 0,
+            default_timeout: None,
+            transaction_callbacks: TransactionCallbacks::default(),
+        }
+    }
+
+    /// The mock this connection runs against.
+    pub fn mock(&self) -> &Mock {
+        &self.mock
+    }
+
+    /// Run a statement that does not need an expectation, e.g. `BEGIN`.
+    pub(crate) fn run_unscripted(&mut self, sql: &str) -> Result<(), Error> {
+        self.mock.run(sql, Vec::new(), false).map(|_| ())
+    }
+}
+
+impl Connection for MockConnection {
+    type Options = MockConnectOptions;
+
+    fn close(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn ping(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.default_timeout = timeout;
+    }
+
+    fn begin(&mut self) -> Result<Transaction<'_, Self::Database>, Error>
+        where
+            Self: Sized,
+    {
+        Transaction::begin(self)
+    }
+
+    fn begin_with(&mut self, options: TransactionOptions) -> Result<Transaction<'_, Self::Database>, Error>
+        where
+            Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
+    #[doc(hidden)]
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    #[doc(hidden)]
+    fn should_flush(&self) -> bool {
+        false
+    }
+
+    fn transaction_callbacks(&mut self) -> Option<&mut TransactionCallbacks> {
+        Some(&mut self.transaction_callbacks)
+    }
+}
@@ -0,0 +1,50 @@
+use cdbc::database::{Database, HasArguments, HasStatement, HasValueRef};
+use crate::{
+    MockArguments, MockColumn, MockConnection, MockQueryResult, MockRow, MockStatement,
+    MockTransactionManager, MockTypeInfo, MockValue, MockValueRef,
+};
+
+/// In-memory database driver for unit tests, answering the statements scripted on a
+/// [`Mock`](crate::Mock).
+#[derive(Debug)]
+pub struct MockDatabase;
+
+impl Database for MockDatabase {
+    type Connection = MockConnection;
+
+    type TransactionManager = MockTransactionManager;
+
+    type Row = MockRow;
+
+    type QueryResult = MockQueryResult;
+
+    type Column = MockColumn;
+
+    type TypeInfo = MockTypeInfo;
+
+    type Value = MockValue;
+
+    fn holder() -> &'static str {
+        "?"
+    }
+}
+
+impl<'r> HasValueRef<'r> for MockDatabase {
+    type Database = MockDatabase;
+
+    type ValueRef = MockValueRef<'r>;
+}
+
+impl<'q> HasArguments<'q> for MockDatabase {
+    type Database = MockDatabase;
+
+    type Arguments = MockArguments;
+
+    type ArgumentBuffer = Vec<MockValue>;
+}
+
+impl HasStatement for MockDatabase {
+    type Database = MockDatabase;
+
+    type Statement = MockStatement;
+}
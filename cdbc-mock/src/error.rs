@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+
+use cdbc::error::{DatabaseError, ErrorKind};
+
+/// A database error returned by an [`Expectation`](crate::Expectation), to test how the code
+/// handles the errors of the database.
+///
+/// ```
+/// use cdbc::error::ErrorKind;
+/// use cdbc_mock::MockDatabaseError;
+///
+/// let err = MockDatabaseError::new("duplicate key value violates unique constraint")
+///     .code("23505")
+///     .kind(ErrorKind::UniqueViolation)
+///     .constraint("user_name_key");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockDatabaseError {
+    message: String,
+    code: Option<String>,
+    kind: ErrorKind,
+    constraint: Option<String>,
+    table: Option<String>,
+    column: Option<String>,
+}
+
+impl MockDatabaseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code: None,
+            kind: ErrorKind::Other,
+            constraint: None,
+            table: None,
+            column: None,
+        }
+    }
+
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn constraint(mut self, constraint: impl Into<String>) -> Self {
+        self.constraint = Some(constraint.into());
+        self
+    }
+
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.table = Some(table.into());
+        self
+    }
+
+    pub fn column(mut self, column: impl Into<String>) -> Self {
+        self.column = Some(column.into());
+        self
+    }
+}
+
+impl Display for MockDatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(&self.message)
+    }
+}
+
+impl StdError for MockDatabaseError {}
+
+impl DatabaseError for MockDatabaseError {
+    #[inline]
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[inline]
+    fn code(&self) -> Option<Cow<'_, str>> {
+        self.code.as_deref().map(Cow::Borrowed)
+    }
+
+    #[doc(hidden)]
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    fn constraint(&self) -> Option<&str> {
+        self.constraint.as_deref()
+    }
+
+    fn table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+}
@@ -0,0 +1,89 @@
+//! **Mock** database driver: an in-memory [`MockDatabase`] for unit testing code written against
+//! cdbc, without running a database.
+//!
+//! A [`Mock`] is scripted with the statements the code under test is expected to run, every
+//! expectation answers with canned rows, a number of affected rows or an error. Connections
+//! opened from the mock (directly or through a [`MockPool`]) share its script, and record every
+//! statement they run so the test can assert on them afterwards.
+//!
+//! Queries are written with `?` placeholders.
+//!
+//! ```
+//! use cdbc::{Executor, Row};
+//! use cdbc_mock::{Mock, MockValue};
+//!
+//! let mock = Mock::new();
+//! mock.expect("select id, name from user where id = ?")
+//!     .with_args(vec![MockValue::from(1)])
+//!     .returns_rows(&["id", "name"], vec![vec![1.into(), "tom".into()]]);
+//! mock.expect("delete from user where id = ?")
+//!     .returns_affected(1);
+//!
+//! let mut conn = mock.connect();
+//! let row = cdbc::query("select id, name from user where id = ?")
+//!     .bind(1)
+//!     .fetch_one(&mut conn)
+//!     .unwrap();
+//! assert_eq!(row.get::<String, _>("name"), "tom");
+//! let done = cdbc::query("delete from user where id = ?")
+//!     .bind(1)
+//!     .execute(&mut conn)
+//!     .unwrap();
+//! assert_eq!(done.rows_affected(), 1);
+//!
+//! mock.assert_executed(&[
+//!     "select id, name from user where id = ?",
+//!     "delete from user where id = ?",
+//! ]);
+//! mock.verify();
+//! ```
+
+#[macro_use]
+pub extern crate cdbc;
+
+use cdbc::executor::Executor;
+
+mod arguments;
+mod column;
+mod connection;
+mod database;
+mod error;
+mod mock;
+mod options;
+mod query_result;
+mod row;
+mod statement;
+mod transaction;
+mod type_info;
+pub mod types;
+mod value;
+
+pub use arguments::MockArguments;
+pub use column::MockColumn;
+pub use connection::MockConnection;
+pub use database::MockDatabase;
+pub use error::MockDatabaseError;
+pub use mock::{ExecutedStatement, Expectation, Mock};
+pub use options::MockConnectOptions;
+pub use query_result::MockQueryResult;
+pub use row::MockRow;
+pub use statement::MockStatement;
+pub use transaction::MockTransactionManager;
+pub use type_info::MockTypeInfo;
+pub use value::{MockValue, MockValueRef};
+
+/// An alias for [`Pool`][cdbc::pool::Pool], specialized for the **Mock** driver.
+pub type MockPool = cdbc::pool::Pool<MockDatabase>;
+
+/// An alias for [`PoolOptions`][cdbc::pool::PoolOptions], specialized for the **Mock** driver.
+pub type MockPoolOptions = cdbc::pool::PoolOptions<MockDatabase>;
+
+/// An alias for [`Executor<'_, Database = MockDatabase>`][Executor].
+pub trait MockExecutor<'c>: Executor<Database = MockDatabase> {}
+impl<'c, T: Executor<Database = MockDatabase>> MockExecutor<'c> for T {}
+
+// NOTE: required due to the lack of lazy normalization
+impl_into_arguments_for_arguments!(MockArguments);
+impl_column_index_for_row!(MockRow);
+impl_column_index_for_statement!(MockStatement);
+impl_into_maybe_pool!(MockDatabase, MockConnection);
@@ -0,0 +1,532 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use cdbc::error::Error;
+use cdbc::utils::ustr::UStr;
+use cdbc::HashMap;
+use regex::Regex;
+use crate::{MockColumn, MockConnectOptions, MockConnection, MockQueryResult, MockRow, MockTypeInfo, MockValue};
+
+/// The script of a [`MockDatabase`](crate::MockDatabase) and the log of what ran against it.
+///
+/// A `Mock` is a cheap handle, its clones and every connection opened from it share the same
+/// expectations and log.
+///
+/// Each statement is answered by the first expectation, in the order they were added, that
+/// matches its SQL and arguments and has not been used up. A statement that matches no
+/// expectation fails with [`Error::Protocol`], except for the statements of transactions
+/// (`BEGIN`, `COMMIT`, `SAVEPOINT` ...) which succeed unless an expectation matches them.
+#[derive(Clone, Default)]
+pub struct Mock {
+    shared: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    expectations: Vec<ExpectationState>,
+    executed: Vec<ExecutedStatement>,
+}
+
+/// A statement that ran against a [`Mock`], with the arguments bound to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutedStatement {
+    pub sql: String,
+    pub arguments: Vec<MockValue>,
+}
+
+enum SqlPattern {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl SqlPattern {
+    fn matches(&self, sql: &str) -> bool {
+        match self {
+            SqlPattern::Exact(expected) => *expected == normalize(sql),
+            SqlPattern::Regex(regex) => regex.is_match(sql),
+        }
+    }
+}
+
+impl Debug for SqlPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlPattern::Exact(sql) => write!(f, "`{}`", sql),
+            SqlPattern::Regex(regex) => write!(f, "/{}/", regex),
+        }
+    }
+}
+
+struct ExpectationState {
+    pattern: SqlPattern,
+    arguments: Option<Vec<MockValue>>,
+    columns: Arc<Vec<MockColumn>>,
+    column_names: Arc<HashMap<UStr, usize>>,
+    rows: Vec<Vec<MockValue>>,
+    result: MockQueryResult,
+    error: Option<Arc<dyn Fn() -> Error + Send + Sync>>,
+    // `None` answers any number of statements
+    times: Option<usize>,
+    calls: usize,
+}
+
+impl ExpectationState {
+    fn new(pattern: SqlPattern) -> Self {
+        Self {
+            pattern,
+            arguments: None,
+            columns: Default::default(),
+            column_names: Default::default(),
+            rows: Vec::new(),
+            result: MockQueryResult::default(),
+            error: None,
+            times: Some(1),
+            calls: 0,
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        self.times.is_none_or(|times| self.calls < times)
+    }
+
+    fn matches(&self, sql: &str, arguments: &[MockValue]) -> bool {
+        self.is_pending()
+            && self.pattern.matches(sql)
+            && self.arguments.as_ref().is_none_or(|expected| expected == arguments)
+    }
+
+    fn rows(&self) -> Vec<MockRow> {
+        self.rows
+            .iter()
+            .map(|values| MockRow {
+                values: values.clone(),
+                columns: self.columns.clone(),
+                column_names: self.column_names.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Collapses the whitespace of `sql`, an exact expectation ignores line breaks and indentation.
+fn normalize(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl Mock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        // a failed assertion of another test thread must not hide the log
+        self.shared.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Expect a statement with exactly this SQL, ignoring differences in whitespace.
+    ///
+    /// The expectation answers a single statement with no rows, until it is given a response.
+    pub fn expect(&self, sql: &str) -> Expectation<'_> {
+        self.push(SqlPattern::Exact(normalize(sql)))
+    }
+
+    /// Expect a statement whose SQL matches the regular expression `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression.
+    pub fn expect_regex(&self, pattern: &str) -> Expectation<'_> {
+        let regex = Regex::new(pattern)
+            .unwrap_or_else(|e| panic!("invalid sql pattern /{}/: {}", pattern, e));
+        self.push(SqlPattern::Regex(regex))
+    }
+
+    fn push(&self, pattern: SqlPattern) -> Expectation<'_> {
+        let mut state = self.state();
+        state.expectations.push(ExpectationState::new(pattern));
+        Expectation {
+            mock: self,
+            index: state.expectations.len() - 1,
+        }
+    }
+
+    /// Open a connection running its statements against this mock.
+    pub fn connect(&self) -> MockConnection {
+        MockConnection::new(self.clone())
+    }
+
+    /// Options opening connections against this mock, e.g. for a [`MockPool`](crate::MockPool).
+    pub fn connect_options(&self) -> MockConnectOptions {
+        MockConnectOptions::new(self.clone())
+    }
+
+    /// The statements that ran so far, in order.
+    pub fn executed(&self) -> Vec<ExecutedStatement> {
+        self.state().executed.clone()
+    }
+
+    /// Forget the statements that ran so far, the expectations are kept.
+    pub fn clear_executed(&self) {
+        self.state().executed.clear();
+    }
+
+    /// Assert that exactly these statements ran, in this order, ignoring differences in whitespace.
+    ///
+    /// # Panics
+    ///
+    /// Panics if other statements ran.
+    pub fn assert_executed(&self, expected: &[&str]) {
+        let executed = self
+            .executed()
+            .into_iter()
+            .map(|statement| normalize(&statement.sql))
+            .collect::<Vec<_>>();
+        let expected = expected.iter().map(|sql| normalize(sql)).collect::<Vec<_>>();
+        if executed != expected {
+            panic!(
+                "unexpected statements ran\n expected: {:#?}\n executed: {:#?}",
+                expected, executed
+            );
+        }
+    }
+
+    /// Assert that every expectation answered as many statements as it expected.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the expectations that are still waiting for a statement.
+    pub fn verify(&self) {
+        let pending = self
+            .state()
+            .expectations
+            .iter()
+            .filter(|expectation| expectation.times.is_some_and(|times| expectation.calls < times))
+            .map(|expectation| {
+                format!(
+                    "{:?} ran {} of {} times",
+                    expectation.pattern,
+                    expectation.calls,
+                    expectation.times.unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>();
+        if !pending.is_empty() {
+            panic!("expected statements did not run: {:#?}", pending);
+        }
+    }
+
+    /// Run a statement: record it and answer it from the first matching expectation.
+    ///
+    /// A statement that does not need to be `scripted` succeeds without rows when no
+    /// expectation matches.
+    pub(crate) fn run(
+        &self,
+        sql: &str,
+        arguments: Vec<MockValue>,
+        scripted: bool,
+    ) -> Result<(Vec<MockRow>, MockQueryResult), Error> {
+        let mut state = self.state();
+        let found = state
+            .expectations
+            .iter()
+            .position(|expectation| expectation.matches(sql, &arguments));
+        let response = match found {
+            Some(index) => {
+                let expectation = &mut state.expectations[index];
+                expectation.calls += 1;
+                match &expectation.error {
+                    Some(error) => Err(error()),
+                    None => Ok((expectation.rows(), expectation.result.clone())),
+                }
+            }
+            None if scripted => Err(Error::Protocol(format!(
+                "unexpected statement `{}` with arguments {:?}",
+                normalize(sql),
+                arguments
+            ))),
+            None => Ok((Vec::new(), MockQueryResult::default())),
+        };
+        state.executed.push(ExecutedStatement {
+            sql: sql.to_owned(),
+            arguments,
+        });
+        response
+    }
+
+    /// The columns of the first expectation that `sql` matches, regardless of its arguments.
+    pub(crate) fn columns(&self, sql: &str) -> (Arc<Vec<MockColumn>>, Arc<HashMap<UStr, usize>>) {
+        self.state()
+            .expectations
+            .iter()
+            .find(|expectation| expectation.is_pending() && expectation.pattern.matches(sql))
+            .map(|expectation| (expectation.columns.clone(), expectation.column_names.clone()))
+            .unwrap_or_default()
+    }
+}
+
+impl Debug for Mock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("Mock")
+            .field("expectations", &state.expectations.len())
+            .field("executed", &state.executed)
+            .finish()
+    }
+}
+
+/// An expected statement of a [`Mock`], configured with the builder methods.
+///
+/// ```
+/// use cdbc_mock::{Mock, MockDatabaseError, MockValue};
+///
+/// let mock = Mock::new();
+/// mock.expect("insert into user (name) values (?)")
+///     .with_args(vec![MockValue::from("tom")])
+///     .returns_affected(1)
+///     .last_insert_id(7);
+/// mock.expect_regex(r"^select .* from user")
+///     .returns_rows(&["id"], vec![vec![7.into()]])
+///     .repeatedly();
+/// mock.expect("delete from user")
+///     .returns_error(|| MockDatabaseError::new("permission denied").into());
+/// ```
+pub struct Expectation<'m> {
+    mock: &'m Mock,
+    index: usize,
+}
+
+impl<'m> Expectation<'m> {
+    fn update(self, f: impl FnOnce(&mut ExpectationState)) -> Self {
+        f(&mut self.mock.state().expectations[self.index]);
+        self
+    }
+
+    /// Only match statements with exactly these arguments bound, by default any arguments match.
+    pub fn with_args(self, arguments: Vec<MockValue>) -> Self {
+        self.update(|e| e.arguments = Some(arguments))
+    }
+
+    /// Answer with these rows, every row has a value for each of the `columns`.
+    ///
+    /// The type of a column is taken from its first value that is not `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row does not have as many values as there are columns.
+    pub fn returns_rows(self, columns: &[&str], rows: Vec<Vec<MockValue>>) -> Self {
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(
+                row.len(),
+                columns.len(),
+                "row {} has {} values for {} columns",
+                i,
+                row.len(),
+                columns.len()
+            );
+        }
+        let columns = columns
+            .iter()
+            .enumerate()
+            .map(|(ordinal, name)| MockColumn {
+                name: UStr::new(name),
+                ordinal,
+                type_info: rows
+                    .iter()
+                    .map(|row| row[ordinal].type_info())
+                    .find(|ty| *ty != MockTypeInfo::Null)
+                    .unwrap_or(MockTypeInfo::Null),
+            })
+            .collect::<Vec<_>>();
+        let column_names = columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ordinal))
+            .collect();
+        self.update(|e| {
+            e.result.rows_affected = rows.len() as u64;
+            e.columns = Arc::new(columns);
+            e.column_names = Arc::new(column_names);
+            e.rows = rows;
+        })
+    }
+
+    /// Answer with no rows and this number of rows affected.
+    pub fn returns_affected(self, rows_affected: u64) -> Self {
+        self.update(|e| e.result.rows_affected = rows_affected)
+    }
+
+    /// Answer with this [`last_insert_id`](MockQueryResult::last_insert_id).
+    pub fn last_insert_id(self, id: i64) -> Self {
+        self.update(|e| e.result.last_insert_id = Some(id))
+    }
+
+    /// Fail with the error made by `error`, e.g. a [`MockDatabaseError`](crate::MockDatabaseError).
+    pub fn returns_error<F>(self, error: F) -> Self
+        where
+            F: Fn() -> Error + Send + Sync + 'static,
+    {
+        self.update(|e| e.error = Some(Arc::new(error)))
+    }
+
+    /// Answer this many statements, once by default.
+    pub fn times(self, times: usize) -> Self {
+        self.update(|e| e.times = Some(times))
+    }
+
+    /// Answer any number of statements, [`Mock::verify`] does not require it to run.
+    pub fn repeatedly(self) -> Self {
+        self.update(|e| e.times = None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::connection::Connection;
+    use cdbc::error::{Error, ErrorKind};
+    use cdbc::executor::Executor;
+    use cdbc::row::Row;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use crate::{ExecutedStatement, Mock, MockDatabaseError, MockPoolOptions, MockValue};
+
+    #[test]
+    fn test_rows_and_arguments() {
+        let mock = Mock::new();
+        mock.expect("select id, name from user where id = ?")
+            .with_args(vec![MockValue::from(1)])
+            .returns_rows(&["id", "name"], vec![vec![1.into(), "tom".into()]]);
+        mock.expect("select id, name from user where id = ?")
+            .with_args(vec![MockValue::from(2)])
+            .returns_rows(&["id", "name"], vec![vec![2.into(), MockValue::Null]]);
+
+        let mut conn = mock.connect();
+        let rows = cdbc::query("select id, name\n  from user where id = ?")
+            .bind(2)
+            .fetch_all(&mut conn)
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<i64, _>("id"), 2);
+        assert_eq!(rows[0].get::<Option<String>, _>(1), None);
+        let row = cdbc::query("select id, name from user where id = ?")
+            .bind(1)
+            .fetch_one(&mut conn)
+            .unwrap();
+        assert_eq!(row.get::<i32, _>(0), 1);
+        assert_eq!(row.get::<&str, _>("name"), "tom");
+        assert!(row.try_get::<i32, _>("name").is_err());
+
+        // both expectations are used up
+        let err = cdbc::query("select id, name from user where id = ?")
+            .bind(1)
+            .fetch_optional(&mut conn)
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol(_)), "{}", err);
+        assert_eq!(
+            mock.executed()[0],
+            ExecutedStatement {
+                sql: "select id, name\n  from user where id = ?".to_string(),
+                arguments: vec![MockValue::Int(2)],
+            }
+        );
+        assert_eq!(mock.executed().len(), 3);
+        mock.verify();
+    }
+
+    #[test]
+    fn test_affected_and_errors() {
+        let mock = Mock::new();
+        mock.expect_regex(r"^insert into user")
+            .returns_affected(1)
+            .last_insert_id(7)
+            .times(2);
+        mock.expect("delete from user").returns_error(|| {
+            MockDatabaseError::new("permission denied")
+                .code("42501")
+                .kind(ErrorKind::Other)
+                .into()
+        });
+        mock.expect("update user set name = ?")
+            .returns_error(|| MockDatabaseError::new("deadlock").kind(ErrorKind::Deadlock).into())
+            .repeatedly();
+
+        let mut conn = mock.connect();
+        for name in ["a", "b"] {
+            let done = cdbc::query("insert into user (name) values (?)")
+                .bind(name)
+                .execute(&mut conn)
+                .unwrap();
+            assert_eq!(done.rows_affected(), 1);
+            assert_eq!(done.last_insert_id(), Some(7));
+        }
+        let err = conn.execute("delete from user").unwrap_err();
+        assert_eq!(err.as_database_error().unwrap().code().as_deref(), Some("42501"));
+        for _ in 0..3 {
+            let err = cdbc::query("update user set name = ?")
+                .bind(None::<String>)
+                .execute(&mut conn)
+                .unwrap_err();
+            assert!(err.is_retryable());
+        }
+        assert_eq!(mock.executed()[3].arguments, vec![MockValue::Null]);
+        mock.verify();
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mock = Mock::new();
+        mock.expect("update account set balance = balance - 1").returns_affected(1);
+        mock.expect("update account set balance = balance + 1").returns_affected(1);
+
+        let pool = MockPoolOptions::new()
+            .connect_with(mock.connect_options())
+            .unwrap();
+        let mut conn = pool.acquire().unwrap();
+        let mut tx = conn.begin().unwrap();
+        tx.execute("update account set balance = balance - 1").unwrap();
+        let committed = Arc::new(AtomicUsize::new(0));
+        let mut sp = tx.savepoint("credit").unwrap();
+        let counter = committed.clone();
+        sp.on_commit(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        sp.rollback_to().unwrap();
+        sp.execute("update account set balance = balance + 1").unwrap();
+        let counter = committed.clone();
+        sp.on_commit(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        sp.release().unwrap();
+        tx.commit().unwrap();
+        assert_eq!(committed.load(Ordering::SeqCst), 1);
+
+        mock.assert_executed(&[
+            "BEGIN",
+            "update account set balance = balance - 1",
+            "SAVEPOINT \"credit\"",
+            "ROLLBACK TO SAVEPOINT \"credit\"",
+            "update account set balance = balance + 1",
+            "RELEASE SAVEPOINT \"credit\"",
+            "COMMIT",
+        ]);
+        mock.verify();
+    }
+
+    #[test]
+    fn test_failing_commit() {
+        let mock = Mock::new();
+        mock.expect("COMMIT")
+            .returns_error(|| MockDatabaseError::new("could not serialize").kind(ErrorKind::SerializationFailure).into());
+
+        let mut conn = mock.connect();
+        let mut tx = conn.begin().unwrap();
+        assert!(tx.commit().unwrap_err().is_retryable());
+        mock.assert_executed(&["BEGIN", "COMMIT"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected statements did not run")]
+    fn test_verify() {
+        let mock = Mock::new();
+        mock.expect("select 1").times(2);
+        mock.connect().execute("select 1").unwrap();
+        mock.verify();
+    }
+}
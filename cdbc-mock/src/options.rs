@@ -0,0 +1,47 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use cdbc::connection::ConnectOptions;
+use cdbc::error::Error;
+use crate::{Mock, MockConnection};
+
+/// Options opening connections against a [`Mock`], see [`Mock::connect_options`].
+///
+/// Parsing a `mock://` url gives options for a new, empty mock.
+#[derive(Debug, Clone)]
+pub struct MockConnectOptions {
+    pub(crate) mock: Mock,
+}
+
+impl MockConnectOptions {
+    pub fn new(mock: Mock) -> Self {
+        Self { mock }
+    }
+
+    /// The mock the connections run against.
+    pub fn mock(&self) -> &Mock {
+        &self.mock
+    }
+}
+
+impl FromStr for MockConnectOptions {
+    type Err = Error;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        if url.starts_with("mock:") {
+            Ok(Self::new(Mock::new()))
+        } else {
+            Err(Error::Configuration(
+                format!("unrecognized mock database url: {}", url).into(),
+            ))
+        }
+    }
+}
+
+impl ConnectOptions for MockConnectOptions {
+    type Connection = MockConnection;
+
+    fn connect(&self, _d: Duration) -> Result<MockConnection, Error> {
+        Ok(MockConnection::new(self.mock.clone()))
+    }
+}
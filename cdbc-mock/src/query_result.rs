@@ -0,0 +1,27 @@
+use std::iter::{Extend, IntoIterator};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MockQueryResult {
+    pub(crate) rows_affected: u64,
+    pub(crate) last_insert_id: Option<i64>,
+}
+
+impl MockQueryResult {
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    /// the id of the last inserted row, if the expectation was given one
+    pub fn last_insert_id(&self) -> Option<i64> {
+        self.last_insert_id
+    }
+}
+
+impl Extend<MockQueryResult> for MockQueryResult {
+    fn extend<T: IntoIterator<Item = MockQueryResult>>(&mut self, iter: T) {
+        for elem in iter {
+            self.rows_affected += elem.rows_affected;
+            self.last_insert_id = elem.last_insert_id.or(self.last_insert_id);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use cdbc::column::ColumnIndex;
+use cdbc::error::Error;
+use cdbc::row::Row;
+use cdbc::utils::ustr::UStr;
+use cdbc::HashMap;
+use crate::{MockColumn, MockDatabase, MockValue, MockValueRef};
+
+/// Implementation of [`Row`] for the [`Mock`](MockDatabase) driver, a canned row of an
+/// [`Expectation`](crate::Expectation).
+#[derive(Debug)]
+pub struct MockRow {
+    pub values: Vec<MockValue>,
+    pub columns: Arc<Vec<MockColumn>>,
+    pub column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl Row for MockRow {
+    type Database = MockDatabase;
+
+    fn columns(&self) -> &[MockColumn] {
+        &self.columns
+    }
+
+    fn try_get_raw<I>(&self, index: I) -> Result<MockValueRef<'_>, Error>
+        where
+            I: ColumnIndex<Self>,
+    {
+        let index = index.index(self)?;
+        Ok(MockValueRef {
+            value: &self.values[index],
+        })
+    }
+}
+
+impl ColumnIndex<MockRow> for &'_ str {
+    fn index(&self, row: &MockRow) -> Result<usize, Error> {
+        row.column_names
+            .get(*self)
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+            .copied()
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use cdbc::column::ColumnIndex;
+use cdbc::error::Error;
+use cdbc::statement::Statement;
+use cdbc::utils::ustr::UStr;
+use cdbc::HashMap;
+use either::Either;
+use crate::{MockArguments, MockColumn, MockDatabase, MockTypeInfo};
+
+/// Implementation of [`Statement`] for the [`Mock`](MockDatabase) driver.
+///
+/// It knows the number of `?` placeholders and the columns of the expectation the
+/// statement matched when it was prepared.
+#[derive(Debug, Clone)]
+pub struct MockStatement {
+    pub sql: String,
+    pub parameters: usize,
+    pub columns: Arc<Vec<MockColumn>>,
+    pub column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl Statement for MockStatement {
+    type Database = MockDatabase;
+
+    fn to_owned(&self) -> MockStatement {
+        self.clone()
+    }
+
+    fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    fn sql_mut(&mut self) -> &mut String {
+        &mut self.sql
+    }
+
+    fn parameters(&self) -> Option<Either<&[MockTypeInfo], usize>> {
+        Some(Either::Right(self.parameters))
+    }
+
+    fn columns(&self) -> &[MockColumn] {
+        &self.columns
+    }
+
+    impl_statement_query!(MockArguments);
+}
+
+impl ColumnIndex<MockStatement> for &'_ str {
+    fn index(&self, statement: &MockStatement) -> Result<usize, Error> {
+        statement
+            .column_names
+            .get(*self)
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+            .copied()
+    }
+}
@@ -0,0 +1,85 @@
+use std::borrow::Cow;
+
+use cdbc::error::Error;
+use cdbc::transaction::{
+    begin_ansi_transaction_sql, check_savepoint_options, commit_ansi_transaction_sql,
+    release_savepoint_ansi_sql, rollback_ansi_transaction_sql, rollback_to_savepoint_ansi_sql,
+    savepoint_ansi_sql, TransactionManager, TransactionOptions,
+};
+use crate::{MockConnection, MockDatabase};
+
+/// Implementation of [`TransactionManager`] for the [`Mock`](MockDatabase) driver.
+///
+/// The statements of a transaction are recorded like any other statement, but they don't
+/// need an expectation, see [`Mock`](crate::Mock).
+pub struct MockTransactionManager;
+
+impl TransactionManager for MockTransactionManager {
+    type Database = MockDatabase;
+
+    fn begin_with(conn: &mut MockConnection, options: &TransactionOptions) -> Result<(), Error> {
+        conn.run_unscripted(&begin_transaction_sql(conn.transaction_depth, options)?)?;
+        conn.transaction_depth += 1;
+        Ok(())
+    }
+
+    fn commit(conn: &mut MockConnection) -> Result<(), Error> {
+        if conn.transaction_depth > 0 {
+            conn.run_unscripted(&commit_ansi_transaction_sql(conn.transaction_depth))?;
+            conn.transaction_depth -= 1;
+        }
+        Ok(())
+    }
+
+    fn rollback(conn: &mut MockConnection) -> Result<(), Error> {
+        if conn.transaction_depth > 0 {
+            conn.run_unscripted(&rollback_ansi_transaction_sql(conn.transaction_depth))?;
+            conn.transaction_depth -= 1;
+        }
+        Ok(())
+    }
+
+    fn start_rollback(conn: &mut MockConnection) {
+        if conn.transaction_depth > 0 {
+            conn.run_unscripted(&rollback_ansi_transaction_sql(conn.transaction_depth)).ok();
+            conn.transaction_depth -= 1;
+        }
+    }
+
+    fn savepoint(conn: &mut MockConnection, name: &str) -> Result<(), Error> {
+        conn.run_unscripted(&savepoint_ansi_sql(name))
+    }
+
+    fn release_savepoint(conn: &mut MockConnection, name: &str) -> Result<(), Error> {
+        conn.run_unscripted(&release_savepoint_ansi_sql(name))
+    }
+
+    fn rollback_to_savepoint(conn: &mut MockConnection, name: &str) -> Result<(), Error> {
+        conn.run_unscripted(&rollback_to_savepoint_ansi_sql(name))
+    }
+}
+
+/// `BEGIN ISOLATION LEVEL .. READ ONLY DEFERRABLE`, or a savepoint.
+fn begin_transaction_sql(
+    depth: usize,
+    options: &TransactionOptions,
+) -> Result<Cow<'static, str>, Error> {
+    check_savepoint_options(depth, options)?;
+    if depth > 0 || options.is_default() {
+        return Ok(begin_ansi_transaction_sql(depth));
+    }
+
+    let mut sql = String::from("BEGIN");
+    if let Some(level) = options.isolation_level {
+        sql.push_str(" ISOLATION LEVEL ");
+        sql.push_str(level.as_sql());
+    }
+    if let Some(mode) = options.access_mode {
+        sql.push(' ');
+        sql.push_str(mode.as_sql());
+    }
+    if options.deferrable {
+        sql.push_str(" DEFERRABLE");
+    }
+    Ok(Cow::Owned(sql))
+}
@@ -0,0 +1,37 @@
+use std::fmt::{self, Display, Formatter};
+
+use cdbc::type_info::TypeInfo;
+
+/// Type information for the [`Mock`](crate::MockDatabase) driver, one per kind of [`MockValue`](crate::MockValue).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MockTypeInfo {
+    Null,
+    Bool,
+    Int,
+    Float,
+    Text,
+    Blob,
+}
+
+impl Display for MockTypeInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl TypeInfo for MockTypeInfo {
+    fn is_null(&self) -> bool {
+        matches!(self, MockTypeInfo::Null)
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            MockTypeInfo::Null => "NULL",
+            MockTypeInfo::Bool => "BOOLEAN",
+            MockTypeInfo::Int => "INTEGER",
+            MockTypeInfo::Float => "REAL",
+            MockTypeInfo::Text => "TEXT",
+            MockTypeInfo::Blob => "BLOB",
+        }
+    }
+}
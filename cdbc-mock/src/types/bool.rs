@@ -0,0 +1,33 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{MockDatabase, MockTypeInfo, MockValue, MockValueRef};
+
+impl Type<MockDatabase> for bool {
+    fn type_info() -> MockTypeInfo {
+        MockTypeInfo::Bool
+    }
+
+    fn compatible(ty: &MockTypeInfo) -> bool {
+        matches!(ty, MockTypeInfo::Bool | MockTypeInfo::Int)
+    }
+}
+
+impl<'q> Encode<'q, MockDatabase> for bool {
+    fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Bool(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MockDatabase> for bool {
+    fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.value() {
+            MockValue::Bool(v) => Ok(*v),
+            MockValue::Int(v) => Ok(*v != 0),
+            other => Err(other.mismatch("bool")),
+        }
+    }
+}
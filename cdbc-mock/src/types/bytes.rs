@@ -0,0 +1,54 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{MockDatabase, MockTypeInfo, MockValue, MockValueRef};
+
+impl Type<MockDatabase> for [u8] {
+    fn type_info() -> MockTypeInfo {
+        MockTypeInfo::Blob
+    }
+}
+
+impl<'q> Encode<'q, MockDatabase> for &'q [u8] {
+    fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Blob(self.to_vec()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MockDatabase> for &'r [u8] {
+    fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.value() {
+            MockValue::Blob(v) => Ok(v.as_slice()),
+            other => Err(other.mismatch("&[u8]")),
+        }
+    }
+}
+
+impl Type<MockDatabase> for Vec<u8> {
+    fn type_info() -> MockTypeInfo {
+        <&[u8] as Type<MockDatabase>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, MockDatabase> for Vec<u8> {
+    fn encode(self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Blob(self));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Blob(self.clone()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MockDatabase> for Vec<u8> {
+    fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+        <&[u8] as Decode<MockDatabase>>::decode(value).map(ToOwned::to_owned)
+    }
+}
@@ -0,0 +1,61 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{MockDatabase, MockTypeInfo, MockValue, MockValueRef};
+
+fn float_decode(value: &MockValue, rust_type: &str) -> Result<f64, BoxDynError> {
+    match value {
+        MockValue::Float(v) => Ok(*v),
+        MockValue::Int(v) => Ok(*v as f64),
+        other => Err(other.mismatch(rust_type)),
+    }
+}
+
+impl Type<MockDatabase> for f32 {
+    fn type_info() -> MockTypeInfo {
+        MockTypeInfo::Float
+    }
+
+    fn compatible(ty: &MockTypeInfo) -> bool {
+        matches!(ty, MockTypeInfo::Float | MockTypeInfo::Int)
+    }
+}
+
+impl<'q> Encode<'q, MockDatabase> for f32 {
+    fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Float(*self as f64));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MockDatabase> for f32 {
+    fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(float_decode(value.value(), "f32")? as f32)
+    }
+}
+
+impl Type<MockDatabase> for f64 {
+    fn type_info() -> MockTypeInfo {
+        MockTypeInfo::Float
+    }
+
+    fn compatible(ty: &MockTypeInfo) -> bool {
+        matches!(ty, MockTypeInfo::Float | MockTypeInfo::Int)
+    }
+}
+
+impl<'q> Encode<'q, MockDatabase> for f64 {
+    fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Float(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MockDatabase> for f64 {
+    fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+        float_decode(value.value(), "f64")
+    }
+}
@@ -0,0 +1,47 @@
+use std::convert::TryInto;
+
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{MockDatabase, MockTypeInfo, MockValue, MockValueRef};
+
+fn int_decode(value: &MockValue, rust_type: &str) -> Result<i64, BoxDynError> {
+    match value {
+        MockValue::Int(v) => Ok(*v),
+        MockValue::Bool(v) => Ok(*v as i64),
+        other => Err(other.mismatch(rust_type)),
+    }
+}
+
+macro_rules! impl_int {
+    ($($T:ty),*) => {
+        $(
+            impl Type<MockDatabase> for $T {
+                fn type_info() -> MockTypeInfo {
+                    MockTypeInfo::Int
+                }
+
+                fn compatible(ty: &MockTypeInfo) -> bool {
+                    matches!(ty, MockTypeInfo::Int | MockTypeInfo::Bool)
+                }
+            }
+
+            impl<'q> Encode<'q, MockDatabase> for $T {
+                fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+                    buf.push(MockValue::Int(*self as i64));
+
+                    IsNull::No
+                }
+            }
+
+            impl<'r> Decode<'r, MockDatabase> for $T {
+                fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+                    Ok(int_decode(value.value(), stringify!($T))?.try_into()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(i8, i16, i32, i64, u8, u16, u32);
@@ -0,0 +1,21 @@
+//! Conversions between Rust and SQL types for the [`Mock`](crate::MockDatabase) driver.
+//!
+//! | Rust type                             | [`MockValue`](crate::MockValue)                     |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `bool`                                | `Bool`                                               |
+//! | `i8`, `i16`, `i32`, `i64`             | `Int`                                                |
+//! | `u8`, `u16`, `u32`                    | `Int`                                                |
+//! | `f32`, `f64`                          | `Float`                                              |
+//! | `&str`, `String`                      | `Text`                                               |
+//! | `&[u8]`, `Vec<u8>`                    | `Blob`                                               |
+//!
+//! ### Nullable
+//!
+//! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
+//! a potentially `NULL` value from the database.
+
+mod bool;
+mod bytes;
+mod float;
+mod int;
+mod str;
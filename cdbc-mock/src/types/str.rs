@@ -0,0 +1,54 @@
+use cdbc::decode::Decode;
+use cdbc::encode::{Encode, IsNull};
+use cdbc::error::BoxDynError;
+use cdbc::types::Type;
+use crate::{MockDatabase, MockTypeInfo, MockValue, MockValueRef};
+
+impl Type<MockDatabase> for str {
+    fn type_info() -> MockTypeInfo {
+        MockTypeInfo::Text
+    }
+}
+
+impl<'q> Encode<'q, MockDatabase> for &'q str {
+    fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Text((*self).to_owned()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MockDatabase> for &'r str {
+    fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.value() {
+            MockValue::Text(v) => Ok(v.as_str()),
+            other => Err(other.mismatch("&str")),
+        }
+    }
+}
+
+impl Type<MockDatabase> for String {
+    fn type_info() -> MockTypeInfo {
+        <&str as Type<MockDatabase>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, MockDatabase> for String {
+    fn encode(self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Text(self));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<MockValue>) -> IsNull {
+        buf.push(MockValue::Text(self.clone()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MockDatabase> for String {
+    fn decode(value: MockValueRef<'r>) -> Result<Self, BoxDynError> {
+        <&str as Decode<MockDatabase>>::decode(value).map(ToOwned::to_owned)
+    }
+}
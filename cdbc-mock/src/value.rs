@@ -0,0 +1,124 @@
+use std::borrow::Cow;
+
+use cdbc::error::{BoxDynError, UnexpectedNullError};
+use cdbc::value::{Value, ValueRef};
+use crate::{MockDatabase, MockTypeInfo};
+
+/// A value of a canned row or of a bound argument.
+///
+/// Every integer is kept as an `i64` and every float as an `f64`, they are converted back
+/// to the requested Rust type when decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl MockValue {
+    pub fn type_info(&self) -> MockTypeInfo {
+        match self {
+            MockValue::Null => MockTypeInfo::Null,
+            MockValue::Bool(_) => MockTypeInfo::Bool,
+            MockValue::Int(_) => MockTypeInfo::Int,
+            MockValue::Float(_) => MockTypeInfo::Float,
+            MockValue::Text(_) => MockTypeInfo::Text,
+            MockValue::Blob(_) => MockTypeInfo::Blob,
+        }
+    }
+
+    /// The error returned when this value cannot be decoded as `rust_type`.
+    pub(crate) fn mismatch(&self, rust_type: &str) -> BoxDynError {
+        match self {
+            MockValue::Null => UnexpectedNullError.into(),
+            _ => format!(
+                "mismatched types; Rust type `{}` is not compatible with SQL type `{}`",
+                rust_type,
+                self.type_info()
+            )
+            .into(),
+        }
+    }
+}
+
+macro_rules! impl_from_for_mock_value {
+    ($($T:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$T> for MockValue {
+                fn from(v: $T) -> Self {
+                    MockValue::$variant(v.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_mock_value!(
+    bool => Bool,
+    i8 => Int,
+    i16 => Int,
+    i32 => Int,
+    i64 => Int,
+    u8 => Int,
+    u16 => Int,
+    u32 => Int,
+    f32 => Float,
+    f64 => Float,
+    String => Text,
+    &str => Text,
+    Vec<u8> => Blob,
+    &[u8] => Blob,
+);
+
+impl<T: Into<MockValue>> From<Option<T>> for MockValue {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(MockValue::Null, Into::into)
+    }
+}
+
+/// Implementation of [`ValueRef`] for the [`Mock`](MockDatabase) driver.
+#[derive(Debug, Clone)]
+pub struct MockValueRef<'r> {
+    pub(crate) value: &'r MockValue,
+}
+
+impl<'r> MockValueRef<'r> {
+    pub fn value(&self) -> &'r MockValue {
+        self.value
+    }
+}
+
+impl Value for MockValue {
+    type Database = MockDatabase;
+
+    fn as_ref(&self) -> MockValueRef<'_> {
+        MockValueRef { value: self }
+    }
+
+    fn type_info(&self) -> Cow<'_, MockTypeInfo> {
+        Cow::Owned(MockValue::type_info(self))
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self, MockValue::Null)
+    }
+}
+
+impl<'r> ValueRef<'r> for MockValueRef<'r> {
+    type Database = MockDatabase;
+
+    fn to_owned(&self) -> MockValue {
+        self.value.clone()
+    }
+
+    fn type_info(&self) -> Cow<'_, MockTypeInfo> {
+        Cow::Owned(self.value.type_info())
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.value, MockValue::Null)
+    }
+}
@@ -0,0 +1,46 @@
+use cdbc::error::Error;
+use cdbc_mock::Mock;
+
+#[test]
+fn test_bind_list() {
+    let mock = Mock::new();
+    mock.expect("select * from user where status = ? and id in (?, ?) and version = ?")
+        .with_args(vec![1.into(), 7.into(), 8.into(), 0.into()])
+        .returns_rows(&["id"], vec![vec![7.into()], vec![8.into()]]);
+    mock.expect("select * from user where id in (NULL)");
+
+    let mut conn = mock.connect();
+    let rows = cdbc::query("select * from user where status = ? and id in (?) and version = ?")
+        .bind(1)
+        .bind_list(vec![7, 8])
+        .bind(0)
+        .fetch_all(&mut conn)
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    cdbc::query("select * from user where id in (?)")
+        .bind_list(Vec::<i64>::new())
+        .fetch_all(&mut conn)
+        .unwrap();
+    mock.verify();
+}
+
+#[test]
+fn test_bind_list_without_placeholder() {
+    let mock = Mock::new();
+    let mut conn = mock.connect();
+    let err = cdbc::query("select * from user where id in (?)")
+        .bind(1)
+        .bind_list(vec![7, 8])
+        .execute(&mut conn)
+        .unwrap_err();
+    assert!(matches!(err, Error::Configuration(_)), "{}", err);
+    assert!(err.to_string().contains("bind_list: no placeholder for the bind parameter 2"), "{}", err);
+
+    let mut rows = cdbc::query_as::<_, (i64,)>("select * from user where id in (1)")
+        .bind_list(vec![7, 8])
+        .fetch(&mut conn);
+    let err = rows.next().unwrap().unwrap_err();
+    assert!(err.to_string().contains("no placeholder for the bind parameter 1"), "{}", err);
+    // nothing reached the database
+    assert!(mock.executed().is_empty());
+}
@@ -0,0 +1,214 @@
+use cdbc::error::Error;
+use cdbc_mock::{Mock, MockConnection, MockValue};
+
+#[derive(cdbc::FromRow, Debug, PartialEq)]
+struct Address {
+    city: String,
+    #[cdbc(default)]
+    zip: Option<String>,
+}
+
+#[derive(cdbc::FromRow, Debug, PartialEq)]
+#[cdbc(rename_all = "camelCase")]
+struct User {
+    user_id: i64,
+    #[cdbc(rename = "name")]
+    user_name: String,
+    #[cdbc(try_from = "i64")]
+    age: u8,
+    remark: Option<String>,
+    #[cdbc(default)]
+    login_count: i32,
+    #[cdbc(skip)]
+    cached: bool,
+    #[cdbc(flatten)]
+    address: Address,
+}
+
+#[derive(cdbc::FromRow, Debug, PartialEq)]
+struct Pair(i64, Option<String>);
+
+/// a connection answering `sql` with one row of `columns`
+fn row(sql: &str, columns: &[&str], values: Vec<MockValue>) -> (Mock, MockConnection) {
+    let mock = Mock::new();
+    mock.expect(sql).returns_rows(columns, vec![values]);
+    let conn = mock.connect();
+    (mock, conn)
+}
+
+fn fetch_user(columns: &[&str], values: Vec<MockValue>) -> Result<User, Error> {
+    let (_mock, mut conn) = row("select * from user", columns, values);
+    cdbc::query_as::<_, User>("select * from user").fetch_one(&mut conn)
+}
+
+#[test]
+fn test_rename_all_default_skip_flatten() {
+    let user = fetch_user(
+        &[
+            "userId",
+            "name",
+            "age",
+            "remark",
+            "loginCount",
+            "cached",
+            "city",
+            "zip",
+        ],
+        vec![
+            1.into(),
+            "tom".into(),
+            20.into(),
+            "vip".into(),
+            3.into(),
+            true.into(),
+            "paris".into(),
+            "75001".into(),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        user,
+        User {
+            user_id: 1,
+            user_name: "tom".to_string(),
+            age: 20,
+            remark: Some("vip".to_string()),
+            login_count: 3,
+            // never read, even with a column of its name
+            cached: false,
+            address: Address {
+                city: "paris".to_string(),
+                zip: Some("75001".to_string())
+            },
+        }
+    );
+
+    // `default` fields without their column, here in the flattened struct too
+    let user = fetch_user(
+        &["userId", "name", "age", "remark", "city"],
+        vec![
+            1.into(),
+            "tom".into(),
+            20.into(),
+            MockValue::Null,
+            "paris".into(),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        (user.login_count, user.remark, user.address.zip),
+        (0, None, None)
+    );
+}
+
+#[test]
+fn test_missing_column() {
+    // `user_id` is read as `userId`
+    let err = fetch_user(
+        &["user_id", "name", "age", "remark", "city"],
+        vec![
+            1.into(),
+            "tom".into(),
+            20.into(),
+            MockValue::Null,
+            "paris".into(),
+        ],
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, Error::ColumnNotFound(column) if column == "userId"),
+        "{}",
+        err
+    );
+    // the column of a flattened field
+    let err = fetch_user(
+        &["userId", "name", "age", "remark"],
+        vec![1.into(), "tom".into(), 20.into(), MockValue::Null],
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, Error::ColumnNotFound(column) if column == "city"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_null() {
+    let err = fetch_user(
+        &["userId", "name", "age", "remark", "city"],
+        vec![
+            1.into(),
+            MockValue::Null,
+            20.into(),
+            MockValue::Null,
+            "paris".into(),
+        ],
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, Error::ColumnDecode { index, .. } if index == "\"name\""),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_try_from() {
+    let err = fetch_user(
+        &["userId", "name", "age", "remark", "city"],
+        vec![
+            1.into(),
+            "tom".into(),
+            300.into(),
+            MockValue::Null,
+            "paris".into(),
+        ],
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, Error::ColumnDecode { index, .. } if index == "\"age\""),
+        "{}",
+        err
+    );
+    let err = fetch_user(
+        &["userId", "name", "age", "remark", "city"],
+        vec![
+            1.into(),
+            "tom".into(),
+            (-1).into(),
+            MockValue::Null,
+            "paris".into(),
+        ],
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, Error::ColumnDecode { index, .. } if index == "\"age\""),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_tuple_struct() {
+    let mock = Mock::new();
+    mock.expect("select id, name from pair").returns_rows(
+        &["id", "name"],
+        vec![vec![1.into(), "a".into()], vec![2.into(), MockValue::Null]],
+    );
+    let pairs = cdbc::query_as::<_, Pair>("select id, name from pair")
+        .fetch_all(&mut mock.connect())
+        .unwrap();
+    assert_eq!(pairs, vec![Pair(1, Some("a".to_string())), Pair(2, None)]);
+
+    // read by position, a missing column is out of bounds
+    let (_mock, mut conn) = row("select id from pair", &["id"], vec![1.into()]);
+    let err = cdbc::query_as::<_, Pair>("select id from pair")
+        .fetch_one(&mut conn)
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::ColumnIndexOutOfBounds { index: 1, len: 1 }),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,60 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use cdbc::error::Error;
+use cdbc_mock::{Mock, MockPool, MockPoolOptions};
+use mco::co;
+use mco::coroutine::sleep;
+
+fn pool(mock: &Mock, acquire_timeout: Duration) -> MockPool {
+    MockPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(acquire_timeout)
+        .connect_with(mock.connect_options())
+        .unwrap()
+}
+
+#[test]
+fn test_acquire_timeout() {
+    let mock = Mock::new();
+    let pool = pool(&mock, Duration::from_millis(300));
+    let _held = pool.acquire().unwrap();
+
+    let start = Instant::now();
+    let err = pool.acquire().unwrap_err();
+    let elapsed = start.elapsed();
+    assert!(matches!(err, Error::PoolTimedOut), "{}", err);
+    assert!(elapsed >= Duration::from_millis(300), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(1300), "{:?}", elapsed);
+}
+
+#[test]
+fn test_acquire_fifo() {
+    let mock = Mock::new();
+    let pool = pool(&mock, Duration::from_secs(10));
+    let held = pool.acquire().unwrap();
+
+    let order = Arc::new(Mutex::new(vec![]));
+    let waiters: Vec<_> = ["first", "second"]
+        .into_iter()
+        .map(|name| {
+            let (pool, order) = (pool.clone(), order.clone());
+            let waiter = co!(move || {
+                let _conn = pool.acquire().unwrap();
+                order.lock().unwrap().push(name);
+                // keep the connection a while, the other waiter must not get ahead
+                sleep(Duration::from_millis(100));
+            });
+            // queue up one after the other
+            sleep(Duration::from_millis(100));
+            waiter
+        })
+        .collect();
+    assert!(order.lock().unwrap().is_empty());
+
+    drop(held);
+    for waiter in waiters {
+        waiter.join().unwrap();
+    }
+    assert_eq!(*order.lock().unwrap(), vec!["first", "second"]);
+    assert_eq!(pool.size(), 1);
+}
@@ -0,0 +1,62 @@
+use cdbc::error::Error;
+use cdbc_mock::{Mock, MockDatabase};
+
+#[test]
+fn test_query_named() {
+    let mock = Mock::new();
+    mock.expect("select * from user where name = ? and (status = ? or remark = ?)")
+        .with_args(vec!["tom".into(), 1.into(), "tom".into()])
+        .returns_rows(&["id"], vec![vec![1.into()]]);
+
+    let mut conn = mock.connect();
+    let rows = cdbc::query_named("select * from user where name = :name and (status = :status or remark = :name)")
+        .bind_named("status", 1)
+        .bind_named(":name", "tom")
+        .fetch_all(&mut conn)
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    mock.verify();
+}
+
+#[test]
+fn test_query_named_variables() {
+    let mock = Mock::new();
+    mock.expect("set @total := ?; select @total, @@version from user where id = @total")
+        .with_args(vec![2.into()]);
+
+    let mut conn = mock.connect();
+    cdbc::query_named("set @total := :total; select @total, @@version from user where id = @total")
+        .bind_named("total", 2)
+        .execute(&mut conn)
+        .unwrap();
+    mock.verify();
+}
+
+#[test]
+fn test_query_named_build_errors() {
+    let err = cdbc::query_named::<MockDatabase>("select * from user where name = :name and status = :status")
+        .bind_named("name", "tom")
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Configuration(_)), "{}", err);
+    assert!(err.to_string().contains("`status` is not bound"), "{}", err);
+
+    let err = cdbc::query_named::<MockDatabase>("select * from user where name = :name")
+        .bind_named("name", "tom")
+        .bind_named("status", 1)
+        .build()
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("`status` is bound but not used by the query"), "{}", err);
+
+    let mock = Mock::new();
+    let err = cdbc::query_named("select * from user where name = :name")
+        .bind_named("name", "tom")
+        .bind_named(":name", "jerry")
+        .fetch_all(&mut mock.connect())
+        .unwrap_err();
+    assert!(err.to_string().contains("`name` is bound more than once"), "{}", err);
+    // nothing reached the database
+    assert!(mock.executed().is_empty());
+}
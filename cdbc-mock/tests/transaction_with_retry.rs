@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use cdbc::connection::Connection;
+use cdbc::error::ErrorKind;
+use cdbc::executor::Executor;
+use cdbc::transaction::RetryPolicy;
+use cdbc_mock::{Mock, MockDatabaseError};
+
+#[test]
+fn test_transaction_with_retry() {
+    let mock = Mock::new();
+    mock.expect("update account set balance = balance - 1")
+        .returns_error(|| MockDatabaseError::new("deadlock").kind(ErrorKind::Deadlock).into())
+        .times(2);
+    mock.expect("update account set balance = balance - 1").returns_affected(1);
+    // a failing rollback keeps the deadlock of the attempt, which is retried
+    mock.expect("ROLLBACK").returns_error(|| MockDatabaseError::new("connection reset").into());
+
+    let policy = RetryPolicy::new().initial_backoff(Duration::from_millis(1));
+    let mut conn = mock.connect();
+    let mut attempts = 0;
+    let affected = conn
+        .transaction_with_retry(&policy, |tx| {
+            attempts += 1;
+            tx.execute("update account set balance = balance - 1")
+                .map(|done| done.rows_affected())
+        })
+        .unwrap();
+    assert_eq!((attempts, affected), (3, 1));
+    mock.assert_executed(&[
+        "BEGIN",
+        "update account set balance = balance - 1",
+        "ROLLBACK",
+        "ROLLBACK",
+        "BEGIN",
+        "update account set balance = balance - 1",
+        "ROLLBACK",
+        "BEGIN",
+        "update account set balance = balance - 1",
+        "COMMIT",
+    ]);
+    mock.verify();
+
+    // errors that are not retryable end the loop
+    mock.expect("delete from account").returns_error(|| MockDatabaseError::new("permission denied").into());
+    let mut attempts = 0;
+    let err = conn
+        .transaction_with_retry(&policy, |tx| {
+            attempts += 1;
+            tx.execute("delete from account")
+        })
+        .unwrap_err();
+    assert!(err.as_database_error().is_some());
+    assert_eq!(attempts, 1);
+}
@@ -1,4 +1,5 @@
 use cdbc::Error;
+use cdbc::transaction::TransactionCallbacks;
 use cdbc::utils::statement_cache::StatementCache;
 use cdbc::io::Decode;
 use crate::connection::stream::MssqlStream;
@@ -82,6 +83,8 @@ impl MssqlConnection {
         Ok(Self {
             stream,
             cache_statement: StatementCache::new(1024),
+            default_timeout: None,
+            transaction_callbacks: TransactionCallbacks::default(),
         })
     }
 }
@@ -14,8 +14,10 @@ use crate::{
 use either::Either;
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Duration;
+use cdbc::deadline::{self, Watchdog};
 use cdbc::Error;
-use cdbc::io::chan_stream::{ChanStream, TryStream};
+use cdbc::io::chan_stream::ChanStream;
 
 impl MssqlConnection {
     fn run(&mut self, query: &str, arguments: Option<MssqlArguments>) -> Result<(), Error> {
@@ -66,22 +68,41 @@ impl MssqlConnection {
 impl Executor for MssqlConnection {
     type Database = Mssql;
 
-    fn fetch_many<'q, E: 'q>(
-        &mut self,
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         mut query: E,
-    ) -> ChanStream<Either<MssqlQueryResult, MssqlRow>>
+    ) -> ChanStream<'e, Either<MssqlQueryResult, MssqlRow>>
         where
             E: Execute<'q, Self::Database>,
     {
         let sql = query.sql().to_owned();
         let arguments = query.take_arguments();
+        // the stream runs the query lazily, take the deadline of the caller now
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
         chan_stream! {
+            on_close: self.stream.cancel_and_drain();
+
+            deadline::check(deadline)?;
+
             self.run(&sql, arguments)?;
 
+            // sends an attention if the request is still running at the deadline
+            let watchdog = Watchdog::start(deadline, self.stream.canceller());
+
             loop {
-                let message = self.stream.recv_message()?;
+                let message = watchdog.check(self.stream.recv_message())?;
 
                 match message {
+                    Message::Done(done) if done.status.contains(Status::DONE_ATTN) => {
+                        // the server stopped the request for the attention of the watchdog
+                        self.stream.handle_attention_ack();
+                        return Err(Error::QueryTimedOut);
+                    }
+
                     Message::Row(row) => {
                         let columns = Arc::clone(&self.stream.columns);
                         let column_names = Arc::clone(&self.stream.column_names);
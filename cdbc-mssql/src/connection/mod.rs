@@ -4,10 +4,11 @@ use cdbc::executor::Executor;
 use crate::connection::stream::MssqlStream;
 use crate::statement::MssqlStatementMetadata;
 use crate::{Mssql, MssqlConnectOptions};
-use cdbc::transaction::Transaction;
+use cdbc::transaction::{Transaction, TransactionCallbacks, TransactionOptions};
 use std::fmt::{self, Debug, Formatter};
 use std::net::Shutdown;
 use std::sync::Arc;
+use std::time::Duration;
 use either::Either;
 use cdbc::database::{Database, HasStatement};
 use cdbc::describe::Describe;
@@ -22,6 +23,10 @@ mod stream;
 pub struct MssqlConnection {
     pub stream: MssqlStream,
     pub cache_statement: StatementCache<Arc<MssqlStatementMetadata>>,
+    pub default_timeout: Option<Duration>,
+
+    // callbacks of the open transactions
+    pub transaction_callbacks: TransactionCallbacks,
 }
 
 impl Debug for MssqlConnection {
@@ -33,7 +38,11 @@ impl Debug for MssqlConnection {
 impl Executor for &mut MssqlConnection {
     type Database = Mssql;
 
-    fn fetch_many<'q, E: 'q>(&mut self, query: E) -> ChanStream<Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database> {
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(&'e mut self, query: E) -> ChanStream<'e, Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database> {
         MssqlConnection::fetch_many(self, query)
     }
 
@@ -58,6 +67,14 @@ impl Connection for MssqlConnection {
         Ok(self.stream.shutdown(Shutdown::Both)?)
     }
 
+    fn is_broken(&self) -> bool {
+        self.stream.broken
+    }
+
+    fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.default_timeout = timeout;
+    }
+
     fn ping(&mut self) -> Result<(), cdbc::Error> {
         // NOTE: we do not use `SELECT 1` as that *could* interact with any ongoing transactions
         self.execute("/* SQLx ping */")?;
@@ -71,6 +88,13 @@ impl Connection for MssqlConnection {
         Ok(Transaction::begin(self)?)
     }
 
+    fn begin_with(&mut self, options: TransactionOptions) -> Result<Transaction<'_, Self::Database>, cdbc::Error>
+        where
+            Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> Result<(), cdbc::Error> {
         self.stream.wait_until_ready()
@@ -80,4 +104,8 @@ impl Connection for MssqlConnection {
     fn should_flush(&self) -> bool {
         !self.stream.wbuf.is_empty()
     }
+
+    fn transaction_callbacks(&mut self) -> Option<&mut TransactionCallbacks> {
+        Some(&mut self.transaction_callbacks)
+    }
 }
@@ -21,7 +21,9 @@ use crate::protocol::row::Row;
 use crate::{MssqlColumn, MssqlConnectOptions, MssqlDatabaseError};
 use cdbc::net::MaybeTlsStream;
 use cdbc::{Error, HashMap};
-use std::sync::Arc;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use mco::net::TcpStream;
 
 pub struct MssqlStream {
@@ -35,6 +37,10 @@ pub struct MssqlStream {
     pub transaction_descriptor: u64,
     pub transaction_depth: usize,
 
+    // set when a transaction changed the isolation level of the session,
+    // it is reset when the transaction ends
+    pub reset_isolation_level: bool,
+
     // current TabularResult from the server that we are iterating over
     response: Option<(PacketHeader, Bytes)>,
 
@@ -42,13 +48,30 @@ pub struct MssqlStream {
     // we need to store this as its needed when decoding <Row>
     pub columns: Arc<Vec<MssqlColumn>>,
     pub column_names: Arc<HashMap<UStr, usize>>,
+
+    // set when a dropped stream left unread messages that could not be drained
+    pub broken: bool,
+
+    // a second handle of the socket, used to send an attention for a timed out request
+    // while the stream is reading its response
+    attention_socket: Option<Arc<Mutex<TcpStream>>>,
+
+    // set once such an attention was sent,
+    // its acknowledgement must be read before the next request
+    attention_sent: Arc<AtomicBool>,
 }
 
 impl MssqlStream {
     pub(super) fn connect(options: &MssqlConnectOptions) -> Result<Self, Error> {
-        let inner = BufStream::new(MaybeTlsStream::Raw(
-            TcpStream::connect((&*options.host, options.port))?,
-        ));
+        let socket = TcpStream::connect((&*options.host, options.port))?;
+        let attention_socket = match socket.try_clone() {
+            Ok(socket) => Some(Arc::new(Mutex::new(socket))),
+            Err(e) => {
+                log::debug!("queries can not time out: failed to clone the socket: {}", e);
+                None
+            }
+        };
+        let inner = BufStream::new(MaybeTlsStream::Raw(socket));
 
         Ok(Self {
             inner,
@@ -58,6 +81,10 @@ impl MssqlStream {
             pending_done_count: 0,
             transaction_descriptor: 0,
             transaction_depth: 0,
+            reset_isolation_level: false,
+            broken: false,
+            attention_socket,
+            attention_sent: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -203,10 +230,21 @@ impl MssqlStream {
     }
 
     pub fn wait_until_ready(&mut self) -> Result<(), Error> {
+        if self.broken {
+            return Err(err_protocol!(
+                "connection is broken: a dropped stream left it in an unknown state"
+            ));
+        }
+
         if !self.wbuf.is_empty() {
             self.flush()?;
         }
 
+        if self.attention_sent.load(Ordering::SeqCst) {
+            // a timed out request was cancelled
+            return self.recv_attention_ack();
+        }
+
         while self.pending_done_count > 0 {
             let message = self.recv_message()?;
 
@@ -220,6 +258,81 @@ impl MssqlStream {
 
         Ok(())
     }
+
+    // called when a stream is dropped before its request completed;
+    // send an attention to cancel the request and read up to the server acknowledging it
+    pub fn cancel_and_drain(&mut self) {
+        if self.pending_done_count == 0 || self.broken {
+            return;
+        }
+
+        if let Err(e) = self.attention() {
+            log::warn!("error occurred while cancelling a dropped stream: {}", e);
+            self.broken = true;
+        }
+    }
+
+    fn attention(&mut self) -> Result<(), Error> {
+        // https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-tds/dc28579f-49b1-4a78-9c5f-63fbda002d2e
+        if !self.attention_sent.load(Ordering::SeqCst) {
+            self.write_packet(PacketType::AttentionSignal, &[][..]);
+            self.flush()?;
+        }
+
+        self.recv_attention_ack()
+    }
+
+    // sends an attention for the running request from another coroutine, used by a
+    // `Watchdog` when the request runs past its deadline
+    pub fn canceller(&self) -> impl FnOnce() + Send + 'static {
+        let socket = self.attention_socket.clone();
+        let sent = self.attention_sent.clone();
+
+        move || {
+            let socket = match socket {
+                Some(socket) => socket,
+                None => return,
+            };
+
+            let mut packet = Vec::with_capacity(8);
+            PacketHeader {
+                r#type: PacketType::AttentionSignal,
+                status: Status::END_OF_MESSAGE,
+                length: 8,
+                server_process_id: 0,
+                packet_id: 1,
+            }
+            .encode_with(&mut packet, &mut 0);
+
+            let mut socket = socket.lock().unwrap();
+            match socket.write_all(&packet).and_then(|_| socket.flush()) {
+                Ok(()) => sent.store(true, Ordering::SeqCst),
+                Err(e) => log::warn!("error occurred while cancelling a timed out request: {}", e),
+            }
+        }
+    }
+
+    // read up to the acknowledgement of an attention, it follows everything the server sent
+    // for the request; errors of the cancelled request are skipped
+    fn recv_attention_ack(&mut self) -> Result<(), Error> {
+        loop {
+            match self.recv_message() {
+                Ok(Message::Done(done)) if done.status.contains(DoneStatus::DONE_ATTN) => break,
+                Ok(_) | Err(Error::Database(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.handle_attention_ack();
+
+        Ok(())
+    }
+
+    // the request is done once the attention was acknowledged
+    pub fn handle_attention_ack(&mut self) {
+        self.pending_done_count = 0;
+        self.attention_sent.store(false, Ordering::SeqCst);
+    }
 }
 
 impl Deref for MssqlStream {
@@ -47,3 +47,36 @@ impl HasArguments<'_> for Mssql {
 
     type ArgumentBuffer = Vec<u8>;
 }
+
+#[cfg(test)]
+mod test {
+    use cdbc::QueryBuilder;
+    use crate::Mssql;
+
+    #[test]
+    fn test_query_builder() {
+        let mut builder = QueryBuilder::<Mssql>::new("select * from biz where id = ");
+        builder.push_bind(1).push(" and name in (");
+        let mut separated = builder.separated(", ");
+        separated.push_bind("a");
+        separated.push("'b'");
+        separated.push_bind("c");
+        separated.push_unseparated(")");
+        assert_eq!(builder.sql(), "select * from biz where id = @p1 and name in (@p2, 'b', @p3)");
+        let query = builder.build();
+        let arguments = query.arguments.unwrap();
+        assert_eq!((query.bound, arguments.ordinal), (3, 3));
+
+        builder.reset();
+        assert_eq!(builder.sql(), "select * from biz where id = ");
+
+        let mut builder = QueryBuilder::<Mssql>::new("insert into biz(id, name) ");
+        builder.push_values(vec![(1, "a"), (2, "b")], |mut b, (id, name)| {
+            b.push_bind(id).push_bind(name);
+        });
+        assert_eq!(builder.sql(), "insert into biz(id, name) VALUES (@p1, @p2), (@p3, @p4)");
+        let query = builder.build();
+        let arguments = query.arguments.unwrap();
+        assert_eq!((query.bound, arguments.ordinal), (4, 4));
+    }
+}
@@ -1,12 +1,27 @@
 use std::error::Error as StdError;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use cdbc::error::DatabaseError;
+use cdbc::error::{DatabaseError, ErrorKind};
 use crate::protocol::error::ProtoError;
 
 /// An error returned from the MSSQL database.
 pub struct MssqlDatabaseError(pub ProtoError);
 
+impl MssqlDatabaseError {
+    /// The [number](https://docs.microsoft.com/en-us/sql/relational-databases/errors-events/database-engine-events-and-errors)
+    /// for this error.
+    pub fn number(&self) -> i32 {
+        self.0.number
+    }
+}
+
+/// The text following `open` up to `close`.
+fn between<'a>(message: &'a str, open: &str, close: char) -> Option<&'a str> {
+    let start = message.find(open)? + open.len();
+    let rest = &message[start..];
+    Some(&rest[..rest.find(close)?])
+}
+
 impl Debug for MssqlDatabaseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("MssqlDatabaseError")
@@ -49,4 +64,98 @@ impl DatabaseError for MssqlDatabaseError {
     fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
         self
     }
+
+    fn kind(&self) -> ErrorKind {
+        match self.number() {
+            // violation of a PRIMARY KEY or UNIQUE KEY constraint, or of a unique index
+            2627 | 2601 => ErrorKind::UniqueViolation,
+            // a statement conflicted with a FOREIGN KEY, REFERENCE or CHECK constraint
+            547 if self.message().contains(" CHECK constraint") => ErrorKind::CheckViolation,
+            547 => ErrorKind::ForeignKeyViolation,
+            515 => ErrorKind::NotNullViolation,
+            // snapshot isolation update conflicts
+            3960 | 3961 => ErrorKind::SerializationFailure,
+            1205 => ErrorKind::Deadlock,
+            // lock request time out period exceeded
+            1222 => ErrorKind::LockTimeout,
+            // the session was killed, or the server is shutting down
+            596 | 6005 => ErrorKind::ConnectionLost,
+            // the database is read-only
+            3906 => ErrorKind::ReadOnly,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    fn constraint(&self) -> Option<&str> {
+        let message = self.message();
+        match self.number() {
+            // Violation of UNIQUE KEY constraint 'UQ_users_email'. Cannot insert duplicate key in object 'dbo.users'.
+            2627 => between(message, "constraint '", '\''),
+            // Cannot insert duplicate key row in object 'dbo.users' with unique index 'ix_users_email'.
+            2601 => between(message, "unique index '", '\''),
+            // The INSERT statement conflicted with the FOREIGN KEY constraint "FK_child_parent".
+            // The conflict occurred in database "db", table "dbo.parent", column 'id'.
+            547 => between(message, "constraint \"", '"'),
+            _ => None,
+        }
+    }
+
+    fn table(&self) -> Option<&str> {
+        let message = self.message();
+        match self.number() {
+            2627 | 2601 => between(message, "in object '", '\''),
+            547 => between(message, "table \"", '"'),
+            // Cannot insert the value NULL into column 'name', table 'db.dbo.users'; column does not allow nulls.
+            515 => between(message, "table '", '\''),
+            _ => None,
+        }
+    }
+
+    fn column(&self) -> Option<&str> {
+        match self.number() {
+            547 | 515 => between(self.message(), "column '", '\''),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::error::{DatabaseError, ErrorKind};
+    use crate::protocol::error::ProtoError;
+    use crate::MssqlDatabaseError;
+
+    fn error(number: i32, message: &str) -> MssqlDatabaseError {
+        MssqlDatabaseError(ProtoError {
+            number,
+            state: 1,
+            class: 14,
+            message: message.to_owned(),
+            server: String::new(),
+            procedure: String::new(),
+            line: 1,
+        })
+    }
+
+    #[test]
+    fn test_kind() {
+        let err = error(2627, "Violation of UNIQUE KEY constraint 'UQ_users_email'. Cannot insert duplicate key in object 'dbo.users'. The duplicate key value is (a).");
+        assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+        assert_eq!(err.constraint(), Some("UQ_users_email"));
+        assert_eq!(err.table(), Some("dbo.users"));
+
+        let err = error(547, "The INSERT statement conflicted with the FOREIGN KEY constraint \"FK_child_parent\". The conflict occurred in database \"db\", table \"dbo.parent\", column 'id'.");
+        assert_eq!(err.kind(), ErrorKind::ForeignKeyViolation);
+        assert_eq!(err.constraint(), Some("FK_child_parent"));
+        assert_eq!(err.table(), Some("dbo.parent"));
+        assert_eq!(err.column(), Some("id"));
+
+        let err = error(547, "The INSERT statement conflicted with the CHECK constraint \"CK_age\". The conflict occurred in database \"db\", table \"dbo.users\", column 'age'.");
+        assert_eq!(err.kind(), ErrorKind::CheckViolation);
+
+        let err = error(515, "Cannot insert the value NULL into column 'name', table 'db.dbo.users'; column does not allow nulls. INSERT fails.");
+        assert_eq!(err.kind(), ErrorKind::NotNullViolation);
+        assert_eq!(err.column(), Some("name"));
+        assert_eq!(err.table(), Some("db.dbo.users"));
+    }
 }
@@ -0,0 +1,197 @@
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::executor::Executor;
+use cdbc::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::{Mssql, MssqlConnectOptions, MssqlConnection};
+use cdbc::query::query;
+use cdbc::query_as::query_as;
+use cdbc::query_scalar::query_scalar;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+
+fn parse_for_maintenance(url: &str) -> Result<(MssqlConnectOptions, String), Error> {
+    let mut options = MssqlConnectOptions::from_str(url)?;
+
+    // switch us to the `master` database
+    let database = std::mem::replace(&mut options.database, "master".to_string());
+
+    Ok((options, database))
+}
+
+pub(crate) fn quote_ident(database: &str) -> String {
+    format!("[{}]", database.replace(']', "]]"))
+}
+
+impl MigrateDatabase for Mssql {
+    fn create_database(url: &str) -> Result<(), Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = MssqlConnection::connect_with(&options)?;
+
+        let _ = conn.execute(&*format!("CREATE DATABASE {}", quote_ident(&database)))?;
+
+        Ok(())
+    }
+
+    fn database_exists(url: &str) -> Result<bool, Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = MssqlConnection::connect_with(&options)?;
+
+        let exists: bool =
+            query_scalar("SELECT CAST(CASE WHEN DB_ID(@p1) IS NULL THEN 0 ELSE 1 END AS BIT)")
+                .bind(&*database)
+                .fetch_one(&mut conn)?;
+
+        Ok(exists)
+    }
+
+    fn drop_database(url: &str) -> Result<(), Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = MssqlConnection::connect_with(&options)?;
+
+        let _ = conn.execute(&*format!(
+            "IF DB_ID(N'{}') IS NOT NULL DROP DATABASE {}",
+            database.replace('\'', "''"),
+            quote_ident(&database)
+        ))?;
+
+        Ok(())
+    }
+}
+
+impl Migrate for MssqlConnection {
+    fn ensure_migrations_table(&mut self) -> Result<(), MigrateError> {
+        // language=TSQL
+        self.execute(
+            r#"
+IF OBJECT_ID('_cdbc_migrations', 'U') IS NULL
+CREATE TABLE _cdbc_migrations (
+    version BIGINT PRIMARY KEY,
+    description NVARCHAR(1000) NOT NULL,
+    installed_on DATETIME2 NOT NULL DEFAULT SYSUTCDATETIME(),
+    success BIT NOT NULL,
+    checksum NVARCHAR(96) NOT NULL,
+    execution_time BIGINT NOT NULL
+);
+                "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError> {
+        // language=TSQL
+        let row: Option<(i64,)> = query_as(
+            "SELECT TOP 1 version FROM _cdbc_migrations WHERE success = 0 ORDER BY version",
+        )
+        .fetch_optional(self)?;
+
+        Ok(row.map(|r| r.0))
+    }
+
+    fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError> {
+        // language=TSQL
+        let rows: Vec<(i64, String)> =
+            query_as("SELECT version, checksum FROM _cdbc_migrations ORDER BY version")
+                .fetch_all(self)?;
+
+        let migrations = rows
+            .into_iter()
+            .map(|(version, checksum)| AppliedMigration {
+                version,
+                checksum: checksum.into(),
+            })
+            .collect();
+
+        Ok(migrations)
+    }
+
+    fn lock(&mut self) -> Result<(), MigrateError> {
+        // application locks are scoped to the current database
+        // this function will not return until the lock is acquired
+
+        // https://docs.microsoft.com/en-us/sql/relational-databases/system-stored-procedures/sp-getapplock-transact-sql
+
+        // language=TSQL
+        let result: i32 = query_scalar(
+            r#"
+DECLARE @result INT;
+EXEC @result = sp_getapplock @Resource = '_cdbc_migrations', @LockMode = 'Exclusive', @LockOwner = 'Session', @LockTimeout = -1;
+SELECT @result;
+            "#,
+        )
+        .fetch_one(self)?;
+
+        if result < 0 {
+            return Err(MigrateError::Source(
+                format!("sp_getapplock returned {}", result).into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn unlock(&mut self) -> Result<(), MigrateError> {
+        // language=TSQL
+        let _ = self.execute(
+            "EXEC sp_releaseapplock @Resource = '_cdbc_migrations', @LockOwner = 'Session'",
+        )?;
+
+        Ok(())
+    }
+
+    fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        // the migration and its record are committed together, a failed migration is rolled back
+        // entirely. statements which can't run in a transaction (e.g. `ALTER DATABASE`) are not supported.
+        let mut tx = self.begin()?;
+        let start = Instant::now();
+
+        let _ = tx.execute(&*migration.sql)?;
+
+        // language=TSQL
+        let _ = query(
+            r#"
+    INSERT INTO _cdbc_migrations ( version, description, success, checksum, execution_time )
+    VALUES ( @p1, @p2, 1, @p3, -1 )
+                "#,
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(&mut *tx)?;
+
+        tx.commit()?;
+        drop(tx);
+
+        let elapsed = start.elapsed();
+
+        // language=TSQL
+        let _ = query("UPDATE _cdbc_migrations SET execution_time = @p1 WHERE version = @p2")
+            .bind(elapsed.as_nanos() as i64)
+            .bind(migration.version)
+            .execute(self)?;
+
+        Ok(elapsed)
+    }
+
+    fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        let down_sql = migration
+            .down_sql
+            .as_deref()
+            .ok_or(MigrateError::Irreversible(migration.version))?;
+
+        let mut tx = self.begin()?;
+        let start = Instant::now();
+
+        let _ = tx.execute(down_sql)?;
+
+        // language=TSQL
+        let _ = query("DELETE FROM _cdbc_migrations WHERE version = @p1")
+            .bind(migration.version)
+            .execute(&mut *tx)?;
+
+        tx.commit()?;
+
+        Ok(start.elapsed())
+    }
+}
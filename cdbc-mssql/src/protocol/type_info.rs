@@ -491,6 +491,7 @@ impl TypeInfo {
             DataType::BigInt => "BIGINT",
             DataType::Real => "REAL",
             DataType::Float => "FLOAT",
+            DataType::Bit | DataType::BitN => "BIT",
 
             DataType::IntN => match self.size {
                 1 => "TINYINT",
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use bytes::{Buf, Bytes};
+use cdbc::error::Error;
+use cdbc::replay::{RecordedResult, RecordedValue, Replay};
+use serde::Deserialize;
+use crate::io::MssqlBufExt;
+use crate::protocol::row::Row;
+use crate::protocol::type_info::TypeInfo;
+use crate::{Mssql, MssqlArguments, MssqlColumn, MssqlQueryResult, MssqlRow};
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(value).map_err(|e| Error::Decode(e.into()))
+}
+
+/// The next parameter of a RPC request, see [`MssqlArguments::add`].
+fn get_argument(buf: &mut Bytes) -> Result<RecordedValue, Error> {
+    buf.get_b_varchar()?; // [ParamName]
+    buf.advance(1); // [StatusFlags]
+    let ty = TypeInfo::get(buf)?; // [TYPE_INFO]
+    let value = ty.get_value(buf); // [ParamLenData]
+    Ok(RecordedValue {
+        type_name: ty.name().to_owned(),
+        value: value.map(hex::encode),
+    })
+}
+
+/// Values are recorded as the hex of their bytes, as MSSQL sent them.
+impl Replay for Mssql {
+    /// A row without values, sharing the columns of the replayed rows.
+    type Columns = MssqlRow;
+
+    fn record_arguments(arguments: &MssqlArguments) -> Vec<RecordedValue> {
+        let mut buf = Bytes::copy_from_slice(&arguments.data);
+        let mut values = Vec::with_capacity(arguments.ordinal);
+        while buf.has_remaining() {
+            match get_argument(&mut buf) {
+                Ok(value) => values.push(value),
+                // still compared as a whole
+                Err(_) => {
+                    values.push(RecordedValue {
+                        type_name: "UNKNOWN".to_owned(),
+                        value: Some(hex::encode(&buf)),
+                    });
+                    break;
+                }
+            }
+        }
+        values
+    }
+
+    fn record_columns(row: &MssqlRow) -> serde_json::Value {
+        serde_json::to_value(&*row.columns).unwrap_or_default()
+    }
+
+    fn record_row(row: &MssqlRow) -> Vec<Option<String>> {
+        row.row.values.iter().map(|value| value.as_ref().map(hex::encode)).collect()
+    }
+
+    fn record_result(result: &MssqlQueryResult) -> RecordedResult {
+        RecordedResult {
+            rows_affected: result.rows_affected,
+            last_insert_id: None,
+        }
+    }
+
+    fn replay_columns(columns: &serde_json::Value) -> Result<Self::Columns, Error> {
+        let columns = Vec::<MssqlColumn>::deserialize(columns).map_err(|e| Error::Decode(e.into()))?;
+        let column_names = columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ordinal))
+            .collect();
+        Ok(MssqlRow {
+            row: Row {
+                column_types: columns.iter().map(|column| column.type_info.clone()).collect(),
+                values: Vec::new(),
+            },
+            columns: Arc::new(columns),
+            column_names: Arc::new(column_names),
+        })
+    }
+
+    fn replay_row(columns: &MssqlRow, values: &[Option<String>]) -> Result<MssqlRow, Error> {
+        if values.len() != columns.columns.len() {
+            return Err(err_protocol!(
+                "recorded row has {} values for {} columns",
+                values.len(),
+                columns.columns.len()
+            ));
+        }
+        let values = values
+            .iter()
+            .map(|value| value.as_deref().map(decode_hex).transpose().map(|v| v.map(Bytes::from)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(MssqlRow {
+            row: Row {
+                column_types: columns.row.column_types.clone(),
+                values,
+            },
+            columns: columns.columns.clone(),
+            column_names: columns.column_names.clone(),
+        })
+    }
+
+    fn replay_result(result: &RecordedResult) -> MssqlQueryResult {
+        MssqlQueryResult {
+            rows_affected: result.rows_affected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::replay::{RecordedValue, Replay};
+    use cdbc::row::Row;
+    use crate::{Mssql, MssqlArguments};
+
+    #[test]
+    fn test_record_arguments() {
+        let mut arguments = MssqlArguments::default();
+        arguments.add(7_i32);
+        arguments.add(None::<i64>);
+        arguments.add("tom");
+        let recorded = Mssql::record_arguments(&arguments);
+        assert_eq!(recorded.len(), 3);
+        assert_eq!(recorded[0], RecordedValue { type_name: "INT".into(), value: Some("07000000".into()) });
+        assert_eq!(recorded[1].value, None);
+        // UTF-16
+        assert_eq!(recorded[2].value.as_deref(), Some("74006f006d00"));
+    }
+
+    #[test]
+    fn test_replay_row() {
+        let columns = serde_json::json!([
+            {"ordinal": 0, "name": "id", "type_info": {"ty": "IntN", "size": 4, "scale": 0, "precision": 0, "collation": null}, "flags": {"bits": 0}},
+            {"ordinal": 1, "name": "name", "type_info": {"ty": "IntN", "size": 8, "scale": 0, "precision": 0, "collation": null}, "flags": {"bits": 1}},
+        ]);
+        let values = vec![Some("07000000".to_string()), None];
+        let replayed = Mssql::replay_columns(&columns).unwrap();
+        let row = Mssql::replay_row(&replayed, &values).unwrap();
+        assert_eq!(row.get::<i32, _>("id"), 7);
+        assert_eq!(row.get::<Option<i64>, _>("name"), None);
+        assert_eq!(Mssql::record_row(&row), values);
+        assert_eq!(Mssql::record_columns(&row), columns);
+    }
+}
@@ -0,0 +1,39 @@
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::executor::Executor;
+use cdbc::testing::{database_url, test_db_name, TestArgs, TestContext, TestSupport};
+use crate::migrate::quote_ident;
+use crate::{Mssql, MssqlConnectOptions, MssqlConnection};
+use std::str::FromStr;
+
+impl TestSupport for Mssql {
+    fn test_context(args: &TestArgs) -> Result<TestContext<Self>, Error> {
+        // new databases are copied from `model`, there is no choosing another one
+        args.reject_template("mssql")?;
+        let mut master_options = MssqlConnectOptions::from_str(&database_url()?)?;
+        master_options.database = "master".to_string();
+        let mut conn = MssqlConnection::connect_with(&master_options)?;
+
+        let db_name = test_db_name();
+        let _ = conn.execute(&*format!("CREATE DATABASE {}", quote_ident(&db_name)))?;
+        conn.close()?;
+
+        let connect_options = master_options.clone().database(&db_name);
+        Ok(TestContext {
+            db_name,
+            connect_options,
+            master_options,
+            keep_alive: None,
+        })
+    }
+
+    fn cleanup_test(db_name: &str, master_options: &MssqlConnectOptions) -> Result<(), Error> {
+        let mut conn = MssqlConnection::connect_with(master_options)?;
+        // disconnect the other sessions first
+        let _ = conn.execute(&*format!(
+            "ALTER DATABASE {0} SET SINGLE_USER WITH ROLLBACK IMMEDIATE; DROP DATABASE {0}",
+            quote_ident(db_name)
+        ))?;
+        conn.close()
+    }
+}
@@ -4,7 +4,9 @@ use cdbc::executor::Executor;
 use crate::protocol::packet::PacketType;
 use crate::protocol::sql_batch::SqlBatch;
 use crate::{Mssql, MssqlConnection};
-use cdbc::transaction::TransactionManager;
+use cdbc::transaction::{
+    check_savepoint_options, AccessMode, TransactionManager, TransactionOptions,
+};
 
 /// Implementation of [`TransactionManager`] for MSSQL.
 pub struct MssqlTransactionManager;
@@ -12,17 +14,33 @@ pub struct MssqlTransactionManager;
 impl TransactionManager for MssqlTransactionManager {
     type Database = Mssql;
 
-    fn begin(conn: &mut MssqlConnection) -> Result<(), Error> {
+    fn begin_with(conn: &mut MssqlConnection, options: &TransactionOptions) -> Result<(), Error> {
         let depth = conn.stream.transaction_depth;
+        check_savepoint_options(depth, options)?;
 
         let query = if depth == 0 {
-            Cow::Borrowed("BEGIN TRAN ")
+            if options.access_mode == Some(AccessMode::ReadOnly) {
+                return Err(Error::Configuration(
+                    "MSSQL does not support read-only transactions".into(),
+                ));
+            }
+            match options.isolation_level {
+                // the isolation level is a session setting in MSSQL
+                Some(level) => Cow::Owned(format!(
+                    "SET TRANSACTION ISOLATION LEVEL {}; BEGIN TRAN ",
+                    level.as_sql()
+                )),
+                None => Cow::Borrowed("BEGIN TRAN "),
+            }
         } else {
             Cow::Owned(format!("SAVE TRAN _sqlx_savepoint_{}", depth))
         };
 
         conn.execute(&*query)?;
         conn.stream.transaction_depth = depth + 1;
+        if depth == 0 && options.isolation_level.is_some() {
+            conn.stream.reset_isolation_level = true;
+        }
 
         Ok(())
     }
@@ -32,7 +50,8 @@ impl TransactionManager for MssqlTransactionManager {
         if depth > 0 {
             if depth == 1 {
                 // savepoints are not released in MSSQL
-                conn.execute("COMMIT TRAN")?;
+                let query = end_transaction_sql(conn, "COMMIT TRAN");
+                conn.execute(&*query)?;
             }
 
             conn.stream.transaction_depth = depth - 1;
@@ -45,7 +64,7 @@ impl TransactionManager for MssqlTransactionManager {
 
             if depth > 0 {
                 let query = if depth == 1 {
-                    Cow::Borrowed("ROLLBACK TRAN")
+                    end_transaction_sql(conn, "ROLLBACK TRAN")
                 } else {
                     Cow::Owned(format!("ROLLBACK TRAN _sqlx_savepoint_{}", depth - 1))
                 };
@@ -62,7 +81,7 @@ impl TransactionManager for MssqlTransactionManager {
 
         if depth > 0 {
             let query = if depth == 1 {
-                Cow::Borrowed("ROLLBACK TRAN")
+                end_transaction_sql(conn, "ROLLBACK TRAN")
             } else {
                 Cow::Owned(format!("ROLLBACK TRAN _sqlx_savepoint_{}", depth - 1))
             };
@@ -80,4 +99,28 @@ impl TransactionManager for MssqlTransactionManager {
             conn.stream.transaction_depth = depth - 1;
         }
     }
+
+    fn savepoint(conn: &mut MssqlConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*format!("SAVE TRAN {}", name))?;
+        Ok(())
+    }
+
+    fn release_savepoint(_conn: &mut MssqlConnection, _name: &str) -> Result<(), Error> {
+        // savepoints are not released in MSSQL
+        Ok(())
+    }
+
+    fn rollback_to_savepoint(conn: &mut MssqlConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*format!("ROLLBACK TRAN {}", name))?;
+        Ok(())
+    }
+}
+
+/// Ends the outermost transaction and puts the isolation level it changed back to the default.
+fn end_transaction_sql(conn: &mut MssqlConnection, end: &'static str) -> Cow<'static, str> {
+    if std::mem::replace(&mut conn.stream.reset_isolation_level, false) {
+        Cow::Owned(format!("{}; SET TRANSACTION ISOLATION LEVEL READ COMMITTED", end))
+    } else {
+        Cow::Borrowed(end)
+    }
 }
@@ -1,6 +1,7 @@
 use std::time::Duration;
 use bytes::buf::Buf;
 use bytes::Bytes;
+use cdbc::transaction::TransactionCallbacks;
 
 use cdbc::utils::statement_cache::StatementCache;
 use cdbc::error::Error;
@@ -130,6 +131,10 @@ impl MySqlConnection {
             stream,
             transaction_depth: 0,
             cache_statement: StatementCache::new(options.statement_cache_capacity),
+            connection_id: handshake.connection_id,
+            options: options.clone(),
+            default_timeout: None,
+            transaction_callbacks: TransactionCallbacks::default(),
         })
     }
 }
@@ -17,8 +17,10 @@ use crate::{
 use cdbc::{chan_stream, HashMap};
 use either::Either;
 use std::{borrow::Cow, sync::Arc};
+use std::time::{Duration, Instant};
+use cdbc::deadline::{self, Watchdog};
 use cdbc::database::{Database, HasStatement};
-use cdbc::io::chan_stream::{ChanStream, Stream, TryStream};
+use cdbc::io::chan_stream::ChanStream;
 use cdbc::utils::ustr::UStr;
 
 impl MySqlConnection {
@@ -85,12 +87,17 @@ impl MySqlConnection {
         sql: &'q str,
         arguments: Option<MySqlArguments>,
         persistent: bool,
-    ) -> Result<ChanStream<Either<MySqlQueryResult, MySqlRow>>, Error>
+        deadline: Option<Instant>,
+    ) -> Result<ChanStream<'e, Either<MySqlQueryResult, MySqlRow>>, Error>
     {
+        deadline::check(deadline)?;
+
         self.stream.wait_until_ready()?;
-        self.stream.waiting.push_back(Waiting::Result);
 
-        Ok(chan_stream!({
+        Ok(chan_stream!(on_close: self.cancel_and_drain(); {
+            // kills the query on the server if it is still running at the deadline
+            let watchdog = Watchdog::start(deadline, self.killer());
+
             // make a slot for the shared column data
             // as long as a reference to a row is not held past one iteration, this enables us
             // to re-use this memory freely between result sets
@@ -104,6 +111,7 @@ impl MySqlConnection {
                 ?;
 
                 // https://dev.mysql.com/doc/internals/en/com-stmt-execute.html
+                self.stream.waiting.push_back(Waiting::Result);
                 self.stream
                     .send_packet(StatementExecute {
                         statement: id,
@@ -114,6 +122,7 @@ impl MySqlConnection {
                 (metadata.column_names, MySqlValueFormat::Binary, false)
             } else {
                 // https://dev.mysql.com/doc/internals/en/com-query.html
+                self.stream.waiting.push_back(Waiting::Result);
                 self.stream.send_packet(Query(sql))?;
 
                 (Arc::default(), MySqlValueFormat::Text, true)
@@ -122,7 +131,7 @@ impl MySqlConnection {
             loop {
                 // query response is a meta-packet which may be one of:
                 //  Ok, Err, ResultSet, or (unhandled) LocalInfileRequest
-                let mut packet = self.stream.recv_packet()?;
+                let mut packet = watchdog.check(self.stream.recv_packet())?;
 
                 if packet[0] == 0x00 || packet[0] == 0xff {
                     // first packet in a query response is OK or ERR
@@ -162,7 +171,7 @@ impl MySqlConnection {
 
                 // finally, there will be none or many result-rows
                 loop {
-                    let packet = self.stream.recv_packet()?;
+                    let packet = watchdog.check(self.stream.recv_packet())?;
 
                     if packet[0] == 0xfe && packet.len() < 9 {
                         let eof = packet.eof(self.stream.capabilities)?;
@@ -203,24 +212,27 @@ impl MySqlConnection {
 impl Executor for MySqlConnection {
     type Database = MySql;
 
-    fn fetch_many<'q, E: 'q>(
-        &mut self,
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         mut query: E,
-    ) -> ChanStream<Either<MySqlQueryResult, MySqlRow>>
+    ) -> ChanStream<'e, Either<MySqlQueryResult, MySqlRow>>
     where E: Execute<'q, Self::Database>,
     {
         let arguments = query.take_arguments();
         let persistent = query.persistent();
+        // the stream runs the query lazily, take the deadline of the caller now
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
 
-        chan_stream! {
-            let mut s = self.run(query.sql(), arguments, persistent)?;
-
-            while let Some(v) = s.try_next()? {
-                r#yield!(v);
+        ChanStream::owned(query, move |query, _| {
+            match self.run(query.sql(), arguments, persistent, deadline) {
+                Ok(s) => s,
+                Err(e) => ChanStream::new(|_| Err(e)),
             }
-
-            Ok(())
-        }
+        })
     }
 
     fn fetch_optional<'q, E: 'q>(
@@ -229,13 +241,17 @@ impl Executor for MySqlConnection {
     ) -> Result<Option<MySqlRow>, Error>
     where E: Execute<'q, Self::Database>,
     {
+        // read the response to the end, a dropped stream would kill the query
+        let mut row = None;
         let mut s = self.fetch_many(query);
-            while let Some(v) = s.try_next()? {
-                if let Either::Right(r) = v {
-                    return Ok(Some(r));
+        while let Some(v) = s.try_next()? {
+            if let Either::Right(r) = v {
+                if row.is_none() {
+                    row = Some(r);
                 }
             }
-            Ok(None)
+        }
+        Ok(row)
     }
 
     fn prepare_with<'q>(
@@ -342,7 +358,11 @@ fn recv_result_metadata(
 impl Executor for &mut MySqlConnection{
     type Database = MySql;
 
-    fn fetch_many<'q, E: 'q>(&mut self, query: E) -> ChanStream<Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database> {
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(&'e mut self, query: E) -> ChanStream<'e, Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database> {
         MySqlConnection::fetch_many(self,query)
     }
 
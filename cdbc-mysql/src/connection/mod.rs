@@ -1,12 +1,14 @@
 use cdbc::utils::statement_cache::StatementCache;
 use cdbc::connection::{Connection};
 use cdbc::error::Error;
+use cdbc::executor::Executor;
 use crate::protocol::statement::StmtClose;
 use crate::protocol::text::{Ping, Quit};
 use crate::statement::MySqlStatementMetadata;
 use crate::{MySql, MySqlConnectOptions};
-use cdbc::transaction::Transaction;
+use cdbc::transaction::{Transaction, TransactionCallbacks, TransactionOptions};
 use std::fmt::{self, Debug, Formatter};
+use std::time::Duration;
 
 mod auth;
 mod establish;
@@ -19,6 +21,9 @@ pub(crate) use stream::{MySqlStream, Waiting};
 
 const MAX_PACKET_SIZE: u32 = 1024;
 
+// how long we wait to connect for killing a query
+const KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// A connection to a MySQL database.
 pub struct MySqlConnection {
     // underlying TCP stream,
@@ -31,6 +36,77 @@ pub struct MySqlConnection {
 
     // cache by query string to the statement id and metadata
     cache_statement: StatementCache<(u32, MySqlStatementMetadata)>,
+
+    // id of this connection (thread) on the server
+    // used to kill the running query
+    connection_id: u32,
+
+    // used to open the connection that kills the running query
+    options: MySqlConnectOptions,
+
+    // timeout of queries without their own
+    pub(crate) default_timeout: Option<Duration>,
+
+    // callbacks of the open transactions
+    pub(crate) transaction_callbacks: TransactionCallbacks,
+}
+
+impl MySqlConnection {
+    // called when a stream is dropped before its query completed;
+    // kill the query on the server and read the rest of its response
+    pub(crate) fn cancel_and_drain(&mut self) {
+        if self.stream.waiting.is_empty() || self.stream.broken {
+            return;
+        }
+
+        if let Err(e) = self.kill_query() {
+            log::debug!("error occurred while killing the query: {}", e);
+        }
+
+        self.drain();
+    }
+
+    // read the rest of the response of a dropped stream, errors of the abandoned query
+    // (e.g. the `ER_QUERY_INTERRUPTED` we asked for) are skipped;
+    // the connection is broken if it does not get back to a ready state
+    pub(crate) fn drain(&mut self) {
+        loop {
+            match self.stream.wait_until_ready() {
+                Ok(()) => break,
+                Err(Error::Database(_)) => continue,
+                Err(e) => {
+                    log::warn!("error occurred while draining a dropped stream: {}", e);
+                    self.stream.broken = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn kill_query(&self) -> Result<(), Error> {
+        kill_query(&self.options, self.connection_id)
+    }
+
+    // kills the running query of this connection from another coroutine, used by a
+    // `Watchdog` when the query runs past its deadline
+    pub(crate) fn killer(&self) -> impl FnOnce() + Send + 'static {
+        let options = self.options.clone();
+        let connection_id = self.connection_id;
+
+        move || {
+            if let Err(e) = kill_query(&options, connection_id) {
+                log::warn!("error occurred while killing a timed out query: {}", e);
+            }
+        }
+    }
+}
+
+fn kill_query(options: &MySqlConnectOptions, connection_id: u32) -> Result<(), Error> {
+    // https://dev.mysql.com/doc/refman/8.0/en/kill.html
+    // a KILL QUERY that arrives after the query finished is ignored by the server
+    let mut conn = MySqlConnection::establish(options, KILL_TIMEOUT)?;
+    conn.execute(&*format!("KILL QUERY {}", connection_id))?;
+    conn.close()
 }
 
 impl Debug for MySqlConnection {
@@ -52,6 +128,14 @@ impl Connection for MySqlConnection {
         }
     }
 
+    fn is_broken(&self) -> bool {
+        self.stream.broken
+    }
+
+    fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.default_timeout = timeout;
+    }
+
     fn ping(&mut self) -> Result<(), Error> {
         self.stream.wait_until_ready()?;
         self.stream.send_packet(Ping)?;
@@ -85,6 +169,10 @@ impl Connection for MySqlConnection {
         !self.stream.wbuf.is_empty()
     }
 
+    fn transaction_callbacks(&mut self) -> Option<&mut TransactionCallbacks> {
+        Some(&mut self.transaction_callbacks)
+    }
+
     fn begin(&mut self) -> Result<Transaction<'_, Self::Database>, Error>
         where
             Self: Sized,
@@ -92,4 +180,11 @@ impl Connection for MySqlConnection {
 
         Transaction::begin(self)
     }
+
+    fn begin_with(&mut self, options: TransactionOptions) -> Result<Transaction<'_, Self::Database>, Error>
+        where
+            Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
 }
@@ -22,6 +22,8 @@ pub struct MySqlStream {
     pub(crate) waiting: VecDeque<Waiting>,
     pub(crate) charset: CharSet,
     pub(crate) collation: Collation,
+    // set when a dropped stream left unread packets that could not be drained
+    pub(crate) broken: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -82,10 +84,17 @@ impl MySqlStream {
             collation,
             charset,
             stream: BufStream::new(MaybeTlsStream::Raw(socket)),
+            broken: false,
         })
     }
 
     pub(crate) fn wait_until_ready(&mut self) -> Result<(), Error> {
+        if self.broken {
+            return Err(err_protocol!(
+                "connection is broken: a dropped stream left it in an unknown state"
+            ));
+        }
+
         if !self.stream.wbuf.is_empty() {
             self.stream.flush()?;
         }
@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use cdbc::error::DatabaseError;
+use cdbc::error::{DatabaseError, ErrorKind};
 use crate::protocol::response::ErrPacket;
 use smallvec::alloc::borrow::Cow;
 
@@ -27,6 +27,90 @@ impl MySqlDatabaseError {
     pub fn message(&self) -> &str {
         &self.0.error_message
     }
+
+    /// The class of this error, read from its error number.
+    pub fn kind(&self) -> ErrorKind {
+        match self.number() {
+            // ER_DUP_ENTRY, ER_DUP_UNIQUE, ER_DUP_ENTRY_WITH_KEY_NAME
+            1062 | 1169 | 1586 => ErrorKind::UniqueViolation,
+            // ER_ROW_IS_REFERENCED(_2), ER_NO_REFERENCED_ROW(_2)
+            1216 | 1217 | 1451 | 1452 => ErrorKind::ForeignKeyViolation,
+            // ER_BAD_NULL_ERROR, ER_NO_DEFAULT_FOR_FIELD
+            1048 | 1364 => ErrorKind::NotNullViolation,
+            // ER_CHECK_CONSTRAINT_VIOLATED
+            3819 => ErrorKind::CheckViolation,
+            // ER_LOCK_DEADLOCK
+            1213 => ErrorKind::Deadlock,
+            // ER_LOCK_WAIT_TIMEOUT, ER_LOCK_NOWAIT
+            1205 | 3572 => ErrorKind::LockTimeout,
+            // ER_QUERY_INTERRUPTED, ER_QUERY_TIMEOUT
+            1317 | 3024 => ErrorKind::QueryCanceled,
+            // ER_SERVER_SHUTDOWN, ER_CONNECTION_KILLED
+            1053 | 1927 => ErrorKind::ConnectionLost,
+            // ER_OPTION_PREVENTS_STATEMENT (--read-only), ER_CANT_EXECUTE_IN_READ_ONLY_TRANSACTION
+            1290 | 1792 => ErrorKind::ReadOnly,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// The name of the violated constraint or unique key, read from the error message.
+    pub fn constraint(&self) -> Option<&str> {
+        let message = self.message();
+        match self.number() {
+            // Duplicate entry 'a' for key 'users.email' (the table is only named since 8.0)
+            1062 | 1586 => {
+                let key = duplicate_key(message)?;
+                Some(key.rsplit('.').next().unwrap_or(key))
+            }
+            // Cannot add or update a child row: a foreign key constraint fails
+            // (`db`.`child`, CONSTRAINT `fk` FOREIGN KEY (`pid`) REFERENCES `parent` (`id`))
+            1451 | 1452 => between(message, "CONSTRAINT `", '`'),
+            // Check constraint 'c' is violated.
+            3819 => between(message, "constraint '", '\''),
+            _ => None,
+        }
+    }
+
+    /// The name of the table, read from the error message.
+    pub fn table(&self) -> Option<&str> {
+        let message = self.message();
+        match self.number() {
+            1062 | 1586 => {
+                let key = duplicate_key(message)?;
+                key.rfind('.').map(|dot| &key[..dot])
+            }
+            1451 | 1452 => between(message, "`.`", '`'),
+            _ => None,
+        }
+    }
+
+    /// The name of the column, read from the error message.
+    pub fn column(&self) -> Option<&str> {
+        let message = self.message();
+        match self.number() {
+            1451 | 1452 => between(message, "FOREIGN KEY (`", '`'),
+            // Column 'name' cannot be null
+            1048 => between(message, "Column '", '\''),
+            // Field 'name' doesn't have a default value
+            1364 => between(message, "Field '", '\''),
+            _ => None,
+        }
+    }
+}
+
+/// The text following `open` up to `close`.
+fn between<'a>(message: &'a str, open: &str, close: char) -> Option<&'a str> {
+    let start = message.find(open)? + open.len();
+    let rest = &message[start..];
+    Some(&rest[..rest.find(close)?])
+}
+
+/// The key of a duplicate entry error, the entry itself may contain anything.
+fn duplicate_key(message: &str) -> Option<&str> {
+    const FOR_KEY: &str = " for key '";
+    let start = message.rfind(FOR_KEY)? + FOR_KEY.len();
+    let rest = &message[start..];
+    Some(&rest[..rest.rfind('\'')?])
 }
 
 impl Debug for MySqlDatabaseError {
@@ -76,4 +160,67 @@ impl DatabaseError for MySqlDatabaseError {
     fn into_error(self: Box<Self>) -> Box<dyn Error + Send + Sync + 'static> {
         self
     }
+
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        self.kind()
+    }
+
+    #[inline]
+    fn constraint(&self) -> Option<&str> {
+        self.constraint()
+    }
+
+    #[inline]
+    fn table(&self) -> Option<&str> {
+        self.table()
+    }
+
+    #[inline]
+    fn column(&self) -> Option<&str> {
+        self.column()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::error::ErrorKind;
+    use crate::protocol::response::ErrPacket;
+    use crate::MySqlDatabaseError;
+
+    fn error(error_code: u16, error_message: &str) -> MySqlDatabaseError {
+        MySqlDatabaseError(ErrPacket {
+            error_code,
+            sql_state: Some("23000".to_owned()),
+            error_message: error_message.to_owned(),
+        })
+    }
+
+    #[test]
+    fn test_kind() {
+        let err = error(1062, "Duplicate entry 'it's' for key 'users.email'");
+        assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+        assert_eq!(err.constraint(), Some("email"));
+        assert_eq!(err.table(), Some("users"));
+
+        let err = error(1062, "Duplicate entry 'a' for key 'email'");
+        assert_eq!(err.constraint(), Some("email"));
+        assert_eq!(err.table(), None);
+
+        let err = error(1452, "Cannot add or update a child row: a foreign key constraint fails (`db`.`child`, CONSTRAINT `child_ibfk_1` FOREIGN KEY (`pid`) REFERENCES `parent` (`id`))");
+        assert_eq!(err.kind(), ErrorKind::ForeignKeyViolation);
+        assert_eq!(err.constraint(), Some("child_ibfk_1"));
+        assert_eq!(err.table(), Some("child"));
+        assert_eq!(err.column(), Some("pid"));
+
+        let err = error(1048, "Column 'name' cannot be null");
+        assert_eq!(err.kind(), ErrorKind::NotNullViolation);
+        assert_eq!(err.column(), Some("name"));
+
+        let err = error(3819, "Check constraint 'age_positive' is violated.");
+        assert_eq!(err.kind(), ErrorKind::CheckViolation);
+        assert_eq!(err.constraint(), Some("age_positive"));
+
+        assert_eq!(error(1213, "Deadlock found").kind(), ErrorKind::Deadlock);
+    }
 }
@@ -16,11 +16,15 @@ mod connection;
 mod database;
 mod error;
 mod io;
+mod migrate;
 mod options;
 mod protocol;
 mod query_result;
+#[cfg(feature = "offline")]
+mod replay;
 mod row;
 mod statement;
+mod testing;
 mod transaction;
 mod type_info;
 pub mod types;
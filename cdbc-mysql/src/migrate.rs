@@ -0,0 +1,184 @@
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::executor::Executor;
+use cdbc::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::{MySql, MySqlConnectOptions, MySqlConnection};
+use cdbc::query::query;
+use cdbc::query_as::query_as;
+use cdbc::query_scalar::query_scalar;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+
+fn parse_for_maintenance(url: &str) -> Result<(MySqlConnectOptions, String), Error> {
+    let mut options = MySqlConnectOptions::from_str(url)?;
+
+    let database = options.database.take().ok_or_else(|| {
+        Error::Configuration("the connection url has no database".into())
+    })?;
+
+    // connect without a database, mysql doesn't need a maintenance database
+    Ok((options, database))
+}
+
+pub(crate) fn quote_ident(database: &str) -> String {
+    format!("`{}`", database.replace('`', "``"))
+}
+
+impl MigrateDatabase for MySql {
+    fn create_database(url: &str) -> Result<(), Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = MySqlConnection::connect_with(&options)?;
+
+        let _ = conn.execute(&*format!("CREATE DATABASE {}", quote_ident(&database)))?;
+
+        Ok(())
+    }
+
+    fn database_exists(url: &str) -> Result<bool, Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = MySqlConnection::connect_with(&options)?;
+
+        let exists: bool = query_scalar(
+            "select exists(SELECT 1 from INFORMATION_SCHEMA.SCHEMATA WHERE SCHEMA_NAME = ?)",
+        )
+        .bind(database)
+        .fetch_one(&mut conn)?;
+
+        Ok(exists)
+    }
+
+    fn drop_database(url: &str) -> Result<(), Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = MySqlConnection::connect_with(&options)?;
+
+        let _ = conn.execute(&*format!("DROP DATABASE IF EXISTS {}", quote_ident(&database)))?;
+
+        Ok(())
+    }
+}
+
+impl Migrate for MySqlConnection {
+    fn ensure_migrations_table(&mut self) -> Result<(), MigrateError> {
+        // language=MySQL
+        self.execute(
+            r#"
+CREATE TABLE IF NOT EXISTS _cdbc_migrations (
+    version BIGINT PRIMARY KEY,
+    description TEXT NOT NULL,
+    installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    success BOOLEAN NOT NULL,
+    checksum VARCHAR(96) NOT NULL,
+    execution_time BIGINT NOT NULL
+);
+                "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError> {
+        // language=SQL
+        let row: Option<(i64,)> = query_as(
+            "SELECT version FROM _cdbc_migrations WHERE success = false ORDER BY version LIMIT 1",
+        )
+        .fetch_optional(self)?;
+
+        Ok(row.map(|r| r.0))
+    }
+
+    fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError> {
+        // language=SQL
+        let rows: Vec<(i64, String)> =
+            query_as("SELECT version, checksum FROM _cdbc_migrations ORDER BY version")
+                .fetch_all(self)?;
+
+        let migrations = rows
+            .into_iter()
+            .map(|(version, checksum)| AppliedMigration {
+                version,
+                checksum: checksum.into(),
+            })
+            .collect();
+
+        Ok(migrations)
+    }
+
+    fn lock(&mut self) -> Result<(), MigrateError> {
+        // create an application lock over the database
+        // this function will not return until the lock is acquired
+
+        // https://dev.mysql.com/doc/refman/8.0/en/locking-functions.html
+        // lock names are limited to 64 characters, so the database name is hashed
+
+        // language=MySQL
+        let _ = query("SELECT GET_LOCK(SHA1(CONCAT('_cdbc_migrations.', DATABASE())), -1)")
+            .execute(self)?;
+
+        Ok(())
+    }
+
+    fn unlock(&mut self) -> Result<(), MigrateError> {
+        // language=MySQL
+        let _ = query("SELECT RELEASE_LOCK(SHA1(CONCAT('_cdbc_migrations.', DATABASE())))")
+            .execute(self)?;
+
+        Ok(())
+    }
+
+    fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        // DDL statements commit implicitly in mysql, so the migration is recorded as not
+        // successful first and marked successful after it ran. if it fails half way the
+        // row stays dirty and the next run refuses to continue.
+        let start = Instant::now();
+
+        // language=MySQL
+        let _ = query(
+            r#"
+    INSERT INTO _cdbc_migrations ( version, description, success, checksum, execution_time )
+    VALUES ( ?, ?, FALSE, ?, -1 )
+                "#,
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(&mut *self)?;
+
+        let _ = self.execute(&*migration.sql)?;
+
+        let elapsed = start.elapsed();
+
+        // language=MySQL
+        let _ = query(
+            "UPDATE _cdbc_migrations SET success = TRUE, execution_time = ? WHERE version = ?",
+        )
+        .bind(elapsed.as_nanos() as i64)
+        .bind(migration.version)
+        .execute(self)?;
+
+        Ok(elapsed)
+    }
+
+    fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        let down_sql = migration
+            .down_sql
+            .as_deref()
+            .ok_or(MigrateError::Irreversible(migration.version))?;
+
+        let start = Instant::now();
+
+        // language=MySQL
+        let _ = query("UPDATE _cdbc_migrations SET success = FALSE WHERE version = ?")
+            .bind(migration.version)
+            .execute(&mut *self)?;
+
+        let _ = self.execute(down_sql)?;
+
+        // language=MySQL
+        let _ = query("DELETE FROM _cdbc_migrations WHERE version = ?")
+            .bind(migration.version)
+            .execute(self)?;
+
+        Ok(start.elapsed())
+    }
+}
@@ -7,5 +7,5 @@ mod stmt_close;
 pub(crate) use execute::Execute;
 pub(crate) use prepare::Prepare;
 pub(crate) use prepare_ok::PrepareOk;
-pub(crate) use row::BinaryRow;
+pub(crate) use row::{binary_value_size, BinaryRow};
 pub(crate) use stmt_close::StmtClose;
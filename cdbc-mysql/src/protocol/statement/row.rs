@@ -45,40 +45,7 @@ impl<'de> Decode<'de, &'de [MySqlColumn]> for BinaryRow {
             // NOTE: MySQL will never generate NULL types for non-NULL values
             let type_info = &column.type_info;
 
-            let size: usize = match type_info.r#type {
-                ColumnType::String
-                | ColumnType::VarChar
-                | ColumnType::VarString
-                | ColumnType::Enum
-                | ColumnType::Set
-                | ColumnType::LongBlob
-                | ColumnType::MediumBlob
-                | ColumnType::Blob
-                | ColumnType::TinyBlob
-                | ColumnType::Geometry
-                | ColumnType::Bit
-                | ColumnType::Decimal
-                | ColumnType::Json
-                | ColumnType::NewDecimal => buf.get_uint_lenenc() as usize,
-
-                ColumnType::LongLong => 8,
-                ColumnType::Long | ColumnType::Int24 => 4,
-                ColumnType::Short | ColumnType::Year => 2,
-                ColumnType::Tiny => 1,
-                ColumnType::Float => 4,
-                ColumnType::Double => 8,
-
-                ColumnType::Time
-                | ColumnType::Timestamp
-                | ColumnType::Date
-                | ColumnType::Datetime => {
-                    // The size of this type is important for decoding
-                    buf[0] as usize + 1
-                }
-
-                // NOTE: MySQL will never generate NULL types for non-NULL values
-                ColumnType::Null => unreachable!(),
-            };
+            let size = binary_value_size(type_info.r#type, &mut buf);
 
             let offset = offset - buf.len();
 
@@ -90,3 +57,42 @@ impl<'de> Decode<'de, &'de [MySqlColumn]> for BinaryRow {
         Ok(BinaryRow(Row { values, storage }))
     }
 }
+
+/// The size of the next non-NULL value of type `ty` in `buf`, reading the length of a
+/// length-encoded value. Arguments are sent in the same encoding.
+pub(crate) fn binary_value_size(ty: ColumnType, buf: &mut Bytes) -> usize {
+    match ty {
+        ColumnType::String
+        | ColumnType::VarChar
+        | ColumnType::VarString
+        | ColumnType::Enum
+        | ColumnType::Set
+        | ColumnType::LongBlob
+        | ColumnType::MediumBlob
+        | ColumnType::Blob
+        | ColumnType::TinyBlob
+        | ColumnType::Geometry
+        | ColumnType::Bit
+        | ColumnType::Decimal
+        | ColumnType::Json
+        | ColumnType::NewDecimal => buf.get_uint_lenenc() as usize,
+
+        ColumnType::LongLong => 8,
+        ColumnType::Long | ColumnType::Int24 => 4,
+        ColumnType::Short | ColumnType::Year => 2,
+        ColumnType::Tiny => 1,
+        ColumnType::Float => 4,
+        ColumnType::Double => 8,
+
+        ColumnType::Time
+        | ColumnType::Timestamp
+        | ColumnType::Date
+        | ColumnType::Datetime => {
+            // The size of this type is important for decoding
+            buf[0] as usize + 1
+        }
+
+        // NOTE: MySQL will never generate NULL types for non-NULL values
+        ColumnType::Null => unreachable!(),
+    }
+}
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use cdbc::error::Error;
+use cdbc::replay::{RecordedResult, RecordedValue, Replay};
+use cdbc::row::Row as _;
+use cdbc::type_info::TypeInfo;
+use serde::Deserialize;
+use crate::protocol::statement::binary_value_size;
+use crate::protocol::Row;
+use crate::{MySql, MySqlArguments, MySqlColumn, MySqlQueryResult, MySqlRow, MySqlValueFormat};
+
+/// The columns of a recorded result, with the format of its values.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RecordedColumns {
+    format: RecordedFormat,
+    columns: Vec<MySqlColumn>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedFormat {
+    Text,
+    Binary,
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(value).map_err(|e| Error::Decode(e.into()))
+}
+
+/// Values are recorded as the hex of their bytes, in the format MySQL sent them.
+impl Replay for MySql {
+    /// A row without values, sharing the columns of the replayed rows.
+    type Columns = MySqlRow;
+
+    fn record_arguments(arguments: &MySqlArguments) -> Vec<RecordedValue> {
+        let mut buf = Bytes::copy_from_slice(&arguments.values);
+        arguments
+            .types
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| {
+                let is_null = arguments.null_bitmap[index / 8] & (1 << (index % 8)) != 0;
+                let value = if is_null {
+                    None
+                } else {
+                    // the length prefix of a length-encoded value is skipped
+                    let size = binary_value_size(ty.r#type, &mut buf);
+                    Some(hex::encode(buf.split_to(size)))
+                };
+                RecordedValue {
+                    type_name: ty.name().to_owned(),
+                    value,
+                }
+            })
+            .collect()
+    }
+
+    fn record_columns(row: &MySqlRow) -> serde_json::Value {
+        let columns = RecordedColumns {
+            format: match row.format {
+                MySqlValueFormat::Text => RecordedFormat::Text,
+                MySqlValueFormat::Binary => RecordedFormat::Binary,
+            },
+            columns: row.columns.to_vec(),
+        };
+        serde_json::to_value(columns).unwrap_or_default()
+    }
+
+    fn record_row(row: &MySqlRow) -> Vec<Option<String>> {
+        (0..row.len()).map(|i| row.row.get(i).map(hex::encode)).collect()
+    }
+
+    fn record_result(result: &MySqlQueryResult) -> RecordedResult {
+        RecordedResult {
+            rows_affected: result.rows_affected,
+            last_insert_id: Some(result.last_insert_id as i64),
+        }
+    }
+
+    fn replay_columns(columns: &serde_json::Value) -> Result<Self::Columns, Error> {
+        let recorded = RecordedColumns::deserialize(columns).map_err(|e| Error::Decode(e.into()))?;
+        let column_names = recorded
+            .columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ordinal))
+            .collect();
+        let format = match recorded.format {
+            RecordedFormat::Text => MySqlValueFormat::Text,
+            RecordedFormat::Binary => MySqlValueFormat::Binary,
+        };
+        Ok(MySqlRow {
+            row: Row {
+                storage: Bytes::new(),
+                values: Vec::new(),
+            },
+            format,
+            columns: Arc::new(recorded.columns),
+            column_names: Arc::new(column_names),
+        })
+    }
+
+    fn replay_row(columns: &MySqlRow, values: &[Option<String>]) -> Result<MySqlRow, Error> {
+        if values.len() != columns.columns.len() {
+            return Err(err_protocol!(
+                "recorded row has {} values for {} columns",
+                values.len(),
+                columns.columns.len()
+            ));
+        }
+        let mut storage = Vec::new();
+        let mut ranges = Vec::with_capacity(values.len());
+        for value in values {
+            ranges.push(match value {
+                Some(value) => {
+                    let start = storage.len();
+                    storage.extend(decode_hex(value)?);
+                    Some(start..storage.len())
+                }
+                None => None,
+            });
+        }
+        Ok(MySqlRow {
+            row: Row {
+                storage: Bytes::from(storage),
+                values: ranges,
+            },
+            format: columns.format,
+            columns: columns.columns.clone(),
+            column_names: columns.column_names.clone(),
+        })
+    }
+
+    fn replay_result(result: &RecordedResult) -> MySqlQueryResult {
+        MySqlQueryResult {
+            rows_affected: result.rows_affected,
+            last_insert_id: result.last_insert_id.unwrap_or_default() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::replay::{RecordedValue, Replay};
+    use cdbc::row::Row;
+    use crate::{MySql, MySqlArguments};
+
+    #[test]
+    fn test_record_arguments() {
+        let mut arguments = MySqlArguments::default();
+        arguments.add(7_i32);
+        arguments.add(None::<String>);
+        arguments.add("tom");
+        arguments.add(2_i64);
+        assert_eq!(
+            MySql::record_arguments(&arguments),
+            vec![
+                RecordedValue { type_name: "INT".into(), value: Some("07000000".into()) },
+                RecordedValue { type_name: "VARCHAR".into(), value: None },
+                RecordedValue { type_name: "VARCHAR".into(), value: Some("746f6d".into()) },
+                RecordedValue { type_name: "BIGINT".into(), value: Some("0200000000000000".into()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replay_row() {
+        let columns = serde_json::json!({
+            "format": "binary",
+            "columns": [
+                {"ordinal": 0, "name": "id", "type_info": {"type": "Long", "flags": {"bits": 0}, "char_set": 63}},
+                {"ordinal": 1, "name": "name", "type_info": {"type": "VarString", "flags": {"bits": 0}, "char_set": 224}},
+            ],
+        });
+        let values = vec![Some("07000000".to_string()), None];
+        let replayed = MySql::replay_columns(&columns).unwrap();
+        let row = MySql::replay_row(&replayed, &values).unwrap();
+        assert_eq!(row.get::<i32, _>("id"), 7);
+        assert_eq!(row.get::<Option<String>, _>("name"), None);
+        assert_eq!(MySql::record_row(&row), values);
+        let recorded = MySql::replay_columns(&MySql::record_columns(&row)).unwrap();
+        assert_eq!(MySql::record_row(&MySql::replay_row(&recorded, &values).unwrap()), values);
+    }
+}
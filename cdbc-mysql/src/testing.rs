@@ -0,0 +1,35 @@
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::executor::Executor;
+use cdbc::testing::{database_url, test_db_name, TestArgs, TestContext, TestSupport};
+use crate::migrate::quote_ident;
+use crate::{MySql, MySqlConnectOptions, MySqlConnection};
+use std::str::FromStr;
+
+impl TestSupport for MySql {
+    fn test_context(args: &TestArgs) -> Result<TestContext<Self>, Error> {
+        // mysql has no template databases, the test database starts empty
+        args.reject_template("mysql")?;
+        let master_options = MySqlConnectOptions::from_str(&database_url()?)?;
+        let mut conn = MySqlConnection::connect_with(&master_options)?;
+
+        let db_name = test_db_name();
+        let _ = conn.execute(&*format!("CREATE DATABASE {}", quote_ident(&db_name)))?;
+        conn.close()?;
+
+        let mut connect_options = master_options.clone();
+        connect_options.database = Some(db_name.clone());
+        Ok(TestContext {
+            db_name,
+            connect_options,
+            master_options,
+            keep_alive: None,
+        })
+    }
+
+    fn cleanup_test(db_name: &str, master_options: &MySqlConnectOptions) -> Result<(), Error> {
+        let mut conn = MySqlConnection::connect_with(master_options)?;
+        let _ = conn.execute(&*format!("DROP DATABASE IF EXISTS {}", quote_ident(db_name)))?;
+        conn.close()
+    }
+}
@@ -5,8 +5,8 @@ use crate::connection::Waiting;
 use crate::protocol::text::Query;
 use crate::{MySql, MySqlConnection};
 use cdbc::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    begin_ansi_transaction_sql, check_savepoint_options, commit_ansi_transaction_sql,
+    rollback_ansi_transaction_sql, IsolationLevel, TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for MySQL.
@@ -15,9 +15,22 @@ pub struct MySqlTransactionManager;
 impl TransactionManager for MySqlTransactionManager {
     type Database = MySql;
 
-    fn begin(conn: &mut MySqlConnection) ->  Result<(), Error> {
+    fn begin_with(conn: &mut MySqlConnection, options: &TransactionOptions) ->  Result<(), Error> {
             let depth = conn.transaction_depth;
-            conn.execute(&*begin_ansi_transaction_sql(depth))?;
+            check_savepoint_options(depth, options)?;
+            if depth == 0 && !options.is_default() {
+                // `SET TRANSACTION` without `SESSION` only applies to the next transaction
+                if let Some(sql) = set_transaction_sql(options) {
+                    conn.execute(&*sql)?;
+                }
+                if options.isolation_level == Some(IsolationLevel::Snapshot) {
+                    conn.execute("START TRANSACTION WITH CONSISTENT SNAPSHOT")?;
+                } else {
+                    conn.execute(&*begin_ansi_transaction_sql(depth))?;
+                }
+            } else {
+                conn.execute(&*begin_ansi_transaction_sql(depth))?;
+            }
             conn.transaction_depth = depth + 1;
 
             Ok(())
@@ -53,4 +66,63 @@ impl TransactionManager for MySqlTransactionManager {
             conn.transaction_depth = depth - 1;
         }
     }
+
+    // MySQL quotes identifiers with backticks
+
+    fn savepoint(conn: &mut MySqlConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*format!("SAVEPOINT `{}`", name))?;
+        Ok(())
+    }
+
+    fn release_savepoint(conn: &mut MySqlConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*format!("RELEASE SAVEPOINT `{}`", name))?;
+        Ok(())
+    }
+
+    fn rollback_to_savepoint(conn: &mut MySqlConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*format!("ROLLBACK TO SAVEPOINT `{}`", name))?;
+        Ok(())
+    }
+}
+
+/// `SET TRANSACTION ISOLATION LEVEL .., READ ONLY`, `DEFERRABLE` is ignored.
+fn set_transaction_sql(options: &TransactionOptions) -> Option<String> {
+    let mut characteristics = Vec::new();
+    if let Some(level) = options.isolation_level {
+        let level = match level {
+            IsolationLevel::Snapshot => IsolationLevel::RepeatableRead,
+            level => level,
+        };
+        characteristics.push(format!("ISOLATION LEVEL {}", level.as_sql()));
+    }
+    if let Some(mode) = options.access_mode {
+        characteristics.push(mode.as_sql().to_owned());
+    }
+    if characteristics.is_empty() {
+        return None;
+    }
+    Some(format!("SET TRANSACTION {}", characteristics.join(", ")))
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::transaction::{IsolationLevel, TransactionOptions};
+    use super::set_transaction_sql;
+
+    #[test]
+    fn test_set_transaction_sql() {
+        let options = TransactionOptions::new()
+            .isolation_level(IsolationLevel::Serializable)
+            .read_only();
+        assert_eq!(
+            set_transaction_sql(&options).unwrap(),
+            "SET TRANSACTION ISOLATION LEVEL SERIALIZABLE, READ ONLY"
+        );
+        let options = TransactionOptions::new().isolation_level(IsolationLevel::Snapshot);
+        assert_eq!(
+            set_transaction_sql(&options).unwrap(),
+            "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ"
+        );
+        assert!(set_transaction_sql(&TransactionOptions::new().deferrable(true)).is_none());
+    }
 }
@@ -1,5 +1,6 @@
 use cdbc::HashMap;
 
+use cdbc::transaction::TransactionCallbacks;
 use cdbc::utils::statement_cache::StatementCache;
 use cdbc::error::Error;
 use cdbc::io::Decode;
@@ -139,6 +140,8 @@ impl PgConnection {
             stream,
             process_id,
             secret_key,
+            host: options.host.clone(),
+            port: options.port,
             transaction_status,
             transaction_depth: 0,
             pending_ready_for_query_count: 0,
@@ -146,6 +149,9 @@ impl PgConnection {
             cache_statement: StatementCache::new(options.statement_cache_capacity),
             cache_type_oid: HashMap::new(),
             cache_type_info: HashMap::new(),
+            broken: false,
+            default_timeout: None,
+            transaction_callbacks: TransactionCallbacks::default(),
         })
     }
 }
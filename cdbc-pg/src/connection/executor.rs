@@ -13,8 +13,10 @@ use crate::{
 };
 use either::Either;
 use std::{borrow::Cow, sync::Arc};
+use std::time::{Duration, Instant};
+use cdbc::deadline::{self, Watchdog};
 use cdbc::database::{Database, HasStatement};
-use cdbc::io::chan_stream::{ChanStream, TryStream};
+use cdbc::io::chan_stream::ChanStream;
 fn prepare(
     conn: &mut PgConnection,
     sql: &str,
@@ -187,19 +189,16 @@ impl PgConnection {
         Ok(statement)
     }
 
-    fn run<'e, 'c: 'e, 'q: 'e>(
-        &'c mut self,
-        query: &'q str,
+    // prepare (if needed) and send the query, returns the format and metadata of its rows
+    fn send_query(
+        &mut self,
+        query: &str,
         arguments: Option<PgArguments>,
         limit: u8,
         persistent: bool,
         metadata_opt: Option<Arc<PgStatementMetadata>>,
-    ) -> Result<ChanStream<Either<PgQueryResult, PgRow>>, Error> {
-
-        // before we continue, wait until we are "ready" to accept more queries
-        self.wait_until_ready()?;
-
-        let mut metadata: Arc<PgStatementMetadata>;
+    ) -> Result<(PgValueFormat, Arc<PgStatementMetadata>), Error> {
+        let metadata: Arc<PgStatementMetadata>;
 
         let format = if let Some(mut arguments) = arguments {
             // prepare the statement if this our first time executing it
@@ -257,9 +256,39 @@ impl PgConnection {
 
         self.stream.flush()?;
 
+        Ok((format, metadata))
+    }
+
+    fn run<'e, 'c: 'e, 'q: 'e>(
+        &'c mut self,
+        query: &'q str,
+        arguments: Option<PgArguments>,
+        limit: u8,
+        persistent: bool,
+        metadata_opt: Option<Arc<PgStatementMetadata>>,
+        deadline: Option<Instant>,
+    ) -> Result<ChanStream<'e, Either<PgQueryResult, PgRow>>, Error> {
+        deadline::check(deadline)?;
+
+        // before we continue, wait until we are "ready" to accept more queries
+        self.wait_until_ready()?;
+
+        // cancels the query on the server if it is still running at the deadline
+        let watchdog = Watchdog::start(deadline, self.canceller());
+
+        let (format, mut metadata) = watchdog.check(
+            self.send_query(query, arguments, limit, persistent, metadata_opt),
+        )?;
+
+        // the protocol-level limit only applies to prepared statements, the rest of a limited
+        // portal is short enough to read it instead of cancelling the query
+        let bounded = limit > 0 && format == PgValueFormat::Binary;
+
         Ok(chan_stream!{
+            on_close: if bounded { self.drain() } else { self.cancel_and_drain() };
+
             loop {
-                let message = self.stream.recv()?;
+                let message = watchdog.check(self.stream.recv())?;
 
                 match message.format {
                     MessageFormat::BindComplete
@@ -330,23 +359,27 @@ impl PgConnection {
 impl Executor for PgConnection {
     type Database = Postgres;
 
-    fn fetch_many< 'q, E: 'q>(
-        &mut self,
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         mut query: E,
-    ) -> ChanStream<Either<PgQueryResult, PgRow>>
+    ) -> ChanStream<'e, Either<PgQueryResult, PgRow>>
     where
         E: Execute<'q, Self::Database>,
     {
         let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
 
-        chan_stream!({
-            let mut s = self.run(query.sql(), arguments, 0, persistent, metadata)?;
-            while let Some(v) = s.try_next()? {
-                r#yield!(v);
+        ChanStream::owned(query, move |query, _| {
+            match self.run(query.sql(), arguments, 0, persistent, metadata, deadline) {
+                Ok(s) => s,
+                Err(e) => ChanStream::new(|_| Err(e)),
             }
-            Ok(())
         })
     }
 
@@ -359,7 +392,8 @@ impl Executor for PgConnection {
         let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
-            let mut s = self.run(query.sql(), arguments, 1, persistent, metadata)?;
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
+            let mut s = self.run(query.sql(), arguments, 1, persistent, metadata, deadline)?;
             while let Some(s) = s.try_next()? {
                 if let Either::Right(r) = s {
                     return Ok(Some(r));
@@ -409,7 +443,11 @@ impl Executor for PgConnection {
 impl Executor for &mut PgConnection{
     type Database = Postgres;
 
-    fn fetch_many<'q, E: 'q>(&mut self, query: E) -> ChanStream<Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database> {
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(&'e mut self, query: E) -> ChanStream<'e, Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database> {
         PgConnection::fetch_many(self,query)
     }
 
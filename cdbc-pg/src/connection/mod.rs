@@ -1,5 +1,7 @@
 use std::fmt::{self, Debug, Formatter};
+use std::io::{Read, Write};
 use std::sync::Arc;
+use std::time::Duration;
 
 use cdbc::HashMap;
 use cdbc::utils::statement_cache::StatementCache;
@@ -7,13 +9,14 @@ use cdbc::connection::{Connection};
 use cdbc::error::Error;
 use cdbc::executor::Executor;
 use cdbc::utils::ustr::UStr;
-use cdbc::io::Decode;
+use cdbc::io::{Decode, Encode};
+use cdbc::net::Socket;
 use crate::message::{
-    Close, Message, MessageFormat, ReadyForQuery, Terminate, TransactionStatus,
+    CancelRequest, Close, Message, MessageFormat, ReadyForQuery, Terminate, TransactionStatus,
 };
 use crate::statement::PgStatementMetadata;
 use crate::{PgConnectOptions, PgTypeInfo, Postgres};
-use cdbc::transaction::Transaction;
+use cdbc::transaction::{Transaction, TransactionCallbacks, TransactionOptions};
 
 pub use self::stream::PgStream;
 
@@ -24,6 +27,9 @@ mod sasl;
 mod stream;
 mod tls;
 
+// how long we wait for the server to take a cancel request
+const CANCEL_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// A connection to a PostgreSQL database.
 pub struct PgConnection {
     // underlying TCP or UDS stream,
@@ -33,14 +39,17 @@ pub struct PgConnection {
 
     // process id of this backend
     // used to send cancel requests
-    #[allow(dead_code)]
     process_id: u32,
 
     // secret key of this backend
     // used to send cancel requests
-    #[allow(dead_code)]
     secret_key: u32,
 
+    // address of the server
+    // used to send cancel requests
+    host: String,
+    port: u16,
+
     // sequence of statement IDs for use in preparing statements
     // in PostgreSQL, the statement is prepared to a user-supplied identifier
     next_statement_id: u32,
@@ -58,11 +67,26 @@ pub struct PgConnection {
     // current transaction status
     transaction_status: TransactionStatus,
     pub(crate) transaction_depth: usize,
+
+    // set when a dropped stream left unread messages that could not be drained
+    broken: bool,
+
+    // timeout of queries without their own
+    pub(crate) default_timeout: Option<Duration>,
+
+    // callbacks of the open transactions
+    pub(crate) transaction_callbacks: TransactionCallbacks,
 }
 
 impl PgConnection {
     // will return when the connection is ready for another query
     pub fn wait_until_ready(&mut self) -> Result<(), Error> {
+        if self.broken {
+            return Err(err_protocol!(
+                "connection is broken: a dropped stream left it in an unknown state"
+            ));
+        }
+
         if !self.stream.wbuf.is_empty() {
             self.stream.flush()?;
         }
@@ -78,6 +102,57 @@ impl PgConnection {
         Ok(())
     }
 
+    // called when a stream is dropped before its query completed;
+    // cancel the query on the server and read the rest of its response
+    pub(crate) fn cancel_and_drain(&mut self) {
+        if self.pending_ready_for_query_count == 0 || self.broken {
+            return;
+        }
+
+        // a cancelled query fails the whole transaction block, so inside one we only drain
+        if !matches!(self.transaction_status, TransactionStatus::Transaction) {
+            if let Err(e) = self.cancel_request() {
+                log::debug!("error occurred while sending a cancel request: {}", e);
+            }
+        }
+
+        self.drain();
+    }
+
+    // read the rest of the response of a dropped stream, errors of the abandoned query
+    // (e.g. the `query_canceled` we asked for) are skipped;
+    // the connection is broken if it does not get back to a ready state
+    pub(crate) fn drain(&mut self) {
+        loop {
+            match self.wait_until_ready() {
+                Ok(()) => break,
+                Err(Error::Database(_)) => continue,
+                Err(e) => {
+                    log::warn!("error occurred while draining a dropped stream: {}", e);
+                    self.broken = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn cancel_request(&self) -> Result<(), Error> {
+        cancel_request(&self.host, self.port, self.process_id, self.secret_key)
+    }
+
+    // cancels the running query of this connection from another coroutine, used by a
+    // `Watchdog` when the query runs past its deadline
+    pub(crate) fn canceller(&self) -> impl FnOnce() + Send + 'static {
+        let host = self.host.clone();
+        let (port, process_id, secret_key) = (self.port, self.process_id, self.secret_key);
+
+        move || {
+            if let Err(e) = cancel_request(&host, port, process_id, secret_key) {
+                log::warn!("error occurred while cancelling a timed out query: {}", e);
+            }
+        }
+    }
+
     fn recv_ready_for_query(&mut self) -> Result<(), Error> {
         let r: ReadyForQuery = self
             .stream
@@ -98,6 +173,33 @@ impl PgConnection {
     }
 }
 
+// https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.9
+fn cancel_request(host: &str, port: u16, process_id: u32, secret_key: u32) -> Result<(), Error> {
+    if process_id == 0 && secret_key == 0 {
+        // the server (or a proxy in between) did not send a BackendKeyData
+        return Ok(());
+    }
+
+    let mut socket = Socket::connect_tcp_timeout(host, port, CANCEL_TIMEOUT)?;
+    socket.inner.set_read_timeout(Some(CANCEL_TIMEOUT))?;
+
+    let mut buf = Vec::with_capacity(16);
+    CancelRequest {
+        process_id,
+        secret_key,
+    }
+    .encode(&mut buf);
+
+    socket.write_all(&buf)?;
+    socket.flush()?;
+
+    // the server closes the connection once the backend was signalled;
+    // wait for that, so the cancel can not hit a later query of this connection
+    let _ = socket.read(&mut [0; 1]);
+
+    Ok(())
+}
+
 impl Debug for PgConnection {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("PgConnection").finish()
@@ -121,6 +223,14 @@ impl Connection for PgConnection {
             Ok(())
     }
 
+    fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.default_timeout = timeout;
+    }
+
     fn ping(&mut self) ->  Result<(), Error> {
         // By sending a comment we avoid an error if the connection was in the middle of a rowset
         self.execute("/* SQLx ping */")?;
@@ -134,6 +244,13 @@ impl Connection for PgConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(&mut self, options: TransactionOptions) -> Result<Transaction<'_, Self::Database>, Error>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.cache_statement.len()
     }
@@ -168,6 +285,10 @@ impl Connection for PgConnection {
     fn should_flush(&self) -> bool {
         !self.stream.wbuf.is_empty()
     }
+
+    fn transaction_callbacks(&mut self) -> Option<&mut TransactionCallbacks> {
+        Some(&mut self.transaction_callbacks)
+    }
 }
 
 pub trait PgConnectionInfo {
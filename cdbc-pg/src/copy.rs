@@ -53,14 +53,14 @@ impl PgConnection {
     pub fn copy_out_raw<'c>(
         &'c mut self,
         statement: &str,
-    ) -> Result<ChanStream<Bytes>> {
+    ) -> Result<ChanStream<'c, Bytes>> {
         pg_begin_copy_out(self, statement)
     }
 }
 
 pub trait CopyRaw{
      fn copy_in_raw(&self, statement: &str) -> Result<PgCopyIn<PoolConnection<Postgres>>>;
-    fn copy_out_raw(&self, statement: &str) -> Result<ChanStream<Bytes>>;
+    fn copy_out_raw(&self, statement: &str) -> Result<ChanStream<'static, Bytes>>;
 }
 
 impl CopyRaw for Pool<Postgres> {
@@ -102,7 +102,7 @@ impl CopyRaw for Pool<Postgres> {
     ///
     /// Command examples and accepted formats for `COPY` data are shown here:
     /// https://www.postgresql.org/docs/current/sql-copy.html
-    fn copy_out_raw(&self, statement: &str) -> Result<ChanStream<Bytes>> {
+    fn copy_out_raw(&self, statement: &str) -> Result<ChanStream<'static, Bytes>> {
         pg_begin_copy_out(self.acquire()?, statement)
     }
 }
@@ -301,7 +301,7 @@ impl<C: DerefMut<Target = PgConnection>> Drop for PgCopyIn<C> {
 fn pg_begin_copy_out<'c, C: DerefMut<Target = PgConnection> + Send + 'c>(
     mut conn: C,
     statement: &str,
-) -> Result<ChanStream<Bytes>> {
+) -> Result<ChanStream<'c, Bytes>> {
     conn.wait_until_ready()?;
     conn.stream.send(Query(statement))?;
 
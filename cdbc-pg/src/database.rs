@@ -51,3 +51,36 @@ impl HasStatement for Postgres {
 }
 
 impl HasStatementCache for Postgres {}
+
+#[cfg(test)]
+mod test {
+    use cdbc::QueryBuilder;
+    use crate::Postgres;
+
+    #[test]
+    fn test_query_builder() {
+        let mut builder = QueryBuilder::<Postgres>::new("select * from biz where id = ");
+        builder.push_bind(1).push(" and name in (");
+        let mut separated = builder.separated(", ");
+        separated.push_bind("a");
+        separated.push("'b'");
+        separated.push_bind("c");
+        separated.push_unseparated(")");
+        assert_eq!(builder.sql(), "select * from biz where id = $1 and name in ($2, 'b', $3)");
+        let query = builder.build();
+        let arguments = query.arguments.unwrap();
+        assert_eq!((query.bound, arguments.types.len()), (3, 3));
+
+        builder.reset();
+        assert_eq!(builder.sql(), "select * from biz where id = ");
+
+        let mut builder = QueryBuilder::<Postgres>::new("insert into biz(id, name) ");
+        builder.push_values(vec![(1, "a"), (2, "b")], |mut b, (id, name)| {
+            b.push_bind(id).push_bind(name);
+        });
+        assert_eq!(builder.sql(), "insert into biz(id, name) VALUES ($1, $2), ($3, $4)");
+        let query = builder.build();
+        let arguments = query.arguments.unwrap();
+        assert_eq!((query.bound, arguments.types.len()), (4, 4));
+    }
+}
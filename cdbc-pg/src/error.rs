@@ -4,7 +4,7 @@ use std::fmt::{self, Debug, Display, Formatter};
 use atoi::atoi;
 use smallvec::alloc::borrow::Cow;
 
-use cdbc::error::DatabaseError;
+use cdbc::error::{DatabaseError, ErrorKind};
 use crate::message::{Notice, PgSeverity};
 
 /// An error returned from the PostgreSQL database.
@@ -113,6 +113,28 @@ impl PgDatabaseError {
     pub fn routine(&self) -> Option<&str> {
         self.0.get(b'R')
     }
+
+    /// The class of this error, read from its SQLSTATE code.
+    pub fn kind(&self) -> ErrorKind {
+        let code = self.code();
+        match code {
+            "23505" => ErrorKind::UniqueViolation,
+            "23503" => ErrorKind::ForeignKeyViolation,
+            "23502" => ErrorKind::NotNullViolation,
+            "23514" => ErrorKind::CheckViolation,
+            "40001" => ErrorKind::SerializationFailure,
+            "40P01" => ErrorKind::Deadlock,
+            // lock_not_available, raised when `lock_timeout` elapses or for `NOWAIT`
+            "55P03" => ErrorKind::LockTimeout,
+            "57014" => ErrorKind::QueryCanceled,
+            // admin_shutdown, crash_shutdown, cannot_connect_now
+            "57P01" | "57P02" | "57P03" => ErrorKind::ConnectionLost,
+            "25006" => ErrorKind::ReadOnly,
+            // class 08: connection exception
+            _ if code.starts_with("08") => ErrorKind::ConnectionLost,
+            _ => ErrorKind::Other,
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -185,7 +207,45 @@ impl DatabaseError for PgDatabaseError {
         self
     }
 
+    fn kind(&self) -> ErrorKind {
+        self.kind()
+    }
+
     fn constraint(&self) -> Option<&str> {
         self.constraint()
     }
+
+    fn table(&self) -> Option<&str> {
+        self.table()
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.column()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+    use cdbc::error::{DatabaseError, ErrorKind};
+    use cdbc::io::Decode;
+    use crate::message::Notice;
+    use crate::PgDatabaseError;
+
+    #[test]
+    fn test_kind() {
+        const DATA: &[u8] = b"SERROR\0VERROR\0C23505\0Mduplicate key value violates unique constraint \"users_email_key\"\0Dkey (email)=(a@b.c) already exists.\0spublic\0tusers\0nusers_email_key\0Fnbtinsert.c\0L656\0R_bt_check_unique\0\0";
+
+        let err: Box<dyn DatabaseError> =
+            Box::new(PgDatabaseError(Notice::decode(Bytes::from_static(DATA)).unwrap()));
+
+        assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+        assert_eq!(err.constraint(), Some("users_email_key"));
+        assert_eq!(err.table(), Some("users"));
+        assert_eq!(err.column(), None);
+
+        const DEADLOCK: &[u8] = b"SERROR\0VERROR\0C40P01\0Mdeadlock detected\0\0";
+        let err = PgDatabaseError(Notice::decode(Bytes::from_static(DEADLOCK)).unwrap());
+        assert_eq!(err.kind(), ErrorKind::Deadlock);
+    }
 }
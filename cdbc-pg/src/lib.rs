@@ -11,10 +11,14 @@ mod error;
 mod io;
 mod listener;
 mod message;
+mod migrate;
 mod options;
 mod query_result;
+#[cfg(feature = "offline")]
+mod replay;
 mod row;
 mod statement;
+mod testing;
 mod transaction;
 mod type_info;
 pub mod types;
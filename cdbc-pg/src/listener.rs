@@ -249,7 +249,7 @@ impl PgListener {
     ///
     /// This has the same potential drawbacks as [`recv`](PgListener::recv).
     ///
-    pub fn into_stream(mut self) -> ChanStream<PgNotification> {
+    pub fn into_stream(mut self) -> ChanStream<'static, PgNotification> {
         chan_stream!( {
             loop {
                 r#yield!(self.recv()?);
@@ -279,10 +279,10 @@ impl Drop for PgListener {
 impl<'c> Executor for &'c mut PgListener {
     type Database = Postgres;
 
-    fn fetch_many<'q, E: 'q>(
-        &mut self,
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         query: E,
-    ) -> ChanStream<Either<PgQueryResult, PgRow>>
+    ) -> ChanStream<'e, Either<PgQueryResult, PgRow>>
     where
         E: Execute<'q, Self::Database>,
     {
@@ -1,20 +1,17 @@
-use cdbc::connection::{ConnectOptions, Connection};
+use cdbc::connection::Connection;
 use cdbc::error::Error;
 use cdbc::executor::Executor;
-use crate::migrate::MigrateError;
-use crate::migrate::{AppliedMigration, Migration};
-use crate::migrate::{Migrate, MigrateDatabase};
-use crate::{PgConnectOptions, PgConnection, Postgres};
-use crate::query::query;
+use cdbc::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::{PgConnectOptions, PgConnection, PgConnectionInfo, Postgres};
+use cdbc::query::query;
 use cdbc::query_as::query_as;
 use cdbc::query_scalar::query_scalar;
-use futures_core::future::BoxFuture;
 use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
 
-fn parse_for_maintenance(uri: &str) -> Result<(PgConnectOptions, String), Error> {
-    let mut options = PgConnectOptions::from_str(uri)?;
+fn parse_for_maintenance(url: &str) -> Result<(PgConnectOptions, String), Error> {
+    let mut options = PgConnectOptions::from_str(url)?;
 
     // pull out the name of the database to create
     let database = options
@@ -35,247 +32,204 @@ fn parse_for_maintenance(uri: &str) -> Result<(PgConnectOptions, String), Error>
     Ok((options, database))
 }
 
+pub(crate) fn quote_ident(database: &str) -> String {
+    format!("\"{}\"", database.replace('"', "\"\""))
+}
+
 impl MigrateDatabase for Postgres {
-    fn create_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(async move {
-            let (options, database) = parse_for_maintenance(uri)?;
-            let mut conn = options.connect()?;
-
-            let _ = conn
-                .execute(&*format!(
-                    "CREATE DATABASE \"{}\"",
-                    database.replace('"', "\"\"")
-                ))
-                ?;
-
-            Ok(())
-        })
+    fn create_database(url: &str) -> Result<(), Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = PgConnection::connect_with(&options)?;
+
+        let _ = conn.execute(&*format!("CREATE DATABASE {}", quote_ident(&database)))?;
+
+        Ok(())
     }
 
-    fn database_exists(uri: &str) -> BoxFuture<'_, Result<bool, Error>> {
-        Box::pin(async move {
-            let (options, database) = parse_for_maintenance(uri)?;
-            let mut conn = options.connect()?;
+    fn database_exists(url: &str) -> Result<bool, Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = PgConnection::connect_with(&options)?;
 
-            let exists: bool =
-                query_scalar("select exists(SELECT 1 from pg_database WHERE datname = $1)")
-                    .bind(database)
-                    .fetch_one(&mut conn)
-                    ?;
+        let exists: bool =
+            query_scalar("select exists(SELECT 1 from pg_database WHERE datname = $1)")
+                .bind(database)
+                .fetch_one(&mut conn)?;
 
-            Ok(exists)
-        })
+        Ok(exists)
     }
 
-    fn drop_database(uri: &str) -> BoxFuture<'_, Result<(), Error>> {
-        Box::pin(async move {
-            let (options, database) = parse_for_maintenance(uri)?;
-            let mut conn = options.connect()?;
+    fn drop_database(url: &str) -> Result<(), Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = PgConnection::connect_with(&options)?;
 
-            let _ = conn
-                .execute(&*format!(
-                    "DROP DATABASE IF EXISTS \"{}\"",
-                    database.replace('"', "\"\"")
-                ))
-                ?;
+        let _ = conn.execute(&*format!("DROP DATABASE IF EXISTS {}", quote_ident(&database)))?;
 
-            Ok(())
-        })
+        Ok(())
+    }
+
+    fn force_drop_database(url: &str) -> Result<(), Error> {
+        let (options, database) = parse_for_maintenance(url)?;
+        let mut conn = PgConnection::connect_with(&options)?;
+
+        if conn.server_version_num().unwrap_or(0) >= 130000 {
+            let _ = conn.execute(&*format!(
+                "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+                quote_ident(&database)
+            ))?;
+        } else {
+            // before postgres 13 the other sessions are terminated by hand, new sessions
+            // are refused first so they can't sneak in between
+            let _ = conn.execute(&*format!(
+                "ALTER DATABASE {} WITH ALLOW_CONNECTIONS false",
+                quote_ident(&database)
+            ));
+            let _ = query(
+                "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE datname = $1 AND pid <> pg_backend_pid()",
+            )
+            .bind(&database)
+            .execute(&mut conn)?;
+            let _ = conn.execute(&*format!("DROP DATABASE IF EXISTS {}", quote_ident(&database)))?;
+        }
+
+        Ok(())
     }
 }
 
 impl Migrate for PgConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
-        Box::pin(async move {
-            // language=SQL
-            self.execute(
-                r#"
-CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+    fn ensure_migrations_table(&mut self) -> Result<(), MigrateError> {
+        // language=SQL
+        self.execute(
+            r#"
+CREATE TABLE IF NOT EXISTS _cdbc_migrations (
     version BIGINT PRIMARY KEY,
     description TEXT NOT NULL,
     installed_on TIMESTAMPTZ NOT NULL DEFAULT now(),
     success BOOLEAN NOT NULL,
-    checksum BYTEA NOT NULL,
+    checksum TEXT NOT NULL,
     execution_time BIGINT NOT NULL
 );
                 "#,
-            )
-            ?;
+        )?;
 
-            Ok(())
-        })
+        Ok(())
     }
 
-    fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>> {
-        Box::pin(async move {
-            // language=SQL
-            let row = query_as(
-                "SELECT version, NOT success FROM _sqlx_migrations ORDER BY version DESC LIMIT 1",
-            )
-            .fetch_optional(self)
-            ?;
-
-            Ok(row)
-        })
-    }
-
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
-        Box::pin(async move {
-            // language=SQL
-            let row: Option<(i64,)> = query_as(
-                "SELECT version FROM _sqlx_migrations WHERE success = false ORDER BY version LIMIT 1",
-            )
-            .fetch_optional(self)
-            ?;
+    fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError> {
+        // language=SQL
+        let row: Option<(i64,)> = query_as(
+            "SELECT version FROM _cdbc_migrations WHERE success = false ORDER BY version LIMIT 1",
+        )
+        .fetch_optional(self)?;
 
-            Ok(row.map(|r| r.0))
-        })
+        Ok(row.map(|r| r.0))
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
-        Box::pin(async move {
-            // language=SQL
-            let rows: Vec<(i64, Vec<u8>)> =
-                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-                    .fetch_all(self)
-                    ?;
-
-            let migrations = rows
-                .into_iter()
-                .map(|(version, checksum)| AppliedMigration {
-                    version,
-                    checksum: checksum.into(),
-                })
-                .collect();
-
-            Ok(migrations)
-        })
+    fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError> {
+        // language=SQL
+        let rows: Vec<(i64, String)> =
+            query_as("SELECT version, checksum FROM _cdbc_migrations ORDER BY version")
+                .fetch_all(self)?;
+
+        let migrations = rows
+            .into_iter()
+            .map(|(version, checksum)| AppliedMigration {
+                version,
+                checksum: checksum.into(),
+            })
+            .collect();
+
+        Ok(migrations)
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
-        Box::pin(async move {
-            let database_name = current_database(self)?;
-            let lock_id = generate_lock_id(&database_name);
+    fn lock(&mut self) -> Result<(), MigrateError> {
+        let database_name = current_database(self)?;
+        let lock_id = generate_lock_id(&database_name);
 
-            // create an application lock over the database
-            // this function will not return until the lock is acquired
+        // create an application lock over the database
+        // this function will not return until the lock is acquired
 
-            // https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS
-            // https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS-TABLE
+        // https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS
+        // https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS-TABLE
 
-            // language=SQL
-            let _ = query("SELECT pg_advisory_lock($1)")
-                .bind(lock_id)
-                .execute(self)
-                ?;
+        // language=SQL
+        let _ = query("SELECT pg_advisory_lock($1)")
+            .bind(lock_id)
+            .execute(self)?;
 
-            Ok(())
-        })
+        Ok(())
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
-        Box::pin(async move {
-            let database_name = current_database(self)?;
-            let lock_id = generate_lock_id(&database_name);
+    fn unlock(&mut self) -> Result<(), MigrateError> {
+        let database_name = current_database(self)?;
+        let lock_id = generate_lock_id(&database_name);
 
-            // language=SQL
-            let _ = query("SELECT pg_advisory_unlock($1)")
-                .bind(lock_id)
-                .execute(self)
-                ?;
+        // language=SQL
+        let _ = query("SELECT pg_advisory_unlock($1)")
+            .bind(lock_id)
+            .execute(self)?;
 
-            Ok(())
-        })
+        Ok(())
     }
 
-    fn validate<'e: 'm, 'm>(
-        &'e mut self,
-        migration: &'m Migration,
-    ) -> BoxFuture<'m, Result<(), MigrateError>> {
-        Box::pin(async move {
-            // language=SQL
-            let checksum: Option<Vec<u8>> =
-                query_scalar("SELECT checksum FROM _sqlx_migrations WHERE version = $1")
-                    .bind(migration.version)
-                    .fetch_optional(self)
-                    ?;
-
-            if let Some(checksum) = checksum {
-                return if checksum == &*migration.checksum {
-                    Ok(())
-                } else {
-                    Err(MigrateError::VersionMismatch(migration.version))
-                };
-            } else {
-                Err(MigrateError::VersionMissing(migration.version))
-            }
-        })
-    }
+    fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        // postgres has transactional DDL, the migration and its record are committed together
+        // so a failed migration never leaves a dirty row behind
+        let mut tx = self.begin()?;
+        let start = Instant::now();
 
-    fn apply<'e: 'm, 'm>(
-        &'e mut self,
-        migration: &'m Migration,
-    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
-        Box::pin(async move {
-            let mut tx = self.begin()?;
-            let start = Instant::now();
+        let _ = tx.execute(&*migration.sql)?;
 
-            let _ = tx.execute(&*migration.sql)?;
+        // language=SQL
+        let _ = query(
+            r#"
+    INSERT INTO _cdbc_migrations ( version, description, success, checksum, execution_time )
+    VALUES ( $1, $2, TRUE, $3, -1 )
+                "#,
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(&mut *tx)?;
 
-            tx.commit()?;
+        tx.commit()?;
+        drop(tx);
 
-            let elapsed = start.elapsed();
+        let elapsed = start.elapsed();
 
-            // language=SQL
-            let _ = query(
-                r#"
-    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
-    VALUES ( $1, $2, TRUE, $3, $4 )
-                "#,
-            )
-            .bind(migration.version)
-            .bind(&*migration.description)
-            .bind(&*migration.checksum)
+        // language=SQL
+        let _ = query("UPDATE _cdbc_migrations SET execution_time = $1 WHERE version = $2")
             .bind(elapsed.as_nanos() as i64)
-            .execute(self)
-            ?;
+            .bind(migration.version)
+            .execute(self)?;
 
-            Ok(elapsed)
-        })
+        Ok(elapsed)
     }
 
-    fn revert<'e: 'm, 'm>(
-        &'e mut self,
-        migration: &'m Migration,
-    ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
-        Box::pin(async move {
-            let mut tx = self.begin()?;
-            let start = Instant::now();
+    fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        let down_sql = migration
+            .down_sql
+            .as_deref()
+            .ok_or(MigrateError::Irreversible(migration.version))?;
 
-            let _ = tx.execute(&*migration.sql)?;
+        let mut tx = self.begin()?;
+        let start = Instant::now();
 
-            tx.commit()?;
+        let _ = tx.execute(down_sql)?;
 
-            let elapsed = start.elapsed();
+        // language=SQL
+        let _ = query("DELETE FROM _cdbc_migrations WHERE version = $1")
+            .bind(migration.version)
+            .execute(&mut *tx)?;
 
-            // language=SQL
-            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = $1"#)
-                .bind(migration.version)
-                .execute(self)
-                ?;
+        tx.commit()?;
 
-            Ok(elapsed)
-        })
+        Ok(start.elapsed())
     }
 }
 
 fn current_database(conn: &mut PgConnection) -> Result<String, MigrateError> {
     // language=SQL
-    Ok(query_scalar("SELECT current_database()")
-        .fetch_one(conn)
-        ?)
+    Ok(query_scalar("SELECT current_database()").fetch_one(conn)?)
 }
 
 // inspired from rails: https://github.com/rails/rails/blob/6e49cc77ab3d16c06e12f93158eaf3e507d4120e/activerecord/lib/active_record/migration.rb#L1308
@@ -284,3 +238,22 @@ fn generate_lock_id(database_name: &str) -> i64 {
     // 0x3d32ad9e chosen by fair dice roll
     0x3d32ad9e * (CRC_IEEE.checksum(database_name.as_bytes()) as i64)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::migrate::parse_for_maintenance;
+
+    #[test]
+    fn test_parse_for_maintenance() {
+        let (options, database) = parse_for_maintenance("postgres://postgres@localhost/app").unwrap();
+        assert_eq!(database, "app");
+        assert_eq!(options.database.as_deref(), Some("postgres"));
+
+        let (options, database) = parse_for_maintenance("postgres://postgres@localhost/postgres").unwrap();
+        assert_eq!(database, "postgres");
+        assert_eq!(options.database.as_deref(), Some("template1"));
+
+        let (_, database) = parse_for_maintenance("postgres://alice@localhost").unwrap();
+        assert_eq!(database, "alice");
+    }
+}
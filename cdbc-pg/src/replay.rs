@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use byteorder::{BigEndian, ByteOrder};
+use bytes::Bytes;
+use cdbc::error::Error;
+use cdbc::replay::{RecordedResult, RecordedValue, Replay};
+use cdbc::row::Row;
+use cdbc::type_info::TypeInfo;
+use serde::Deserialize;
+use crate::message::DataRow;
+use crate::statement::PgStatementMetadata;
+use crate::{PgArguments, PgColumn, PgQueryResult, PgRow, PgValueFormat, Postgres};
+
+/// The columns of a recorded result, with the format of its values.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RecordedColumns {
+    format: RecordedFormat,
+    columns: Vec<PgColumn>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedFormat {
+    Text,
+    Binary,
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(value).map_err(|e| Error::Decode(e.into()))
+}
+
+/// Values are recorded as the hex of their bytes, in the format Postgres sent them.
+impl Replay for Postgres {
+    /// A row without values, sharing the metadata of the replayed rows.
+    type Columns = PgRow;
+
+    fn record_arguments(arguments: &PgArguments) -> Vec<RecordedValue> {
+        let buffer = &arguments.buffer[..];
+        let mut offset = 0;
+        arguments
+            .types
+            .iter()
+            .map(|ty| {
+                // every value is prefixed with its length, -1 for NULL
+                let len = BigEndian::read_i32(&buffer[offset..]);
+                offset += 4;
+                let value = if len < 0 {
+                    None
+                } else {
+                    let value = hex::encode(&buffer[offset..offset + len as usize]);
+                    offset += len as usize;
+                    Some(value)
+                };
+                RecordedValue {
+                    type_name: ty.name().to_owned(),
+                    value,
+                }
+            })
+            .collect()
+    }
+
+    fn record_columns(row: &PgRow) -> serde_json::Value {
+        let columns = RecordedColumns {
+            format: match row.format {
+                PgValueFormat::Text => RecordedFormat::Text,
+                PgValueFormat::Binary => RecordedFormat::Binary,
+            },
+            columns: row.metadata.columns.clone(),
+        };
+        serde_json::to_value(columns).unwrap_or_default()
+    }
+
+    fn record_row(row: &PgRow) -> Vec<Option<String>> {
+        (0..row.len()).map(|i| row.data.get(i).map(hex::encode)).collect()
+    }
+
+    fn record_result(result: &PgQueryResult) -> RecordedResult {
+        RecordedResult {
+            rows_affected: result.rows_affected,
+            last_insert_id: None,
+        }
+    }
+
+    fn replay_columns(columns: &serde_json::Value) -> Result<Self::Columns, Error> {
+        let recorded = RecordedColumns::deserialize(columns).map_err(|e| Error::Decode(e.into()))?;
+        let column_names = recorded
+            .columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ordinal))
+            .collect();
+        let format = match recorded.format {
+            RecordedFormat::Text => PgValueFormat::Text,
+            RecordedFormat::Binary => PgValueFormat::Binary,
+        };
+        Ok(PgRow {
+            data: DataRow {
+                storage: Bytes::new(),
+                values: Vec::new(),
+            },
+            format,
+            metadata: Arc::new(PgStatementMetadata {
+                columns: recorded.columns,
+                column_names,
+                parameters: Vec::new(),
+            }),
+        })
+    }
+
+    fn replay_row(columns: &PgRow, values: &[Option<String>]) -> Result<PgRow, Error> {
+        let metadata = &columns.metadata;
+        if values.len() != metadata.columns.len() {
+            return Err(err_protocol!(
+                "recorded row has {} values for {} columns",
+                values.len(),
+                metadata.columns.len()
+            ));
+        }
+        let mut storage = Vec::new();
+        let mut ranges = Vec::with_capacity(values.len());
+        for value in values {
+            ranges.push(match value {
+                Some(value) => {
+                    let start = storage.len() as u32;
+                    storage.extend(decode_hex(value)?);
+                    Some(start..storage.len() as u32)
+                }
+                None => None,
+            });
+        }
+        Ok(PgRow {
+            data: DataRow {
+                storage: Bytes::from(storage),
+                values: ranges,
+            },
+            format: columns.format,
+            metadata: metadata.clone(),
+        })
+    }
+
+    fn replay_result(result: &RecordedResult) -> PgQueryResult {
+        PgQueryResult {
+            rows_affected: result.rows_affected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::replay::{RecordedValue, Replay};
+    use cdbc::row::Row;
+    use crate::{PgArguments, Postgres};
+
+    #[test]
+    fn test_record_arguments() {
+        let mut arguments = PgArguments::default();
+        arguments.add(7_i32);
+        arguments.add(None::<String>);
+        arguments.add("tom");
+        assert_eq!(
+            Postgres::record_arguments(&arguments),
+            vec![
+                RecordedValue { type_name: "INT4".into(), value: Some("00000007".into()) },
+                RecordedValue { type_name: "TEXT".into(), value: None },
+                RecordedValue { type_name: "TEXT".into(), value: Some("746f6d".into()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replay_row() {
+        let columns = serde_json::json!({
+            "format": "binary",
+            "columns": [
+                {"ordinal": 0, "name": "id", "type_info": "Int4"},
+                {"ordinal": 1, "name": "name", "type_info": "Text"},
+            ],
+        });
+        let values = vec![Some("00000007".to_string()), None];
+        let replayed = Postgres::replay_columns(&columns).unwrap();
+        let row = Postgres::replay_row(&replayed, &values).unwrap();
+        assert_eq!(row.get::<i32, _>("id"), 7);
+        assert_eq!(row.get::<Option<String>, _>("name"), None);
+        assert_eq!(Postgres::record_columns(&row), columns);
+        assert_eq!(Postgres::record_row(&row), values);
+    }
+}
@@ -0,0 +1,41 @@
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::executor::Executor;
+use cdbc::migrate::MigrateDatabase;
+use cdbc::testing::{database_url, test_db_name, TestArgs, TestContext, TestSupport};
+use crate::migrate::quote_ident;
+use crate::{PgConnectOptions, PgConnection, Postgres};
+use std::str::FromStr;
+use url::Url;
+
+impl TestSupport for Postgres {
+    fn test_context(args: &TestArgs) -> Result<TestContext<Self>, Error> {
+        let master_options = PgConnectOptions::from_str(&database_url()?)?;
+        let mut conn = PgConnection::connect_with(&master_options)?;
+
+        let db_name = test_db_name();
+        // the template can't have other sessions while it is copied
+        let _ = conn.execute(&*format!(
+            "CREATE DATABASE {} TEMPLATE {}",
+            quote_ident(&db_name),
+            quote_ident(args.template.unwrap_or("template1"))
+        ))?;
+        conn.close()?;
+
+        let mut connect_options = master_options.clone();
+        connect_options.database = Some(db_name.clone());
+        Ok(TestContext {
+            db_name,
+            connect_options,
+            master_options,
+            keep_alive: None,
+        })
+    }
+
+    fn cleanup_test(db_name: &str, _master_options: &PgConnectOptions) -> Result<(), Error> {
+        // a session the test leaked must not keep the database alive
+        let mut url = Url::parse(&database_url()?).map_err(Error::config)?;
+        url.set_path(db_name);
+        Postgres::force_drop_database(url.as_str())
+    }
+}
@@ -1,11 +1,13 @@
+use std::borrow::Cow;
 
 use cdbc::error::Error;
 use cdbc::executor::Executor;
 use crate::message::Query;
 use crate::{PgConnection, Postgres};
 use cdbc::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    begin_ansi_transaction_sql, check_savepoint_options, commit_ansi_transaction_sql,
+    release_savepoint_ansi_sql, rollback_ansi_transaction_sql, rollback_to_savepoint_ansi_sql,
+    savepoint_ansi_sql, IsolationLevel, TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for PostgreSQL.
@@ -14,9 +16,9 @@ pub struct PgTransactionManager;
 impl TransactionManager for PgTransactionManager {
     type Database = Postgres;
 
-    fn begin(conn: &mut PgConnection) ->  Result<(), Error> {
+    fn begin_with(conn: &mut PgConnection, options: &TransactionOptions) ->  Result<(), Error> {
 
-            conn.execute(&*begin_ansi_transaction_sql(conn.transaction_depth))
+            conn.execute(&*begin_transaction_sql(conn.transaction_depth, options)?)
                 ?;
 
             conn.transaction_depth += 1;
@@ -53,4 +55,72 @@ impl TransactionManager for PgTransactionManager {
             conn.transaction_depth -= 1;
         }
     }
+
+    fn savepoint(conn: &mut PgConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*savepoint_ansi_sql(name))?;
+        Ok(())
+    }
+
+    fn release_savepoint(conn: &mut PgConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*release_savepoint_ansi_sql(name))?;
+        Ok(())
+    }
+
+    fn rollback_to_savepoint(conn: &mut PgConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*rollback_to_savepoint_ansi_sql(name))?;
+        Ok(())
+    }
+}
+
+/// `BEGIN ISOLATION LEVEL .. READ ONLY DEFERRABLE`, or a savepoint.
+fn begin_transaction_sql(
+    depth: usize,
+    options: &TransactionOptions,
+) -> Result<Cow<'static, str>, Error> {
+    check_savepoint_options(depth, options)?;
+    if depth > 0 || options.is_default() {
+        return Ok(begin_ansi_transaction_sql(depth));
+    }
+
+    let mut sql = String::from("BEGIN");
+    if let Some(level) = options.isolation_level {
+        sql.push_str(" ISOLATION LEVEL ");
+        sql.push_str(match level {
+            // `REPEATABLE READ` is snapshot isolation in Postgres
+            IsolationLevel::Snapshot => IsolationLevel::RepeatableRead.as_sql(),
+            level => level.as_sql(),
+        });
+    }
+    if let Some(mode) = options.access_mode {
+        sql.push(' ');
+        sql.push_str(mode.as_sql());
+    }
+    if options.deferrable {
+        sql.push_str(" DEFERRABLE");
+    }
+    Ok(Cow::Owned(sql))
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::transaction::{IsolationLevel, TransactionOptions};
+    use super::begin_transaction_sql;
+
+    #[test]
+    fn test_begin_transaction_sql() {
+        let options = TransactionOptions::new()
+            .isolation_level(IsolationLevel::Serializable)
+            .read_only()
+            .deferrable(true);
+        assert_eq!(
+            begin_transaction_sql(0, &options).unwrap(),
+            "BEGIN ISOLATION LEVEL SERIALIZABLE READ ONLY DEFERRABLE"
+        );
+        assert_eq!(begin_transaction_sql(0, &TransactionOptions::new()).unwrap(), "BEGIN");
+        assert_eq!(
+            begin_transaction_sql(1, &TransactionOptions::new()).unwrap(),
+            "SAVEPOINT _sqlx_savepoint_1"
+        );
+        assert!(begin_transaction_sql(1, &options).is_err());
+    }
 }
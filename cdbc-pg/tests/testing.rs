@@ -0,0 +1,24 @@
+//! needs a postgres server, run with `DATABASE_URL=postgres://.. cargo test -p cdbc-pg -- --ignored`
+use cdbc::pool::Pool;
+use cdbc_pg::Postgres;
+
+#[cdbc::test(template = "template0")]
+#[ignore = "needs DATABASE_URL"]
+fn test_template(pool: Pool<Postgres>) -> Result<(), cdbc::Error> {
+    let name: String = cdbc::query_scalar("select current_database()").fetch_one(&pool)?;
+    assert!(name.starts_with("_cdbc_test_"), "{}", name);
+    Ok(())
+}
+
+#[cdbc::test(template = "_cdbc_missing_template")]
+#[ignore = "needs DATABASE_URL"]
+#[should_panic(expected = "create test database")]
+fn test_template_missing(_pool: Pool<Postgres>) {}
+
+// the database is dropped although the test leaks a session to it
+#[cdbc::test]
+#[ignore = "needs DATABASE_URL"]
+fn test_leaked_connection(pool: Pool<Postgres>) -> Result<(), cdbc::Error> {
+    std::mem::forget(pool.acquire()?.detach());
+    Ok(())
+}
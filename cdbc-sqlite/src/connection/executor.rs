@@ -11,11 +11,13 @@ use either::Either;
 use libsqlite3_sys::sqlite3_last_insert_rowid;
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Duration;
 use std::sync::mpsc::RecvError;
 use mco::std::io::TryStream;
 use mco::std::sync::channel;
 use mco::std::sync::channel::Receiver;
 use cdbc::database::{Database, HasStatement};
+use cdbc::deadline;
 use cdbc::io::chan_stream::ChanStream;
 use cdbc::utils::statement_cache::StatementCache;
 use crate::connection::executor_mut;
@@ -24,25 +26,28 @@ use crate::connection::executor_mut;
 impl Executor for &mut SqliteConnection {
     type Database = Sqlite;
 
-    fn fetch_many<'q, E: 'q>(&mut self,
-                             mut query: E,
-    ) -> ChanStream<Either<SqliteQueryResult, SqliteRow>>
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(&'e mut self,
+                                     mut query: E,
+    ) -> ChanStream<'e, Either<SqliteQueryResult, SqliteRow>>
         where
             E: Execute<'q, Self::Database>,
     {
         let sql = query.sql().to_owned();
         let arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
-        let s = self.worker
-            .execute(&sql, arguments, self.row_channel_size, persistent);
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
+        let s = executor_mut::execute(self, &sql, arguments, persistent, deadline);
         if s.is_err() {
             let c = ChanStream::new(|sender|
                 Err(s.err().unwrap())
             );
             return c;
         }
-        let s = s.unwrap();
-        executor_mut::sender_to_stream(s)
+        s.unwrap()
     }
 
     fn fetch_optional<'q, E: 'q>(
@@ -54,11 +59,8 @@ impl Executor for &mut SqliteConnection {
     {
         let arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
-        let mut stream = self
-            .worker
-            .execute(query.sql(), arguments, self.row_channel_size, persistent)?;
-        let mut stream = executor_mut::sender_to_stream(stream);
-        use crate::cdbc::io::chan_stream::TryStream;
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
+        let mut stream = executor_mut::execute(self, query.sql(), arguments, persistent, deadline)?;
         while let Some(res) = stream.try_next()? {
             if let Either::Right(row) = res {
                 return Ok(Some(row));
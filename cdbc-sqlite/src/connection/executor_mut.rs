@@ -1,20 +1,50 @@
+use std::time::{Duration, Instant};
 use mco::std::sync::channel::Receiver;
 use either::Either;
+use libsqlite3_sys::sqlite3_interrupt;
 use cdbc::database::{Database, HasStatement};
+use cdbc::deadline::{self, Watchdog};
 use cdbc::{Error, Execute, Executor};
 use cdbc::describe::Describe;
 use cdbc::io::chan_stream::ChanStream;
-use crate::{Sqlite, SqliteConnection, SqliteQueryResult, SqliteRow, SqliteStatement, SqliteTypeInfo};
+use crate::{Sqlite, SqliteArguments, SqliteConnection, SqliteQueryResult, SqliteRow, SqliteStatement, SqliteTypeInfo};
 
-pub(crate) fn sender_to_stream(arg: Receiver<Result<Either<SqliteQueryResult, SqliteRow>, Error>>) -> ChanStream<Either<SqliteQueryResult, SqliteRow>> {
-    ChanStream::new(|s| {
+// run the query on the worker, it is interrupted if it still runs at the deadline
+pub(crate) fn execute(
+    conn: &mut SqliteConnection,
+    query: &str,
+    arguments: Option<SqliteArguments<'_>>,
+    persistent: bool,
+    deadline: Option<Instant>,
+) -> Result<ChanStream<'static, Either<SqliteQueryResult, SqliteRow>>, Error> {
+    deadline::check(deadline)?;
+    let rx = conn
+        .worker
+        .execute(query, arguments, conn.row_channel_size, persistent)?;
+    let handle = conn.worker.handle_raw.clone();
+    let watchdog = Watchdog::start(deadline, move || {
+        // SAFETY: `sqlite3_interrupt` may be called from any thread,
+        // the connection outlives the stream holding the watchdog
+        unsafe { sqlite3_interrupt(handle.as_ptr()) }
+    });
+    Ok(sender_to_stream(rx, watchdog))
+}
+
+pub(crate) fn sender_to_stream(arg: Receiver<Result<Either<SqliteQueryResult, SqliteRow>, Error>>, watchdog: Watchdog) -> ChanStream<'static, Either<SqliteQueryResult, SqliteRow>> {
+    ChanStream::with_cleanup(move |s| {
         loop {
+            if s.is_closed() {
+                // the stream was dropped, the worker may be blocked on the full channel:
+                // drain the rest of the results, so it gets back to the next command
+                while arg.recv().is_ok() {}
+                return Ok(());
+            }
             match arg.recv() {
                 Ok(v) => {
                     if v.is_err() {
-                        return Err(v.err().unwrap());
+                        return watchdog.check(v).map(|_| ());
                     } else {
-                        s.send(Some(v));
+                        let _ = s.send(Some(v));
                     }
                 }
                 Err(_) => {
@@ -31,24 +61,27 @@ pub(crate) fn sender_to_stream(arg: Receiver<Result<Either<SqliteQueryResult, Sq
 impl Executor for SqliteConnection {
     type Database = Sqlite;
 
-    fn fetch_many<'q, E: 'q>(&mut self,
-                             mut query: E,
-    ) -> ChanStream<Either<SqliteQueryResult, SqliteRow>>
+    fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(&'e mut self,
+                                     mut query: E,
+    ) -> ChanStream<'e, Either<SqliteQueryResult, SqliteRow>>
         where
             E: Execute<'q, Self::Database>,
     {
         let arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
-        let s = self.worker
-            .execute(query.sql(), arguments, self.row_channel_size, persistent);
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
+        let s = execute(self, query.sql(), arguments, persistent, deadline);
         if s.is_err() {
             let c = ChanStream::new(|sender|
                 Err(s.err().unwrap())
             );
             return c;
         }
-        let s = s.unwrap();
-        sender_to_stream(s)
+        s.unwrap()
     }
 
     fn fetch_optional<'q, E: 'q>(
@@ -60,11 +93,8 @@ impl Executor for SqliteConnection {
     {
         let arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
-        let mut stream = self
-            .worker
-            .execute(query.sql(), arguments, self.row_channel_size, persistent)?;
-        let mut stream = sender_to_stream(stream);
-        use crate::cdbc::io::chan_stream::TryStream;
+        let deadline = deadline::query_deadline(query.timeout().or(self.default_timeout));
+        let mut stream = execute(self, query.sql(), arguments, persistent, deadline)?;
         while let Some(res) = stream.try_next()? {
             if let Either::Right(row) = res {
                 return Ok(Some(row));
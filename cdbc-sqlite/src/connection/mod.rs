@@ -3,11 +3,12 @@ use cdbc::connection::{Connection};
 use cdbc::error::Error;
 use crate::statement::{VirtualStatement};
 use crate::{Sqlite, SqliteConnectOptions};
-use cdbc::transaction::Transaction;
+use cdbc::transaction::{Transaction, TransactionCallbacks, TransactionOptions};
 use libsqlite3_sys::sqlite3;
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Formatter};
 use std::ptr::NonNull;
+use std::time::Duration;
 use mco::std::sync::MutexGuard;
 use cdbc::database::{Database, HasStatement};
 use cdbc::{Execute, Executor};
@@ -41,6 +42,10 @@ use crate::connection::worker::ConnectionWorker;
 pub struct SqliteConnection {
     pub worker: ConnectionWorker,
     pub row_channel_size: usize,
+    pub default_timeout: Option<Duration>,
+
+    // callbacks of the open transactions
+    pub transaction_callbacks: TransactionCallbacks,
 }
 
 pub struct LockedSqliteHandle<'a> {
@@ -70,6 +75,8 @@ impl SqliteConnection {
         Ok(Self {
             worker,
             row_channel_size: options.row_channel_size,
+            default_timeout: None,
+            transaction_callbacks: TransactionCallbacks::default(),
         })
     }
 
@@ -150,6 +157,10 @@ impl Connection for SqliteConnection {
         shutdown
     }
 
+    fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.default_timeout = timeout;
+    }
+
     /// Ensure the background worker thread is alive and accepting commands.
     fn ping(&mut self) -> Result<(), Error> {
        self.worker.ping()
@@ -162,6 +173,13 @@ impl Connection for SqliteConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(&mut self, options: TransactionOptions) -> Result<Transaction<'_, Self::Database>, Error>
+        where
+            Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.worker
             .shared
@@ -187,6 +205,10 @@ impl Connection for SqliteConnection {
     fn should_flush(&self) -> bool {
         false
     }
+
+    fn transaction_callbacks(&mut self) -> Option<&mut TransactionCallbacks> {
+        Some(&mut self.transaction_callbacks)
+    }
 }
 
 impl LockedSqliteHandle<'_> {
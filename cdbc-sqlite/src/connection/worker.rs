@@ -18,8 +18,9 @@ use crate::connection::{execute};
 use crate::{Sqlite, SqliteArguments, SqliteQueryResult, SqliteRow, SqliteStatement};
 use crate::connection::handle::ConnectionHandleRaw;
 use cdbc::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
+    commit_ansi_transaction_sql, rollback_ansi_transaction_sql, TransactionOptions,
 };
+use crate::transaction::begin_transaction_sql;
 
 // Each SQLite connection has a dedicated thread.
 
@@ -56,6 +57,7 @@ enum Command {
         tx: Sender<Result<Either<SqliteQueryResult, SqliteRow>, Error>>,
     },
     Begin {
+        options: TransactionOptions,
         tx: Sender<Result<(), Error>>,
     },
     Commit {
@@ -149,21 +151,25 @@ impl ConnectionWorker {
                             };
 
                             for res in iter {
-                                if tx.send(res).is_err() {
+                                // stepping a failed statement again would restart it,
+                                // e.g. an interrupted query would run once more
+                                let failed = res.is_err();
+                                if tx.send(res).is_err() || failed {
                                     break;
                                 }
                             }
 
                             update_cached_statements_size(&conn, &shared.cached_statements_size);
                         }
-                        Command::Begin { tx } => {
+                        Command::Begin { options, tx } => {
                             let depth = conn.transaction_depth;
-                            let res =
+                            let res = begin_transaction_sql(depth, &options).and_then(|sql| {
                                 conn.handle
-                                    .exec(begin_ansi_transaction_sql(depth))
+                                    .exec(sql)
                                     .map(|_| {
                                         conn.transaction_depth += 1;
-                                    });
+                                    })
+                            });
 
                             tx.send(res).ok();
                         }
@@ -271,8 +277,8 @@ impl ConnectionWorker {
         Ok(rx)
     }
 
-    pub fn begin(&mut self) -> Result<(), Error> {
-        self.oneshot_cmd(|tx| Command::Begin { tx })?
+    pub fn begin(&mut self, options: TransactionOptions) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Begin { options, tx })?
     }
 
     pub fn commit(&mut self) -> Result<(), Error> {
@@ -4,9 +4,14 @@ use std::fmt::{self, Display, Formatter};
 use std::os::raw::c_int;
 use std::{borrow::Cow, str::from_utf8_unchecked};
 
-use libsqlite3_sys::{sqlite3, sqlite3_errmsg, sqlite3_extended_errcode};
+use libsqlite3_sys::{
+    sqlite3, sqlite3_errmsg, sqlite3_extended_errcode, SQLITE_BUSY, SQLITE_BUSY_SNAPSHOT,
+    SQLITE_CONSTRAINT_CHECK, SQLITE_CONSTRAINT_FOREIGNKEY, SQLITE_CONSTRAINT_NOTNULL,
+    SQLITE_CONSTRAINT_PRIMARYKEY, SQLITE_CONSTRAINT_UNIQUE, SQLITE_INTERRUPT, SQLITE_LOCKED,
+    SQLITE_READONLY,
+};
 
-use cdbc::error::DatabaseError;
+use cdbc::error::{DatabaseError, ErrorKind};
 
 // Error Codes And Messages
 // https://www.sqlite.org/c3ref/errcode.html
@@ -35,6 +40,27 @@ impl SqliteError {
             message: message.to_owned(),
         }
     }
+
+    /// What follows `<kind> constraint failed: ` in the message,
+    /// e.g. `users.email` or the name of a check constraint.
+    fn constraint_detail(&self, kind: &str) -> Option<&str> {
+        let detail = self.message.strip_prefix(kind)?;
+        detail.strip_prefix(" constraint failed: ")
+    }
+
+    /// The first `table.column` of a unique or not null constraint error.
+    fn table_column(&self) -> Option<(&str, &str)> {
+        let detail = match self.code {
+            SQLITE_CONSTRAINT_UNIQUE | SQLITE_CONSTRAINT_PRIMARYKEY => {
+                self.constraint_detail("UNIQUE")?
+            }
+            SQLITE_CONSTRAINT_NOTNULL => self.constraint_detail("NOT NULL")?,
+            _ => return None,
+        };
+        let first = detail.split(", ").next()?;
+        let dot = first.find('.')?;
+        Some((&first[..dot], &first[dot + 1..]))
+    }
 }
 
 impl Display for SqliteError {
@@ -71,4 +97,80 @@ impl DatabaseError for SqliteError {
     fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
         self
     }
+
+    fn kind(&self) -> ErrorKind {
+        match self.code {
+            SQLITE_CONSTRAINT_UNIQUE | SQLITE_CONSTRAINT_PRIMARYKEY => ErrorKind::UniqueViolation,
+            SQLITE_CONSTRAINT_FOREIGNKEY => ErrorKind::ForeignKeyViolation,
+            SQLITE_CONSTRAINT_NOTNULL => ErrorKind::NotNullViolation,
+            SQLITE_CONSTRAINT_CHECK => ErrorKind::CheckViolation,
+            // a WAL read transaction that can't be upgraded to a write transaction
+            SQLITE_BUSY_SNAPSHOT => ErrorKind::SerializationFailure,
+            // the primary result code is the lower 8 bits of the extended one
+            code => match code & 0xff {
+                SQLITE_BUSY | SQLITE_LOCKED => ErrorKind::LockTimeout,
+                SQLITE_INTERRUPT => ErrorKind::QueryCanceled,
+                SQLITE_READONLY => ErrorKind::ReadOnly,
+                _ => ErrorKind::Other,
+            },
+        }
+    }
+
+    fn constraint(&self) -> Option<&str> {
+        match self.code {
+            // CHECK constraint failed: age_positive
+            SQLITE_CONSTRAINT_CHECK => self.constraint_detail("CHECK"),
+            // UNIQUE constraint failed: index 'idx_lower_email'
+            SQLITE_CONSTRAINT_UNIQUE => {
+                let index = self.constraint_detail("UNIQUE")?.strip_prefix("index '")?;
+                index.strip_suffix('\'')
+            }
+            _ => None,
+        }
+    }
+
+    fn table(&self) -> Option<&str> {
+        self.table_column().map(|(table, _)| table)
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.table_column().map(|(_, column)| column)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::error::{DatabaseError, ErrorKind};
+    use libsqlite3_sys::{SQLITE_BUSY, SQLITE_CONSTRAINT_CHECK, SQLITE_CONSTRAINT_NOTNULL, SQLITE_CONSTRAINT_UNIQUE};
+    use crate::SqliteError;
+
+    fn error(code: i32, message: &str) -> SqliteError {
+        SqliteError {
+            code,
+            message: message.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_kind() {
+        let err = error(SQLITE_CONSTRAINT_UNIQUE, "UNIQUE constraint failed: users.email, users.tenant");
+        assert_eq!(err.kind(), ErrorKind::UniqueViolation);
+        assert_eq!(err.table(), Some("users"));
+        assert_eq!(err.column(), Some("email"));
+        assert_eq!(err.constraint(), None);
+
+        let err = error(SQLITE_CONSTRAINT_UNIQUE, "UNIQUE constraint failed: index 'idx_lower_email'");
+        assert_eq!(err.constraint(), Some("idx_lower_email"));
+        assert_eq!(err.table(), None);
+
+        let err = error(SQLITE_CONSTRAINT_NOTNULL, "NOT NULL constraint failed: users.name");
+        assert_eq!(err.kind(), ErrorKind::NotNullViolation);
+        assert_eq!(err.column(), Some("name"));
+
+        let err = error(SQLITE_CONSTRAINT_CHECK, "CHECK constraint failed: age_positive");
+        assert_eq!(err.kind(), ErrorKind::CheckViolation);
+        assert_eq!(err.constraint(), Some("age_positive"));
+
+        assert_eq!(error(SQLITE_BUSY, "database is locked").kind(), ErrorKind::LockTimeout);
+    }
 }
@@ -17,10 +17,14 @@ mod column;
 mod connection;
 mod database;
 mod error;
+mod migrate;
 mod options;
 mod query_result;
+#[cfg(feature = "offline")]
+mod replay;
 mod row;
 mod statement;
+mod testing;
 mod transaction;
 mod type_info;
 pub mod types;
@@ -0,0 +1,214 @@
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::executor::Executor;
+use cdbc::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::{Sqlite, SqliteConnectOptions, SqliteConnection};
+use cdbc::query::query;
+use cdbc::query_as::query_as;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+
+fn parse_for_maintenance(url: &str) -> Result<(SqliteConnectOptions, PathBuf), Error> {
+    let options = SqliteConnectOptions::from_str(url)?;
+
+    if options.in_memory {
+        return Err(Error::Configuration(
+            "an in-memory sqlite database has no file to create or drop".into(),
+        ));
+    }
+
+    let path = options.filename.to_path_buf();
+    Ok((options, path))
+}
+
+impl MigrateDatabase for Sqlite {
+    fn create_database(url: &str) -> Result<(), Error> {
+        let (options, path) = parse_for_maintenance(url)?;
+
+        if path.exists() {
+            return Err(Error::Configuration(
+                format!("database file {} already exists", path.display()).into(),
+            ));
+        }
+
+        // opening the database creates the file
+        SqliteConnection::connect_with(&options.create_if_missing(true))?.close()
+    }
+
+    fn database_exists(url: &str) -> Result<bool, Error> {
+        let (_, path) = parse_for_maintenance(url)?;
+
+        Ok(path.exists())
+    }
+
+    fn drop_database(url: &str) -> Result<(), Error> {
+        let (_, path) = parse_for_maintenance(url)?;
+
+        // the write-ahead log and shared memory files are deleted with the database
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let mut file = path.clone().into_os_string();
+            file.push(suffix);
+            match fs::remove_file(&file) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Migrate for SqliteConnection {
+    fn ensure_migrations_table(&mut self) -> Result<(), MigrateError> {
+        // language=SQLite
+        self.execute(
+            r#"
+CREATE TABLE IF NOT EXISTS _cdbc_migrations (
+    version BIGINT PRIMARY KEY,
+    description TEXT NOT NULL,
+    installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    success BOOLEAN NOT NULL,
+    checksum TEXT NOT NULL,
+    execution_time BIGINT NOT NULL
+);
+                "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError> {
+        // language=SQLite
+        let row: Option<(i64,)> = query_as(
+            "SELECT version FROM _cdbc_migrations WHERE success = false ORDER BY version LIMIT 1",
+        )
+        .fetch_optional(self)?;
+
+        Ok(row.map(|r| r.0))
+    }
+
+    fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError> {
+        // language=SQLite
+        let rows: Vec<(i64, String)> =
+            query_as("SELECT version, checksum FROM _cdbc_migrations ORDER BY version")
+                .fetch_all(self)?;
+
+        let migrations = rows
+            .into_iter()
+            .map(|(version, checksum)| AppliedMigration {
+                version,
+                checksum: checksum.into(),
+            })
+            .collect();
+
+        Ok(migrations)
+    }
+
+    fn lock(&mut self) -> Result<(), MigrateError> {
+        // sqlite has no application locks, an exclusive transaction locks the whole database file
+        // until `unlock`. waiting for another migrator is bounded by the busy timeout.
+        // migrations are applied in savepoints inside of it.
+
+        // language=SQLite
+        let _ = self.execute("BEGIN EXCLUSIVE")?;
+
+        Ok(())
+    }
+
+    fn unlock(&mut self) -> Result<(), MigrateError> {
+        // language=SQLite
+        let _ = self.execute("COMMIT")?;
+
+        Ok(())
+    }
+
+    fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        let start = Instant::now();
+
+        savepoint(self, |conn| {
+            let _ = conn.execute(&*migration.sql)?;
+
+            // language=SQLite
+            let _ = query(
+                r#"
+    INSERT INTO _cdbc_migrations ( version, description, success, checksum, execution_time )
+    VALUES ( ?1, ?2, TRUE, ?3, ?4 )
+                "#,
+            )
+            .bind(migration.version)
+            .bind(&*migration.description)
+            .bind(&*migration.checksum)
+            .bind(start.elapsed().as_nanos() as i64)
+            .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(start.elapsed())
+    }
+
+    fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        let down_sql = migration
+            .down_sql
+            .as_deref()
+            .ok_or(MigrateError::Irreversible(migration.version))?;
+
+        let start = Instant::now();
+
+        savepoint(self, |conn| {
+            let _ = conn.execute(down_sql)?;
+
+            // language=SQLite
+            let _ = query("DELETE FROM _cdbc_migrations WHERE version = ?1")
+                .bind(migration.version)
+                .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(start.elapsed())
+    }
+}
+
+/// run `f` in a savepoint, which works both inside the exclusive transaction of `lock`
+/// and as a transaction of its own when the migrator doesn't lock.
+fn savepoint<F>(conn: &mut SqliteConnection, f: F) -> Result<(), Error>
+    where F: FnOnce(&mut SqliteConnection) -> Result<(), Error> {
+    // language=SQLite
+    conn.execute("SAVEPOINT _cdbc_migration")?;
+    match f(conn) {
+        Ok(_) => {
+            conn.execute("RELEASE _cdbc_migration")?;
+            Ok(())
+        }
+        Err(e) => {
+            conn.execute("ROLLBACK TO _cdbc_migration; RELEASE _cdbc_migration")?;
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::migrate::MigrateDatabase;
+    use crate::Sqlite;
+
+    #[test]
+    fn test_create_drop_database() {
+        let path = std::env::temp_dir().join(format!("cdbc_test_create_{}.db", std::process::id()));
+        let url = format!("sqlite://{}", path.display());
+        assert!(!Sqlite::database_exists(&url).unwrap());
+        Sqlite::create_database(&url).unwrap();
+        assert!(Sqlite::database_exists(&url).unwrap());
+        assert!(Sqlite::create_database(&url).is_err());
+        Sqlite::drop_database(&url).unwrap();
+        assert!(!Sqlite::database_exists(&url).unwrap());
+        // dropping a missing database is fine
+        Sqlite::drop_database(&url).unwrap();
+        assert!(Sqlite::create_database("sqlite::memory:").is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use std::cell::RefCell;
+use std::os::raw::{c_char, c_void};
+use std::ptr::{null, null_mut, NonNull};
+use std::sync::Arc;
+
+use cdbc::error::Error;
+use cdbc::replay::{RecordedResult, RecordedValue, Replay};
+use cdbc::utils::ustr::UStr;
+use cdbc::value::{Value, ValueRef};
+use cdbc::HashMap;
+use libsqlite3_sys::{
+    sqlite3, sqlite3_bind_blob64, sqlite3_bind_double, sqlite3_bind_int64, sqlite3_bind_null,
+    sqlite3_bind_text64, sqlite3_close, sqlite3_column_value, sqlite3_finalize, sqlite3_open_v2,
+    sqlite3_prepare_v2, sqlite3_reset, sqlite3_step, sqlite3_stmt, SQLITE_OK, SQLITE_OPEN_CREATE,
+    SQLITE_OPEN_MEMORY, SQLITE_OPEN_NOMUTEX, SQLITE_OPEN_READWRITE, SQLITE_ROW, SQLITE_TRANSIENT,
+    SQLITE_UTF8,
+};
+use serde::Deserialize;
+use crate::type_info::DataType;
+use crate::{
+    Sqlite, SqliteArgumentValue, SqliteArguments, SqliteColumn, SqliteError, SqliteQueryResult,
+    SqliteRow, SqliteTypeInfo, SqliteValue, SqliteValueRef,
+};
+
+/// The columns of a recorded result, with the values they are replayed with.
+pub struct SqliteReplayColumns {
+    columns: Arc<Vec<SqliteColumn>>,
+    column_names: Arc<HashMap<UStr, usize>>,
+    values: RefCell<ValueFactory>,
+}
+
+/// A statement `SELECT ?1` of an in-memory database, a [`SqliteValue`] can only be created
+/// by SQLite.
+struct ValueFactory {
+    db: NonNull<sqlite3>,
+    statement: NonNull<sqlite3_stmt>,
+}
+
+// the database is opened with [SQLITE_OPEN_NOMUTEX] and only used by its owner
+unsafe impl Send for ValueFactory {}
+
+impl ValueFactory {
+    fn new() -> Result<Self, Error> {
+        let flags = SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_MEMORY | SQLITE_OPEN_NOMUTEX;
+        let mut db = null_mut();
+        // <https://www.sqlite.org/c3ref/open.html>
+        let status = unsafe {
+            sqlite3_open_v2(c":memory:".as_ptr(), &mut db, flags, null())
+        };
+        let db = NonNull::new(db).ok_or_else(|| err_protocol!("SQLite is unable to allocate memory"))?;
+        if status != SQLITE_OK {
+            return Err(close_with_error(db));
+        }
+
+        let mut statement = null_mut();
+        // <https://www.sqlite.org/c3ref/prepare.html>
+        let status = unsafe {
+            sqlite3_prepare_v2(
+                db.as_ptr(),
+                c"SELECT ?1".as_ptr(),
+                -1,
+                &mut statement,
+                null_mut(),
+            )
+        };
+        match NonNull::new(statement) {
+            Some(statement) if status == SQLITE_OK => Ok(ValueFactory { db, statement }),
+            _ => Err(close_with_error(db)),
+        }
+    }
+
+    fn last_error(&self) -> Error {
+        SqliteError::new(self.db.as_ptr()).into()
+    }
+
+    /// The value recorded by [`record_value`].
+    fn value(&mut self, value: Option<&str>, type_info: &SqliteTypeInfo) -> Result<SqliteValue, Error> {
+        let statement = self.statement.as_ptr();
+        // <https://www.sqlite.org/c3ref/bind_blob.html>
+        let status = match value.map(|v| v.split_once(':').unwrap_or((v, ""))) {
+            None => unsafe { sqlite3_bind_null(statement, 1) },
+            Some(("INTEGER", v)) => {
+                let v = v.parse::<i64>().map_err(|e| Error::Decode(e.into()))?;
+                unsafe { sqlite3_bind_int64(statement, 1, v) }
+            }
+            Some(("REAL", v)) => {
+                let v = v.parse::<f64>().map_err(|e| Error::Decode(e.into()))?;
+                unsafe { sqlite3_bind_double(statement, 1, v) }
+            }
+            Some(("TEXT", v)) => unsafe {
+                sqlite3_bind_text64(
+                    statement,
+                    1,
+                    v.as_ptr() as *const c_char,
+                    v.len() as u64,
+                    SQLITE_TRANSIENT(),
+                    SQLITE_UTF8 as u8,
+                )
+            },
+            Some(("BLOB", v)) => {
+                let v = hex::decode(v).map_err(|e| Error::Decode(e.into()))?;
+                unsafe {
+                    sqlite3_bind_blob64(
+                        statement,
+                        1,
+                        v.as_ptr() as *const c_void,
+                        v.len() as u64,
+                        SQLITE_TRANSIENT(),
+                    )
+                }
+            }
+            Some((ty, _)) => return Err(err_protocol!("unknown recorded SQLite value of type {}", ty)),
+        };
+        if status != SQLITE_OK {
+            return Err(self.last_error());
+        }
+        // <https://www.sqlite.org/c3ref/step.html>
+        if unsafe { sqlite3_step(statement) } != SQLITE_ROW {
+            return Err(self.last_error());
+        }
+        // the value is copied before the statement is reset
+        let value = unsafe { SqliteValue::new(sqlite3_column_value(statement, 0), type_info.clone()) };
+        unsafe { sqlite3_reset(statement) };
+        Ok(value)
+    }
+}
+
+impl Drop for ValueFactory {
+    fn drop(&mut self) {
+        unsafe {
+            sqlite3_finalize(self.statement.as_ptr());
+            sqlite3_close(self.db.as_ptr());
+        }
+    }
+}
+
+fn close_with_error(db: NonNull<sqlite3>) -> Error {
+    let error = SqliteError::new(db.as_ptr());
+    unsafe { sqlite3_close(db.as_ptr()) };
+    error.into()
+}
+
+fn record_value(value: &SqliteValue) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    let value_ref = SqliteValueRef::value(value);
+    Some(match value_ref.type_info().0 {
+        DataType::Int | DataType::Int64 | DataType::Bool => format!("INTEGER:{}", value_ref.int64()),
+        DataType::Float => format!("REAL:{}", value_ref.double()),
+        DataType::Blob => format!("BLOB:{}", hex::encode(value_ref.blob())),
+        _ => format!("TEXT:{}", value_ref.text().unwrap_or_default()),
+    })
+}
+
+/// Values are recorded with the storage class SQLite returned them in,
+/// e.g. `INTEGER:7`, `TEXT:tom` or the hex of a `BLOB`.
+impl Replay for Sqlite {
+    type Columns = SqliteReplayColumns;
+
+    fn record_arguments(arguments: &SqliteArguments<'_>) -> Vec<RecordedValue> {
+        arguments
+            .values
+            .iter()
+            .map(|value| {
+                let (type_name, value) = match value {
+                    SqliteArgumentValue::Null => ("NULL", None),
+                    SqliteArgumentValue::Text(v) => ("TEXT", Some(v.to_string())),
+                    SqliteArgumentValue::Blob(v) => ("BLOB", Some(hex::encode(v))),
+                    SqliteArgumentValue::Double(v) => ("REAL", Some(v.to_string())),
+                    SqliteArgumentValue::Int(v) => ("INTEGER", Some(v.to_string())),
+                    SqliteArgumentValue::Int64(v) => ("INTEGER", Some(v.to_string())),
+                };
+                RecordedValue {
+                    type_name: type_name.to_owned(),
+                    value,
+                }
+            })
+            .collect()
+    }
+
+    fn record_columns(row: &SqliteRow) -> serde_json::Value {
+        serde_json::to_value(&*row.columns).unwrap_or_default()
+    }
+
+    fn record_row(row: &SqliteRow) -> Vec<Option<String>> {
+        row.values.iter().map(record_value).collect()
+    }
+
+    fn record_result(result: &SqliteQueryResult) -> RecordedResult {
+        RecordedResult {
+            rows_affected: result.changes,
+            last_insert_id: Some(result.last_insert_rowid),
+        }
+    }
+
+    fn replay_columns(columns: &serde_json::Value) -> Result<Self::Columns, Error> {
+        let columns = Vec::<SqliteColumn>::deserialize(columns).map_err(|e| Error::Decode(e.into()))?;
+        let column_names = columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ordinal))
+            .collect();
+        Ok(SqliteReplayColumns {
+            columns: Arc::new(columns),
+            column_names: Arc::new(column_names),
+            values: RefCell::new(ValueFactory::new()?),
+        })
+    }
+
+    fn replay_row(columns: &Self::Columns, values: &[Option<String>]) -> Result<SqliteRow, Error> {
+        if values.len() != columns.columns.len() {
+            return Err(err_protocol!(
+                "recorded row has {} values for {} columns",
+                values.len(),
+                columns.columns.len()
+            ));
+        }
+        let mut factory = columns.values.borrow_mut();
+        let values = values
+            .iter()
+            .zip(columns.columns.iter())
+            .map(|(value, column)| factory.value(value.as_deref(), &column.type_info))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(SqliteRow {
+            values: values.into_boxed_slice(),
+            columns: columns.columns.clone(),
+            column_names: columns.column_names.clone(),
+        })
+    }
+
+    fn replay_result(result: &RecordedResult) -> SqliteQueryResult {
+        SqliteQueryResult {
+            changes: result.rows_affected,
+            last_insert_rowid: result.last_insert_id.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::error::{Error, ErrorKind};
+    use cdbc::executor::Executor;
+    use cdbc::pool::Pool;
+    use cdbc::replay::{Recorder, Replayer};
+    use crate::Sqlite;
+
+    type User = (i64, Option<String>, Option<f64>, Option<Vec<u8>>);
+
+    fn users<E>(executor: E) -> Result<(Vec<User>, Option<User>, i64, ErrorKind), Error>
+        where E: Executor<Database = Sqlite> + Copy,
+    {
+        cdbc::query("create table user(id integer primary key, name text, score real, avatar blob)")
+            .execute(executor)?;
+        let id = cdbc::query("insert into user(name, score, avatar) values (?, ?, ?)")
+            .bind("tom")
+            .bind(1.5)
+            .bind(vec![0u8, 255])
+            .execute(executor)?
+            .last_insert_rowid();
+        cdbc::query("insert into user(name, score, avatar) values (?, ?, ?)")
+            .bind(None::<String>)
+            .bind(None::<f64>)
+            .bind(None::<Vec<u8>>)
+            .execute(executor)?;
+        let all = cdbc::query_as::<_, User>("select * from user order by id").fetch_all(executor)?;
+        let missing = cdbc::query_as::<_, User>("select * from user where id = ?")
+            .bind(100)
+            .fetch_optional(executor)?;
+        let err = cdbc::query("insert into user(id) values (?)")
+            .bind(id)
+            .execute(executor)
+            .unwrap_err();
+        Ok((all, missing, id, err.as_database_error().unwrap().kind()))
+    }
+
+    #[cdbc::test]
+    fn test_record_replay(pool: Pool<Sqlite>) {
+        let recorder = Recorder::new(pool);
+        let recorded = users(&recorder).unwrap();
+        assert_eq!(recorded.0.len(), 2);
+        assert_eq!(recorded.0[0], (1, Some("tom".to_string()), Some(1.5), Some(vec![0, 255])));
+        assert_eq!(recorded.0[1], (2, None, None, None));
+        assert_eq!(recorded.3, ErrorKind::UniqueViolation);
+
+        let path = std::env::temp_dir().join(format!("cdbc_sqlite_recording_{}.json", std::process::id()));
+        recorder.save(&path).unwrap();
+        let replayer = Replayer::<Sqlite>::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(users(&replayer).unwrap(), recorded);
+        replayer.verify();
+    }
+}
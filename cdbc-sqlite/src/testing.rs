@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+use cdbc::connection::Connection;
+use cdbc::error::Error;
+use cdbc::testing::{test_db_name, TestArgs, TestContext, TestSupport};
+use crate::{Sqlite, SqliteConnectOptions, SqliteConnection};
+
+fn memory_options(db_name: &str) -> SqliteConnectOptions {
+    let mut options = SqliteConnectOptions::new();
+    options.in_memory = true;
+    options.shared_cache = true;
+    options.filename = Cow::Owned(PathBuf::from(format!("file:{}", db_name)));
+    options
+}
+
+impl TestSupport for Sqlite {
+    fn test_context(args: &TestArgs) -> Result<TestContext<Self>, Error> {
+        args.reject_template("sqlite")?;
+        // a named in-memory database lives as long as one connection to it is open,
+        // `keep_alive` holds it while the pool opens and closes connections
+        let db_name = test_db_name();
+        let keep_alive = SqliteConnection::connect_with(&memory_options(&db_name))?;
+        Ok(TestContext {
+            connect_options: memory_options(&db_name),
+            master_options: memory_options(&db_name),
+            db_name,
+            keep_alive: Some(keep_alive),
+        })
+    }
+
+    fn cleanup_test(_db_name: &str, _master_options: &SqliteConnectOptions) -> Result<(), Error> {
+        // freed with the last connection
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdbc::executor::Executor;
+    use cdbc::pool::Pool;
+    use crate::Sqlite;
+
+    // each test gets its own database, so both can create the same table
+    #[cdbc::test]
+    fn test_isolated_a(pool: Pool<Sqlite>) -> Result<(), cdbc::Error> {
+        let mut conn = pool.acquire()?;
+        conn.execute("create table t(id integer); insert into t values (1)")?;
+        let count: i64 = cdbc::query_scalar("select count(*) from t").fetch_one(&mut *conn)?;
+        assert_eq!(count, 1);
+        Ok(())
+    }
+
+    #[cdbc::test]
+    fn test_isolated_b(pool: Pool<Sqlite>) {
+        let mut conn = pool.acquire().unwrap();
+        conn.execute("create table t(id integer)").unwrap();
+        let count: i64 = cdbc::query_scalar("select count(*) from t").fetch_one(&mut *conn).unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::ptr;
 
 use libsqlite3_sys::{sqlite3_exec, SQLITE_OK};
@@ -6,8 +7,10 @@ use cdbc::error::Error;
 use cdbc::executor::Executor;
 use crate::{Sqlite, SqliteConnection, SqliteError};
 use cdbc::transaction::{
-    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    begin_ansi_transaction_sql, check_savepoint_options, commit_ansi_transaction_sql,
+    release_savepoint_ansi_sql, rollback_ansi_transaction_sql, rollback_to_savepoint_ansi_sql,
+    savepoint_ansi_sql, AccessMode, IsolationLevel, TransactionManager,
+    TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for SQLite.
@@ -16,8 +19,8 @@ pub struct SqliteTransactionManager;
 impl TransactionManager for SqliteTransactionManager {
     type Database = Sqlite;
 
-    fn begin(conn: &mut SqliteConnection) ->  Result<(), Error> {
-      conn.worker.begin()
+    fn begin_with(conn: &mut SqliteConnection, options: &TransactionOptions) ->  Result<(), Error> {
+      conn.worker.begin(*options)
     }
 
     fn commit(conn: &mut SqliteConnection) ->Result<(), Error> {
@@ -31,4 +34,45 @@ impl TransactionManager for SqliteTransactionManager {
     fn start_rollback(conn: &mut SqliteConnection) {
         conn.worker.start_rollback().ok();
     }
+
+    fn savepoint(conn: &mut SqliteConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*savepoint_ansi_sql(name))?;
+        Ok(())
+    }
+
+    fn release_savepoint(conn: &mut SqliteConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*release_savepoint_ansi_sql(name))?;
+        Ok(())
+    }
+
+    fn rollback_to_savepoint(conn: &mut SqliteConnection, name: &str) -> Result<(), Error> {
+        conn.execute(&*rollback_to_savepoint_ansi_sql(name))?;
+        Ok(())
+    }
+}
+
+/// `BEGIN EXCLUSIVE` or `BEGIN IMMEDIATE` to take the locks up front, or a savepoint.
+pub(crate) fn begin_transaction_sql(
+    depth: usize,
+    options: &TransactionOptions,
+) -> Result<Cow<'static, str>, Error> {
+    check_savepoint_options(depth, options)?;
+    if depth > 0 {
+        return Ok(begin_ansi_transaction_sql(depth));
+    }
+    if options.access_mode == Some(AccessMode::ReadOnly) {
+        return Err(Error::Configuration(
+            "SQLite does not support read-only transactions".into(),
+        ));
+    }
+    // SQLite transactions are always serializable, the other levels run a plain (deferred) `BEGIN`
+    if options.isolation_level == Some(IsolationLevel::Serializable) {
+        // no other connection may read or write until we are done
+        Ok(Cow::Borrowed("BEGIN EXCLUSIVE"))
+    } else if options.access_mode == Some(AccessMode::ReadWrite) {
+        // take the write lock now instead of failing with SQLITE_BUSY on the first write
+        Ok(Cow::Borrowed("BEGIN IMMEDIATE"))
+    } else {
+        Ok(begin_ansi_transaction_sql(depth))
+    }
 }
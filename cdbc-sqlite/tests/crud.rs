@@ -0,0 +1,53 @@
+use cdbc::crud::{Condition, PageRequest, CRUD};
+use cdbc::pool::Pool;
+use cdbc::Error;
+use cdbc_sqlite::Sqlite;
+
+#[cdbc::crud(db = "sqlite")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    #[crud(id)]
+    pub id: Option<i32>,
+    pub name: Option<String>,
+}
+
+// no `#[crud(id)]`
+#[cdbc::crud(db = "sqlite")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub at: Option<i32>,
+    pub name: Option<String>,
+}
+
+#[cdbc::test]
+fn test_find_page_by_key(mut pool: Pool<Sqlite>) -> Result<(), Error> {
+    cdbc::query("create table account(id integer, name text)").execute(&pool)?;
+    let rows = [3, 1, 2].map(|id| Account { id: Some(id), name: Some(format!("a{}", id)) });
+    pool.inserts(rows.to_vec())?;
+
+    let ids = |page: cdbc::crud::Page<Account>| page.records.iter().map(|a| a.id.unwrap()).collect::<Vec<_>>();
+    // ordered by the key without an order
+    assert_eq!(ids(pool.find_page(Condition::new(), PageRequest::new(1, 2))?), vec![1, 2]);
+    let page = pool.find_page(Condition::new().order_by_desc(Account::id()), PageRequest::new(2, 2))?;
+    assert_eq!((page.total, page.pages()), (3, 2));
+    assert_eq!(ids(page), vec![1]);
+    Ok(())
+}
+
+#[cdbc::test]
+fn test_find_page_without_key(mut pool: Pool<Sqlite>) -> Result<(), Error> {
+    cdbc::query("create table event(at integer, name text)").execute(&pool)?;
+    let rows = [2, 3, 1].map(|at| Event { at: Some(at), name: Some(format!("e{}", at)) });
+    pool.inserts(rows.to_vec())?;
+
+    let err = pool.find_page(Condition::<Event>::new(), PageRequest::new(1, 2)).unwrap_err();
+    assert!(matches!(&err, Error::InvalidArgument(msg) if msg.contains("Condition::order_by")), "{}", err);
+
+    type F = Event;
+    let page = pool.find_page(F::at().gt(1).order_by_desc(F::at()), PageRequest::new(1, 1))?;
+    assert_eq!(page.total, 2);
+    assert_eq!(page.records, vec![Event { at: Some(3), name: Some("e3".to_string()) }]);
+    let ats: Vec<_> = pool.finds(Condition::new().order_by(F::at()))?.into_iter().map(|e| e.at.unwrap()).collect();
+    assert_eq!(ats, vec![1, 2, 3]);
+    Ok(())
+}
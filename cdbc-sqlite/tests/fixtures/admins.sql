@@ -0,0 +1 @@
+insert into users (id, name) values (3, 'admin');
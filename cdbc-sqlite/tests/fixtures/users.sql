@@ -0,0 +1,6 @@
+create table users
+(
+    id   integer primary key,
+    name text not null
+);
+insert into users (id, name) values (1, 'tom'), (2, 'jerry');
@@ -0,0 +1,21 @@
+use cdbc::pool::Pool;
+use cdbc_sqlite::Sqlite;
+
+#[cdbc::test(fixtures("fixtures/users.sql"))]
+fn test_fixture(pool: Pool<Sqlite>) -> Result<(), cdbc::Error> {
+    let users: Vec<(i64, String)> = cdbc::query_as("select id, name from users order by id").fetch_all(&pool)?;
+    assert_eq!(users, vec![(1, "tom".to_string()), (2, "jerry".to_string())]);
+    Ok(())
+}
+
+// applied in the order they are listed
+#[cdbc::test(fixtures("fixtures/users.sql", "fixtures/admins.sql"))]
+fn test_fixtures_in_order(pool: Pool<Sqlite>) -> Result<(), cdbc::Error> {
+    let names: Vec<String> = cdbc::query_scalar("select name from users order by id").fetch_all(&pool)?;
+    assert_eq!(names, vec!["tom", "jerry", "admin"]);
+    Ok(())
+}
+
+#[cdbc::test(template = "users")]
+#[should_panic(expected = "`template = \"users\"` is not supported by sqlite")]
+fn test_template_unsupported(_pool: Pool<Sqlite>) {}
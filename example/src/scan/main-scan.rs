@@ -36,7 +36,7 @@ fn main() -> cdbc::Result<()> {
     //pool.clone() also is support
     let mut arg1 = arg.clone();
     arg1.id = None;
-    let r = CRUD::update(&mut pool.clone(), arg1, "id = '2'");
+    let r = CRUD::update(&mut pool.clone(), arg1, BizActivity::id().eq("2"));
     println!("CRUD::update => {:?}", r);
 
     let mut conn = pool.acquire().unwrap();
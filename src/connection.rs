@@ -1,6 +1,6 @@
 use crate::database::{Database, HasStatementCache};
 use crate::error::Error;
-use crate::transaction::Transaction;
+use crate::transaction::{RetryPolicy, Transaction, TransactionCallbacks, TransactionOptions};
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::time::Duration;
@@ -21,6 +21,20 @@ pub trait Connection: Send+Executor {
     /// Checks if a connection to the database is still valid.
     fn ping(&mut self) -> Result<(), Error>;
 
+    /// `true` if the connection was left in an unknown state and must not be used again,
+    /// e.g. a dropped stream of it could not be cancelled or drained.
+    ///
+    /// A broken [`PoolConnection`](crate::pool::PoolConnection) is closed instead of being
+    /// returned to the pool.
+    fn is_broken(&self) -> bool {
+        false
+    }
+
+    /// Set the [`default_timeout`](Executor::default_timeout) of the queries run on this connection.
+    ///
+    /// Does nothing by default, for connections that don't support a query timeout.
+    fn set_default_timeout(&mut self, _timeout: Option<Duration>) {}
+
     /// Begin a new transaction or establish a savepoint within the active transaction.
     ///
     /// Returns a [`Transaction`] for controlling and tracking the new transaction.
@@ -28,6 +42,15 @@ pub trait Connection: Send+Executor {
         where
             Self: Sized;
 
+    /// Begin a new transaction with the given isolation level and access mode,
+    /// see [`TransactionOptions`].
+    ///
+    /// Inside an active transaction only the default options are accepted,
+    /// which establish a savepoint like [`begin`](Self::begin).
+    fn begin_with(&mut self, options: TransactionOptions) -> Result<Transaction<'_, Self::Database>, Error>
+        where
+            Self: Sized;
+
     /// Execute the function inside a transaction.
     ///
     /// If the function returns an error, the transaction will be rolled back. If it does not
@@ -72,6 +95,59 @@ pub trait Connection: Send+Executor {
         }
     }
 
+    /// Execute the function inside a transaction, and run it again in a new transaction
+    /// if it or the commit fails with a serialization failure, a deadlock or a lock timeout.
+    ///
+    /// Every attempt begins with [`RetryPolicy::options`], e.g. `SERIALIZABLE`. A failed attempt
+    /// is rolled back before waiting for the backoff of the `policy`, an error of the rollback is
+    /// only logged. The last error is returned once the policy gives up,
+    /// see [`RetryPolicy::retry_after`].
+    ///
+    /// Call it outside of a transaction, a savepoint can't recover from a serialization failure.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use cdbc::connection::Connection;
+    /// use cdbc::transaction::{IsolationLevel, RetryPolicy, TransactionOptions};
+    ///
+    /// let policy = RetryPolicy::new()
+    ///     .options(TransactionOptions::new().isolation_level(IsolationLevel::Serializable));
+    /// conn.transaction_with_retry(&policy, |tx| {
+    ///     cdbc::query("update account set balance = balance - 1 where id = 1").execute(&mut **tx)
+    /// })
+    /// ```
+    fn transaction_with_retry<'a, F, R>(&'a mut self, policy: &RetryPolicy, mut callback: F) -> Result<R, Error>
+        where
+                for<'c> F: FnMut(&'c mut Transaction<'_, Self::Database>) -> Result<R, Error>
+        + 'a
+        + Send,
+                Self: Sized,
+                R: Send,
+    {
+        let mut attempt = 1;
+        loop {
+            let err = match self.begin_with(policy.options) {
+                Ok(mut transaction) => match callback(&mut transaction) {
+                    Ok(ret) => match transaction.commit() {
+                        Ok(()) => return Ok(ret),
+                        Err(err) => rollback_attempt(transaction, err),
+                    },
+                    Err(err) => rollback_attempt(transaction, err),
+                },
+                Err(err) => err,
+            };
+            match policy.retry_after(attempt, &err) {
+                Some(delay) => {
+                    log::debug!("transaction attempt {} failed, retrying in {:?}: {}", attempt, delay, err);
+                    mco::coroutine::sleep(delay);
+                }
+                None => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+
     /// The number of statements currently cached in the connection.
     fn cached_statements_size(&self) -> usize
         where
@@ -95,6 +171,15 @@ pub trait Connection: Send+Executor {
     #[doc(hidden)]
     fn should_flush(&self) -> bool;
 
+    /// The callbacks of the open transactions on this connection.
+    ///
+    /// `None` by default, then each [`Transaction`] keeps its own callbacks and those of a
+    /// nested transaction run when it ends instead of with the outermost transaction.
+    #[doc(hidden)]
+    fn transaction_callbacks(&mut self) -> Option<&mut TransactionCallbacks> {
+        None
+    }
+
     /// Establish a new database connection.
     ///
     /// A value of [`Options`][Self::Options] is parsed from the provided connection string. This parsing
@@ -118,6 +203,15 @@ pub trait Connection: Send+Executor {
     }
 }
 
+/// Rolls back a failed attempt of [`Connection::transaction_with_retry`], keeping the error
+/// of the attempt so the policy can still retry it.
+fn rollback_attempt<DB: Database>(mut transaction: Transaction<'_, DB>, err: Error) -> Error {
+    if let Err(e) = transaction.rollback() {
+        log::warn!("error occurred while rolling back a failed transaction attempt: {}", e);
+    }
+    err
+}
+
 pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug {
     type Connection: Connection + ?Sized;
 
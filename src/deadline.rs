@@ -0,0 +1,230 @@
+//! Query timeouts and coroutine-local deadlines.
+//!
+//! A query is bounded by the earliest of its own [`Query::timeout`](crate::query::Query::timeout)
+//! (or the [`Executor::default_timeout`](crate::executor::Executor::default_timeout) of its executor)
+//! and the deadline of the coroutine running it, set with [`with_deadline`] or [`with_timeout`].
+//! When a query runs past it, the driver cancels the statement on the server and the query fails
+//! with [`Error::QueryTimedOut`].
+//!
+//! ```ignore
+//! // every query and `Pool::acquire` of the request shares its deadline
+//! cdbc::deadline::with_timeout(Duration::from_secs(3), || {
+//!     let user = query_as::<_, User>("select * from user where id = ?").bind(id).fetch_one(&pool)?;
+//!     query("update user set visits = visits + 1 where id = ?").bind(id).execute(&pool)?;
+//!     Ok(user)
+//! })
+//! ```
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mco::coroutine::{Builder, JoinHandle};
+use mco::std::sync::channel::{channel, Sender};
+
+use crate::error::{Error, Result};
+
+mco::coroutine_local!(static DEADLINE: Cell<Option<Instant>> = Cell::new(None));
+
+/// The deadline of the current coroutine, `None` outside of [`with_deadline`].
+pub fn deadline() -> Option<Instant> {
+    DEADLINE.with(|d| d.get())
+}
+
+/// Run `f` with a deadline for the current coroutine.
+///
+/// Every query and [`Pool::acquire`](crate::pool::Pool::acquire) made by `f` on this coroutine
+/// fails once the deadline passed. A nested deadline can only make the current one earlier.
+pub fn with_deadline<R, F: FnOnce() -> R>(deadline: Instant, f: F) -> R {
+    let _guard = Restore(DEADLINE.with(|d| d.replace(earliest(d.get(), Some(deadline)))));
+    f()
+}
+
+/// Run `f` with a deadline `timeout` from now for the current coroutine, see [`with_deadline`].
+pub fn with_timeout<R, F: FnOnce() -> R>(timeout: Duration, f: F) -> R {
+    with_deadline(Instant::now() + timeout, f)
+}
+
+/// The deadline of a query run now with `timeout`: the earliest of `timeout` from now and the
+/// deadline of the current coroutine.
+///
+/// Must be called on the coroutine of the caller, not in the producer of a stream.
+pub fn query_deadline(timeout: Option<Duration>) -> Option<Instant> {
+    earliest(timeout.map(|t| Instant::now() + t), deadline())
+}
+
+/// Fails with [`Error::QueryTimedOut`] if `deadline` already passed.
+pub fn check(deadline: Option<Instant>) -> Result<()> {
+    match deadline {
+        Some(deadline) if deadline <= Instant::now() => Err(Error::QueryTimedOut),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn earliest(a: Option<Instant>, b: Option<Instant>) -> Option<Instant> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// restores the deadline of the coroutine when [`with_deadline`] returns(or unwinds)
+struct Restore(Option<Instant>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        DEADLINE.with(|d| d.set(self.0));
+    }
+}
+
+/// Cancels a running statement once its deadline passes.
+///
+/// A driver starts one after sending the statement, with a `cancel` that reaches the server out
+/// of band (e.g. a PostgreSQL CancelRequest). The server then fails the statement, and
+/// [`check`](Watchdog::check) turns that failure into [`Error::QueryTimedOut`].
+///
+/// Dropping the watchdog stops it, and waits for a `cancel` in progress to finish, so it can not
+/// hit a later statement.
+pub struct Watchdog {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+    fired: Arc<AtomicBool>,
+}
+
+impl Watchdog {
+    /// Watch a statement, `cancel` is called if it still runs at `deadline`.
+    /// Without a deadline nothing is watched.
+    pub fn start<F>(deadline: Option<Instant>, cancel: F) -> Self
+        where F: FnOnce() + Send + 'static {
+        let fired = Arc::new(AtomicBool::new(false));
+        let deadline = match deadline {
+            Some(deadline) => deadline,
+            None => {
+                return Self {
+                    stop: None,
+                    handle: None,
+                    fired,
+                };
+            }
+        };
+        let (stop, stopped) = channel::<()>();
+        let fired_ = fired.clone();
+        let handle = Builder::new().spawn(move || {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(timeout) {
+                fired_.store(true, Ordering::SeqCst);
+                cancel();
+            }
+        });
+        Self {
+            stop: Some(stop),
+            handle: Some(handle),
+            fired,
+        }
+    }
+
+    /// `true` once the deadline passed and the statement was cancelled.
+    pub fn timed_out(&self) -> bool {
+        self.fired.load(Ordering::SeqCst)
+    }
+
+    /// Replace the database error of a statement cancelled by this watchdog
+    /// with [`Error::QueryTimedOut`].
+    pub fn check<T>(&self, result: Result<T>) -> Result<T> {
+        match result {
+            Err(Error::Database(_)) if self.timed_out() => Err(Error::QueryTimedOut),
+            result => result,
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use crate::deadline::{deadline, query_deadline, with_deadline, with_timeout, Watchdog};
+    use crate::error::DatabaseError;
+    use crate::Error;
+
+    #[derive(Debug)]
+    struct Canceled;
+
+    impl std::fmt::Display for Canceled {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("canceling statement due to user request")
+        }
+    }
+
+    impl std::error::Error for Canceled {}
+
+    impl DatabaseError for Canceled {
+        fn message(&self) -> &str {
+            "canceling statement due to user request"
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+    }
+
+    #[test]
+    fn test_with_deadline() {
+        assert_eq!(deadline(), None);
+        let outer = Instant::now() + Duration::from_secs(10);
+        with_deadline(outer, || {
+            assert_eq!(deadline(), Some(outer));
+            // a nested deadline can only be earlier
+            with_timeout(Duration::from_secs(60), || assert_eq!(deadline(), Some(outer)));
+            with_timeout(Duration::from_secs(1), || assert!(deadline().unwrap() < outer));
+            assert_eq!(deadline(), Some(outer));
+            assert!(query_deadline(Some(Duration::from_secs(1))).unwrap() < outer);
+            assert_eq!(query_deadline(None), Some(outer));
+        });
+        assert_eq!(deadline(), None);
+        assert_eq!(query_deadline(None), None);
+    }
+
+    #[test]
+    fn test_watchdog() {
+        let cancelled = Arc::new(AtomicUsize::new(0));
+
+        let c = cancelled.clone();
+        let w = Watchdog::start(Some(Instant::now() + Duration::from_secs(10)), move || {
+            c.fetch_add(1, Ordering::SeqCst);
+        });
+        drop(w);
+        assert_eq!(cancelled.load(Ordering::SeqCst), 0);
+
+        let c = cancelled.clone();
+        let w = Watchdog::start(Some(Instant::now() + Duration::from_millis(10)), move || {
+            c.fetch_add(1, Ordering::SeqCst);
+        });
+        mco::coroutine::sleep(Duration::from_millis(100));
+        assert!(w.timed_out());
+        assert!(matches!(w.check::<()>(Err(Error::Database(Box::new(Canceled)))), Err(Error::QueryTimedOut)));
+        assert!(matches!(w.check::<()>(Err(Error::RowNotFound)), Err(Error::RowNotFound)));
+        drop(w);
+        assert_eq!(cancelled.load(Ordering::SeqCst), 1);
+    }
+}
@@ -5,7 +5,6 @@ use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::fmt::Display;
 use std::io;
-use std::io::ErrorKind;
 use std::result::Result as StdResult;
 use std::sync::mpsc::{RecvError, SendError};
 use std::sync::PoisonError;
@@ -41,6 +40,12 @@ pub enum Error {
     #[error("error with configuration: {0}")]
     Configuration(#[source] BoxDynError),
 
+    /// One or more of the arguments to the called function was invalid.
+    ///
+    /// The string contains more information.
+    #[error("{0}")]
+    InvalidArgument(String),
+
     /// Error returned from the database.
     #[error("error returned from database: {0}")]
     Database(#[source] Box<dyn DatabaseError>),
@@ -96,6 +101,13 @@ pub enum Error {
     #[error("pool timed out while waiting for an open connection")]
     PoolTimedOut,
 
+    /// A query did not complete before its timeout or the deadline of its coroutine,
+    /// the statement was cancelled on the server.
+    ///
+    /// See [`deadline`](crate::deadline).
+    #[error("query timed out")]
+    QueryTimedOut,
+
     /// [`Pool::close`] was called while we were waiting in [`Pool::acquire`].
     ///
     /// [`Pool::acquire`]: crate::pool::Pool::acquire
@@ -106,6 +118,10 @@ pub enum Error {
     /// A background worker has crashed.
     #[error("attempted to communicate with a crashed background worker")]
     WorkerCrashed(String),
+
+    /// Error occurred while running migrations.
+    #[error("{0}")]
+    Migrate(#[source] Box<crate::migrate::MigrateError>),
 }
 
 impl StdError for Box<dyn DatabaseError> {}
@@ -125,6 +141,15 @@ impl Error {
         }
     }
 
+    /// `true` if a transaction failed because of concurrent transactions (a serialization failure,
+    /// a deadlock or a lock that couldn't be acquired in time), running it again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self.as_database_error().map(|err| err.kind()) {
+            Some(ErrorKind::SerializationFailure | ErrorKind::Deadlock | ErrorKind::LockTimeout) => true,
+            _ => false,
+        }
+    }
+
     #[allow(dead_code)]
     #[inline]
     pub fn protocol(err: impl Display) -> Self {
@@ -149,6 +174,37 @@ pub fn mismatched_types<DB: Database, T: Type<DB>>(ty: &DB::TypeInfo) -> BoxDynE
         .into()
 }
 
+/// The class of a [`DatabaseError`], shared by every driver.
+///
+/// Drivers map their own codes (SQLSTATE, MySQL and MSSQL error numbers, SQLite result codes)
+/// onto it, so retry and mapping logic can be written once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A unique constraint or a unique index was violated.
+    UniqueViolation,
+    /// A foreign key constraint was violated.
+    ForeignKeyViolation,
+    /// A `NULL` was written to a `NOT NULL` column.
+    NotNullViolation,
+    /// A check constraint was violated.
+    CheckViolation,
+    /// The transaction could not be serialized with concurrent transactions, retrying it may succeed.
+    SerializationFailure,
+    /// The transaction was chosen as the victim of a deadlock, retrying it may succeed.
+    Deadlock,
+    /// A lock could not be acquired in time.
+    LockTimeout,
+    /// The statement was cancelled or interrupted.
+    QueryCanceled,
+    /// The server closed or is closing the connection.
+    ConnectionLost,
+    /// The database or the transaction is read-only.
+    ReadOnly,
+    /// Any other error.
+    Other,
+}
+
 /// An error that was returned from the database.
 pub trait DatabaseError: 'static + Send + Sync + StdError {
     /// The primary, human-readable error message.
@@ -168,14 +224,29 @@ pub trait DatabaseError: 'static + Send + Sync + StdError {
     #[doc(hidden)]
     fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static>;
 
+    /// The class of the error, see [`ErrorKind`].
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+
     /// Returns the name of the constraint that triggered the error, if applicable.
     /// If the error was caused by a conflict of a unique index, this will be the index name.
     ///
     /// ### Note
-    /// Currently only populated by the Postgres driver.
+    /// Postgres sends it as a field, the other drivers read it from the error message.
     fn constraint(&self) -> Option<&str> {
         None
     }
+
+    /// Returns the name of the table the error is about, if the server sent it.
+    fn table(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the name of the column the error is about, if the server sent it.
+    fn column(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl dyn DatabaseError {
@@ -251,6 +322,12 @@ impl From<String> for Error{
     }
 }
 
+impl From<crate::migrate::MigrateError> for Error {
+    fn from(arg: crate::migrate::MigrateError) -> Self {
+        Self::Migrate(Box::new(arg))
+    }
+}
+
 impl From<RecvError> for Error {
     fn from(arg: RecvError) -> Self {
          Self::Protocol(arg.to_string())
@@ -259,7 +336,7 @@ impl From<RecvError> for Error {
 
 impl From<Error> for std::io::Error{
     fn from(arg: Error) -> Self {
-        std::io::Error::new(ErrorKind::UnexpectedEof, Box::new(arg))
+        std::io::Error::new(io::ErrorKind::UnexpectedEof, Box::new(arg))
     }
 }
 
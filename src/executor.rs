@@ -3,8 +3,9 @@ use crate::describe::Describe;
 use crate::error::Error;
 use either::Either;
 use std::fmt::Debug;
+use std::time::Duration;
 use crate::{chan_stream, Pool};
-use crate::io::chan_stream::{ChanStream, Stream, TryStream};
+use crate::io::chan_stream::ChanStream;
 
 /// A type that contains or can provide a database
 /// connection to use for executing queries against the database.
@@ -24,6 +25,13 @@ use crate::io::chan_stream::{ChanStream, Stream, TryStream};
 pub trait Executor: Send + Debug + Sized {
     type Database: Database;
 
+    /// The timeout of queries without their own [`Execute::timeout`], `None` by default.
+    ///
+    /// See [`deadline`](crate::deadline).
+    fn default_timeout(&self) -> Option<Duration> {
+        None
+    }
+
     /// Execute the query and return the total number of rows affected.
     fn execute<'q,E:'q>(
         &mut self,
@@ -37,14 +45,14 @@ pub trait Executor: Send + Debug + Sized {
     }
 
     /// Execute multiple queries and return the rows affected from each query, in a stream.
-    fn execute_many<'q,E: 'q>(
-        &mut self,
+    fn execute_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         query: E,
-    ) -> ChanStream<<Self::Database as Database>::QueryResult>
+    ) -> ChanStream<'e, <Self::Database as Database>::QueryResult>
         where E: Execute<'q, Self::Database>,
     {
-        let mut s = self.fetch_many(query);
-        s.map(|either| {
+        let s = self.fetch_many(query);
+        s.filter_map(|either| {
             match either {
                 Either::Left(rows) => {
                     Some(rows)
@@ -57,13 +65,13 @@ pub trait Executor: Send + Debug + Sized {
     }
 
     /// Execute the query and return the generated results as a stream.
-    fn fetch<'q, E: 'q>(
-        &mut self,
+    fn fetch<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         query: E,
-    ) -> ChanStream<<Self::Database as Database>::Row> where E: Execute<'q, Self::Database>,
+    ) -> ChanStream<'e, <Self::Database as Database>::Row> where E: Execute<'q, Self::Database>,
     {
-        let mut s = self.fetch_many(query);
-        s.map(|either| {
+        let s = self.fetch_many(query);
+        s.filter_map(|either| {
             match either{
                 Either::Left(rows) => {
                     None
@@ -77,10 +85,10 @@ pub trait Executor: Send + Debug + Sized {
 
     /// Execute multiple queries and return the generated results as a stream
     /// from each query, in a stream.
-    fn fetch_many<'q, E: 'q>(
-        &mut self,
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         query: E,
-    ) -> ChanStream<Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database>;
+    ) -> ChanStream<'e, Either<<Self::Database as Database>::QueryResult, <Self::Database as Database>::Row>> where E: Execute<'q, Self::Database>;
 
     /// Execute the query and return all the generated results, collected into a [`Vec`].
     fn fetch_all< 'q, E: 'q>(
@@ -177,6 +185,12 @@ pub trait Execute<'q, DB: Database>: Send + Sized {
 
     /// Returns `true` if the statement should be cached.
     fn persistent(&self) -> bool;
+
+    /// Returns the timeout of the query, overriding the
+    /// [`default_timeout`](Executor::default_timeout) of the executor.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
 }
 
 // NOTE: `Execute` is explicitly not implemented for String and &String to make it slightly more
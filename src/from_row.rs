@@ -1,5 +1,9 @@
-use crate::error::Error;
+use crate::column::ColumnIndex;
+use crate::decode::Decode;
+use crate::error::{Error, UnexpectedNullError};
 use crate::row::Row;
+use crate::types::Type;
+use crate::value::ValueRef;
 
 /// A record that can be built from a row returned by the database.
 ///
@@ -7,12 +11,13 @@ use crate::row::Row;
 ///
 /// ## Derivable
 ///
-/// This trait can be derived by SQLx for any struct. The generated implementation
-/// will consist of a sequence of calls to [`Row::try_get`] using the name from each
-/// struct field.
+/// This trait can be derived by `#[derive(cdbc::FromRow)]` for any struct. The generated
+/// implementation will consist of a sequence of calls to [`Row::try_get`] using the name from each
+/// struct field, for the rows of every database that can decode the fields. A missing column is
+/// an error, as is a `NULL` in a field that is not an `Option`.
 ///
 /// ```rust,ignore
-/// #[derive(sqlx::FromRow)]
+/// #[derive(cdbc::FromRow)]
 /// struct User {
 ///     id: i32,
 ///     name: String,
@@ -30,11 +35,11 @@ use crate::row::Row;
 /// For example:
 ///
 /// ```rust,ignore
-/// #[derive(sqlx::FromRow)]
+/// #[derive(cdbc::FromRow)]
 /// struct User {
 ///     id: i32,
 ///     name: String,
-///     #[sqlx(rename = "description")]
+///     #[cdbc(rename = "description")]
 ///     about_me: String
 /// }
 /// ```
@@ -52,8 +57,8 @@ use crate::row::Row;
 /// Placed at the struct level, this attribute changes how the field name is mapped to its SQL column name:
 ///
 /// ```rust,ignore
-/// #[derive(sqlx::FromRow)]
-/// #[sqlx(rename_all = "camelCase")]
+/// #[derive(cdbc::FromRow)]
+/// #[cdbc(rename_all = "camelCase")]
 /// struct UserPost {
 ///     id: i32,
 ///     // remapped to "userId"
@@ -74,11 +79,11 @@ use crate::row::Row;
 /// For example:
 ///
 /// ```rust,ignore
-/// #[derive(sqlx::FromRow)]
+/// #[derive(cdbc::FromRow)]
 /// struct User {
 ///     id: i32,
 ///     name: String,
-///     #[sqlx(default)]
+///     #[cdbc(default)]
 ///     location: Option<String>
 /// }
 /// ```
@@ -92,10 +97,68 @@ use crate::row::Row;
 /// will set the value of the field `location` to the default value of `Option<String>`,
 /// which is `None`.
 ///
+/// #### `skip`
+///
+/// The field is not read from the row, it is set to its [`Default`] value.
+///
+/// #### `flatten`
+///
+/// The field is a struct implementing `FromRow` itself, read from the same row:
+///
+/// ```rust,ignore
+/// #[derive(cdbc::FromRow)]
+/// struct Address {
+///     country: String,
+///     city: String,
+/// }
+///
+/// #[derive(cdbc::FromRow)]
+/// struct User {
+///     id: i32,
+///     #[cdbc(flatten)]
+///     address: Address,
+/// }
+/// ```
+///
+/// #### `try_from`
+///
+/// The column is decoded as another type and converted with [`TryFrom`], a failed conversion is
+/// a [`ColumnDecode`](Error::ColumnDecode) error naming the column:
+///
+/// ```rust,ignore
+/// #[derive(cdbc::FromRow)]
+/// struct User {
+///     #[cdbc(try_from = "i64")]
+///     id: u32,
+/// }
+/// ```
+///
+/// ### Tuple structs
+///
+/// The fields of a tuple struct are read by position, `struct Pair(i32, String);` reads the
+/// first and second column.
 pub trait FromRow<'r, R: Row>: Sized {
     fn from_row(row: &'r R) -> Result<Self, Error>;
 }
 
+/// [`Row::try_get`] failing on `NULL` whatever the type decodes it to, used by
+/// `#[derive(FromRow)]` for the fields that are not an `Option`.
+#[doc(hidden)]
+pub fn try_get_not_null<'r, R, T, I>(row: &'r R, index: I) -> Result<T, Error>
+    where
+        R: Row,
+        I: ColumnIndex<R>,
+        T: Decode<'r, R::Database> + Type<R::Database>,
+{
+    if row.try_get_raw(&index)?.is_null() {
+        return Err(Error::ColumnDecode {
+            index: format!("{:?}", index),
+            source: Box::new(UnexpectedNullError),
+        });
+    }
+    row.try_get(index)
+}
+
 // implement FromRow for tuples of types that implement Decode
 // up to tuples of 9 values
 #[macro_export]
@@ -1,10 +1,13 @@
-use std::fmt::{Debug, Display};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::SendError;
+use std::sync::Arc;
 
-use std::sync::mpsc::RecvError;
 use mco::chan;
+use mco::coroutine::{Builder, JoinHandle};
 use mco::std::sync::channel::{Receiver, Sender};
 
-use crate::Error;
 use crate::error::Result;
 
 pub trait Stream {
@@ -16,7 +19,7 @@ pub trait Stream {
 
     fn next(&mut self) -> Option<Self::Item>;
 
-    fn for_each(&mut self, f: fn(a: Self::Item)) where Self: Sized {
+    fn for_each<F>(&mut self, mut f: F) where Self: Sized, F: FnMut(Self::Item) {
         loop {
             if let Some(v) = self.next() {
                 f(v);
@@ -31,7 +34,7 @@ pub trait TryStream: Stream {
     type Ok;
     fn try_next(&mut self) -> crate::error::Result<Option<Self::Ok>>;
 
-    fn try_for_each(&mut self, f: fn(a: Self::Ok)->Result<()>) -> Result<()> where Self: Sized {
+    fn try_for_each<F>(&mut self, mut f: F) -> Result<()> where Self: Sized, F: FnMut(Self::Ok) -> Result<()> {
         loop {
             if let Some(v) = self.try_next()? {
                 f(v)?;
@@ -42,80 +45,241 @@ pub trait TryStream: Stream {
     }
 }
 
+/// The sending half of a [`ChanStream`], given to the producer.
+///
+/// Every [`send`](ChanSender::send) waits until the consumer asks for the next item,
+/// so the producer never runs ahead of the consumer.
+pub struct ChanSender<T> {
+    send: Sender<Option<Result<T>>>,
+    pull: Receiver<()>,
+    closed: Arc<AtomicBool>,
+}
 
-/// Channel Stream
-pub struct ChanStream<T> {
-    pub recv: Receiver<Option<Result<T>>>,
-    pub send: Sender<Option<Result<T>>>,
+impl<T> Clone for ChanSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            send: self.send.clone(),
+            pull: self.pull.clone(),
+            closed: self.closed.clone(),
+        }
+    }
 }
 
+impl<T> ChanSender<T> {
+    /// Send one item to the consumer, then wait until the consumer pulls the next one.
+    ///
+    /// `None` is ignored, the end of the stream is sent once the producer returns.
+    /// Returns a error if the stream was dropped meanwhile, the producer should stop.
+    pub fn send(&self, v: Option<Result<T>>) -> std::result::Result<(), SendError<Option<Result<T>>>> {
+        if v.is_none() {
+            return Ok(());
+        }
+        if self.is_closed() {
+            return Err(SendError(v));
+        }
+        if let Err(e) = self.send.send(v) {
+            self.closed.store(true, Ordering::SeqCst);
+            return Err(e);
+        }
+        self.wait_pull();
+        if self.is_closed() {
+            // delivered, but nobody wants more
+            return Err(SendError(None));
+        }
+        Ok(())
+    }
 
+    /// `true` if the stream was dropped
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
 
-impl<T> Stream for ChanStream<T> {
-    type Item = Result<T>;
+    fn wait_pull(&self) {
+        if self.pull.recv().is_err() {
+            self.closed.store(true, Ordering::SeqCst);
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.recv.recv() {
-            Ok(v) => {
-                match v {
-                    None => { None }
-                    Some(v) => {
-                        Some(v)
-                    }
+    fn end(&self, result: Result<()>) {
+        if let Err(e) = result {
+            let _ = self.send.send(Some(Err(e)));
+        }
+        let _ = self.send.send(None);
+    }
+}
+
+/// the consumer half of the coroutine behind a [`ChanStream`]
+struct Producer<T> {
+    recv: Option<Receiver<Option<Result<T>>>>,
+    pull: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+    done: bool,
+}
+
+impl<T> Producer<T> {
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.done {
+            return None;
+        }
+        if let Some(pull) = &self.pull {
+            let _ = pull.send(());
+        }
+        match self.recv.as_ref()?.recv() {
+            Ok(Some(v)) => Some(v),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(_) => {
+                //the producer panicked
+                self.done = true;
+                self.join();
+                None
+            }
+        }
+    }
+
+    fn join(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            if let Err(e) = handle.join() {
+                if !std::thread::panicking() {
+                    std::panic::resume_unwind(e);
                 }
             }
-            Err(e) => { None }
         }
     }
 }
 
+impl<T> Drop for Producer<T> {
+    fn drop(&mut self) {
+        // wake the producer waiting for a pull, its next send fails and it stops.
+        // the producer may borrow from the caller, so it must be finished before we return
+        self.pull.take();
+        self.recv.take();
+        self.join();
+    }
+}
+
+/// a stream and the boxed value it borrows, see [`ChanStream::owned`]
+struct Owned<'a, T, O> {
+    stream: Option<ChanStream<'a, T>>,
+    owner: *mut O,
+}
+
+unsafe impl<'a, T, O: Send> Send for Owned<'a, T, O> {}
+
+impl<'a, T, O> Drop for Owned<'a, T, O> {
+    fn drop(&mut self) {
+        // the stream must be dropped before the value it borrows
+        self.stream.take();
+        unsafe {
+            drop(Box::from_raw(self.owner));
+        }
+    }
+}
+
+/// Channel Stream
+///
+/// A lazy stream, the producer runs in its own coroutine and produces one item each time the consumer
+/// pulls one(from [`next`](ChanStream::next), [`try_next`](TryStream::try_next) or the [`Iterator`] impl).
+/// Dropping the stream makes the pending `send` of the producer return a error.
+///
+/// Breaking change: the public `recv`/`send` channel fields of the old(eager) stream are gone,
+/// build a stream with [`chan_stream!`] or [`ChanStream::new`] and read it with [`next`](ChanStream::next).
+pub struct ChanStream<'a, T> {
+    inner: Box<dyn FnMut() -> Option<Result<T>> + Send + 'a>,
+}
+
+impl<'a, T> Debug for ChanStream<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChanStream").finish()
+    }
+}
+
+impl<'a, T> Stream for ChanStream<'a, T> {
+    type Item = Result<T>;
 
-impl<T> TryStream for ChanStream<T> {
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.inner)()
+    }
+}
+
+impl<'a, T> TryStream for ChanStream<'a, T> {
     type Ok = T;
 
     fn try_next(&mut self) -> crate::error::Result<Option<Self::Ok>> {
-        return match self.recv.recv() {
-            Ok(v) => {
-                match v {
-                    None => { Ok(None) }
-                    Some(v) => {
-                        if let Ok(v)=v{
-                            Ok(Some(v))
-                        }else{
-                            Err(v.err().unwrap())
-                        }
-                    }
-                }
-            }
-            Err(e) => { Err(e.into()) }
-        };
+        match (self.inner)() {
+            None => Ok(None),
+            Some(v) => v.map(Some),
+        }
     }
-
 }
 
+impl<'a, T> Iterator for ChanStream<'a, T> {
+    type Item = Result<T>;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.inner)()
+    }
+}
 
+/// Build a [`ChanStream`] from a producer block, which uses `r#yield!(v)` to produce a item.
+///
+/// `chan_stream!(on_close: expr; ..)` evaluates `expr` when the stream is dropped before the
+/// producer is done(even before it started), so a producer reading from a connection can
+/// cancel or drain what the server still sends.
 #[macro_export]
 macro_rules! chan_stream {
+    (on_close: $close:expr; $($block:tt)*) => {
+        ChanStream::with_cleanup(move |sender| {
+            macro_rules! r#yield {
+                ($v:expr) => {{
+                    if sender.send(Some(Ok($v))).is_err() {
+                        //the stream was dropped
+                        $close;
+                        return Ok(());
+                    }
+                }}
+            }
+            macro_rules! err_end {
+                ($v:expr) => {{
+                    let _ = sender.send(Some(Err($v)));
+                }}
+            }
+            ///end loop
+            macro_rules! end {
+                () => {{
+                    let _ = sender.send(None);
+                }}
+            }
+
+            if sender.is_closed() {
+                $close;
+                return Ok(());
+            }
+
+            $($block)*
+        })
+    };
     ($($block:tt)*) => {
         ChanStream::new(move |sender| {
             macro_rules! r#yield {
                 ($v:expr) => {{
-                    //mco::std::sync::channel::Sender::send(&sender,Some(Ok($v)));
-                    sender.send(Some(Ok($v)));
+                    if sender.send(Some(Ok($v))).is_err() {
+                        //the stream was dropped
+                        return Ok(());
+                    }
                 }}
             }
             macro_rules! err_end {
                 ($v:expr) => {{
-                    sender.send(Some(Err($v)));
-                    sender.send(None);
+                    let _ = sender.send(Some(Err($v)));
                 }}
             }
             ///end loop
             macro_rules! end {
                 () => {{
-                   //mco::std::sync::channel::Sender::send(&sender,None);
-                     sender.send(None);
+                    let _ = sender.send(None);
                 }}
             }
 
@@ -124,31 +288,108 @@ macro_rules! chan_stream {
     }
 }
 
+impl<'a, T: Send + 'a> ChanStream<'a, T> {
+    /// Start the producer `f` in a new coroutine.
+    /// `f` may borrow from the caller, the stream will not outlive it.
+    pub fn new<F>(f: F) -> Self where F: FnOnce(ChanSender<T>) -> Result<()> + Send + 'a {
+        Self::spawn(f, false)
+    }
+
+    /// Like [`new`](ChanStream::new), but `f` is also called when the stream is dropped before
+    /// the first pull, so it can clean up what it holds. `f` should check
+    /// [`ChanSender::is_closed`] before producing anything.
+    pub fn with_cleanup<F>(f: F) -> Self where F: FnOnce(ChanSender<T>) -> Result<()> + Send + 'a {
+        Self::spawn(f, true)
+    }
 
-impl<T> ChanStream<T> {
-    pub fn new<F>(f: F) -> Self where F: FnOnce(Sender<Option<Result<T>>>)-> Result<()> {
-        let (s, r) = chan!();
-        let result=f(s.clone());
-        //send none, make sure work is done
-        if let Err(e)=result{
-            s.send(Some(Err(e)));
+    fn spawn<F>(f: F, always: bool) -> Self where F: FnOnce(ChanSender<T>) -> Result<()> + Send + 'a {
+        let (s, r) = chan!(2);
+        let (pull_s, pull_r) = chan!();
+        let sender = ChanSender {
+            send: s,
+            pull: pull_r,
+            closed: Arc::new(AtomicBool::new(false)),
+        };
+        let task: Box<dyn FnOnce() + Send + 'a> = Box::new(move || {
+            // start after the first pull
+            sender.wait_pull();
+            let result = if sender.is_closed() && !always {
+                Ok(())
+            } else {
+                f(sender.clone())
+            };
+            // `f` and everything it borrowed is dropped here
+            sender.end(result);
+        });
+        // Safety: the producer only runs while the consumer waits in `next()`, or in `Producer::drop`
+        // which joins it. A leaked stream leaves the producer waiting for a pull forever.
+        let task: Box<dyn FnOnce() + Send + 'static> = unsafe { std::mem::transmute(task) };
+        let handle = Builder::new().spawn(task);
+        let mut producer = Producer {
+            recv: Some(r),
+            pull: Some(pull_s),
+            handle: Some(handle),
+            done: false,
+        };
+        Self {
+            inner: Box::new(move || producer.next()),
         }
-        s.send(None);
+    }
+
+    /// A stream borrowing from `owner`, `owner` is kept(and not moved) until the stream is dropped.
+    ///
+    /// `f` gets a reference for a lifetime `'b` of its own, so the reference can't be kept
+    /// anywhere but in the stream it returns(the [`PhantomData`] only tells that `'a` outlives `'b`).
+    pub fn owned<O, F>(owner: O, f: F) -> Self
+        where O: Send + 'a,
+              F: for<'b> FnOnce(&'b mut O, PhantomData<&'b &'a ()>) -> ChanStream<'b, T> {
+        let owner = Box::into_raw(Box::new(owner));
+        // Safety: `owner` is only freed by `Owned::drop`, after the stream borrowing it
+        let stream = f(unsafe { &mut *owner }, PhantomData);
+        // Safety: the stream lives in `Owned` next to `owner` and is dropped first, so
+        // extending it from `'b` to `'a` never lets it outlive `owner`
+        let stream: ChanStream<'a, T> = unsafe { std::mem::transmute(stream) };
+        let mut owned = Owned {
+            stream: Some(stream),
+            owner,
+        };
         Self {
-            recv: r,
-            send: s,
+            inner: Box::new(move || owned.stream.as_mut().unwrap().next()),
         }
     }
 
+    /// Get the next item, `None` at the end of the stream.
+    #[inline]
+    pub fn next(&mut self) -> Option<Result<T>> {
+        (self.inner)()
+    }
+
+    /// Get the next item, `Ok(None)` at the end of the stream.
+    #[inline]
+    pub fn try_next(&mut self) -> Result<Option<T>> {
+        TryStream::try_next(self)
+    }
+
+    #[inline]
+    pub fn try_for_each<F>(&mut self, f: F) -> Result<()> where F: FnMut(T) -> Result<()> {
+        TryStream::try_for_each(self, f)
+    }
+
+    #[inline]
+    pub fn for_each<F>(mut self, f: F) where F: FnMut(Result<T>) {
+        Stream::for_each(&mut self, f)
+    }
 
-    pub fn collect<A,E>(&mut self, f:fn(T) -> Option<Result<A>>) -> Result<E>
-    where E: Extend<A> + std::default::Default {
-        let mut extend:E = Default::default();
+    /// collect the items mapped by `f`, stop at the first `None`
+    pub fn collect<A, E, F>(mut self, mut f: F) -> Result<E>
+        where E: Extend<A> + std::default::Default,
+              F: FnMut(T) -> Option<Result<A>> {
+        let mut extend: E = Default::default();
         Ok(loop {
             match self.try_next()? {
                 Some(x) => {
-                    match f(x){
-                        None => { break extend;}
+                    match f(x) {
+                        None => { break extend; }
                         Some(v) => {
                             extend.extend(Some(v?));
                         }
@@ -161,37 +402,72 @@ impl<T> ChanStream<T> {
         })
     }
 
-    //try map
-    pub fn map<O>(&mut self,f:fn(<ChanStream<T> as TryStream>::Ok)->Option<O>) -> ChanStream<O> {
-        chan_stream!({
-            loop{
-                if let Some(either)=self.try_next()?{
-                    match f(either){
-                        Some(v)=>{
-                             r#yield!(v);
+    pub fn map<O, F>(mut self, mut f: F) -> ChanStream<'a, O>
+        where F: FnMut(T) -> O + Send + 'a {
+        ChanStream {
+            inner: Box::new(move || {
+                self.next().map(|v| v.map(&mut f))
+            }),
+        }
+    }
+
+    /// map the items with the fallible `f`
+    pub fn try_map<O, F>(mut self, mut f: F) -> ChanStream<'a, O>
+        where F: FnMut(T) -> Result<O> + Send + 'a {
+        ChanStream {
+            inner: Box::new(move || {
+                self.next().map(|v| v.and_then(&mut f))
+            }),
+        }
+    }
+
+    /// skip the items `f` returns `false` for
+    pub fn filter<F>(mut self, mut f: F) -> ChanStream<'a, T>
+        where F: FnMut(&T) -> bool + Send + 'a {
+        ChanStream {
+            inner: Box::new(move || {
+                loop {
+                    match self.next()? {
+                        Ok(v) => {
+                            if f(&v) {
+                                return Some(Ok(v));
+                            }
                         }
-                        None =>{
-                             end!();
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+            }),
+        }
+    }
+
+    /// map the items with `f`, skip the items `f` returns `None` for
+    pub fn filter_map<O, F>(mut self, mut f: F) -> ChanStream<'a, O>
+        where F: FnMut(T) -> Option<O> + Send + 'a {
+        ChanStream {
+            inner: Box::new(move || {
+                loop {
+                    match self.next()? {
+                        Ok(v) => {
+                            if let Some(v) = f(v) {
+                                return Some(Ok(v));
+                            }
                         }
+                        Err(e) => return Some(Err(e)),
                     }
-                }else {
-                    break Ok(());
                 }
-            }
-        })
+            }),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::thread::sleep;
-    use std::time::Duration;
-    use mco::co;
-    use crate::io::chan_stream::{ChanStream, Stream, TryStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::io::chan_stream::{ChanStream, TryStream};
 
     #[test]
     fn test_chan_stream() {
-        let mut s = chan_stream!({
+        let s = chan_stream!({
               println!("start");
               r#yield!(1);
             Ok(())
@@ -204,7 +480,7 @@ mod test {
 
     #[test]
     fn test_for_each() {
-        let mut s = chan_stream!({
+        let s = chan_stream!({
              r#yield!(1);
              r#yield!(2);
              r#yield!(3);
@@ -214,4 +490,93 @@ mod test {
             println!("{:?}", v);
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_lazy() {
+        let counter = AtomicUsize::new(0);
+        let produced = &counter;
+        let mut s = chan_stream!({
+            for i in 0..1000 {
+                produced.fetch_add(1, Ordering::SeqCst);
+                r#yield!(i);
+            }
+            Ok(())
+        });
+        assert_eq!(produced.load(Ordering::SeqCst), 0);
+        assert_eq!(s.try_next().unwrap(), Some(0));
+        assert_eq!(s.try_next().unwrap(), Some(1));
+        assert_eq!(produced.load(Ordering::SeqCst), 2);
+        drop(s);
+        assert_eq!(produced.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_owned() {
+        let s = ChanStream::owned(vec![1, 2, 3], |v, _| chan_stream!({
+            for i in v.iter() {
+                r#yield!(*i);
+            }
+            Ok(())
+        }));
+        let v: Vec<i32> = s.map(|v| v * 2).collect(|v| Some(Ok(v))).unwrap();
+        assert_eq!(v, vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn test_on_close() {
+        let counter = AtomicUsize::new(0);
+        let closed = &counter;
+        let stream = || chan_stream!(on_close: closed.fetch_add(1, Ordering::SeqCst); {
+            for i in 0..3 {
+                r#yield!(i);
+            }
+            Ok(())
+        });
+        // consumed to the end
+        assert_eq!(stream().count(), 3);
+        assert_eq!(closed.load(Ordering::SeqCst), 0);
+        // dropped half way
+        let mut s = stream();
+        assert_eq!(s.try_next().unwrap(), Some(0));
+        drop(s);
+        assert_eq!(closed.load(Ordering::SeqCst), 1);
+        // dropped before the first pull
+        drop(stream());
+        assert_eq!(closed.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_closures() {
+        let offset = 10;
+        let s = chan_stream!({
+            for i in 0..10 {
+                r#yield!(i);
+            }
+            Ok(())
+        });
+        let v: Vec<i32> = s.filter(|v| v % 2 == 0)
+            .map(|v| v + offset)
+            .collect(|v| Some(Ok(v)))
+            .unwrap();
+        assert_eq!(v, vec![10, 12, 14, 16, 18]);
+
+        let s = chan_stream!({
+            r#yield!(1);
+            r#yield!(2);
+            Err(crate::Error::PoolClosed)
+        });
+        let v: crate::Result<Vec<i32>> = s.collect(|v| Some(Ok(v)));
+        assert!(v.is_err());
+
+        let s = chan_stream!({
+            r#yield!(1);
+            r#yield!(2);
+            Ok(())
+        });
+        let mut sum = 0;
+        for v in s {
+            sum += v.unwrap();
+        }
+        assert_eq!(sum, 3);
+    }
+}
@@ -2,7 +2,12 @@
 #[macro_use]
 pub extern crate cdbc_macro;
 
-pub use cdbc_macro::{Scan,ScanSqlite,ScanMysql,ScanMssql,ScanPg,crud};
+pub use cdbc_macro::{Scan,ScanSqlite,ScanMysql,ScanMssql,ScanPg,FromRow,crud,migrate,cdbc_test as test};
+
+/// compile-time checked `query!`,`query_as!`,`query_scalar!`
+pub mod checked {
+    pub use cdbc_macro::{query, query_as, query_scalar};
+}
 
 #[macro_use]
 pub mod error;
@@ -40,15 +45,23 @@ pub mod query;
 pub mod query_as;
 #[macro_use]
 pub mod query_scalar;
+pub mod query_builder;
+pub mod query_named;
 pub mod executor;
 pub mod describe;
 pub mod net;
+pub mod deadline;
+pub mod migrate;
+pub mod testing;
+pub mod replay;
 pub use ahash::AHashMap as HashMap;
 
 pub use error::*;
 pub use query::*;
 pub use query_as::*;
 pub use query_scalar::*;
+pub use query_builder::QueryBuilder;
+pub use query_named::{query_named, NamedQuery};
 pub use decode::*;
 pub use encode::*;
 pub use column::*;
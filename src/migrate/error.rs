@@ -0,0 +1,29 @@
+use crate::error::{BoxDynError, Error};
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum MigrateError {
+    #[error("while executing migrations: {0}")]
+    Execute(#[from] Error),
+
+    #[error("while resolving migrations: {0}")]
+    Source(#[source] BoxDynError),
+
+    #[error("invalid migration file name {0:?}, expected `<VERSION>_<DESCRIPTION>.sql` or `<VERSION>_<DESCRIPTION>.down.sql`")]
+    InvalidFileName(String),
+
+    #[error("migration {0} is defined more than once")]
+    DuplicateVersion(i64),
+
+    #[error("migration {0} was previously applied but is missing in the resolved migrations")]
+    VersionMissing(i64),
+
+    #[error("migration {0} was previously applied but has been modified")]
+    VersionMismatch(i64),
+
+    #[error("migration {0} has no `.down.sql` and cannot be reverted")]
+    Irreversible(i64),
+
+    #[error("migration {0} is partially applied; fix and remove row from `_cdbc_migrations` table")]
+    Dirty(i64),
+}
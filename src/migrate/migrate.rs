@@ -0,0 +1,87 @@
+use std::time::Duration;
+use crate::database::Database;
+use crate::error::Error;
+use crate::migrate::{AppliedMigration, MigrateError, Migration};
+use crate::pool::PoolConnection;
+
+/// Create, check and drop whole databases, implemented by the [`Database`] of each driver.
+///
+/// postgres, mysql and mssql connect to a maintenance database of the same server to do it,
+/// sqlite creates and deletes the database file.
+///
+/// ```rust,ignore
+/// Postgres::create_database("postgres://postgres@localhost/test")?;
+/// ```
+pub trait MigrateDatabase {
+    /// create the database of `url`
+    fn create_database(url: &str) -> Result<(), Error>;
+
+    /// `true` if the database of `url` exists
+    fn database_exists(url: &str) -> Result<bool, Error>;
+
+    /// drop the database of `url` if it exists
+    fn drop_database(url: &str) -> Result<(), Error>;
+
+    /// drop the database of `url` if it exists, terminating the other sessions connected to it.
+    /// only supported by postgres.
+    fn force_drop_database(url: &str) -> Result<(), Error> {
+        let _ = url;
+        Err(Error::Configuration("force_drop_database is not supported by this database".into()))
+    }
+}
+
+/// Implemented by the connection of each driver, used by [`Migrator`](crate::migrate::Migrator).
+///
+/// Applied migrations are recorded in the `_cdbc_migrations` table.
+pub trait Migrate {
+    /// create the `_cdbc_migrations` table if it does not exist
+    fn ensure_migrations_table(&mut self) -> Result<(), MigrateError>;
+
+    /// the lowest version which was started but not finished
+    fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError>;
+
+    /// all recorded migrations, ordered by version
+    fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError>;
+
+    /// take a database level lock, blocks until no one else is migrating this database
+    fn lock(&mut self) -> Result<(), MigrateError>;
+
+    fn unlock(&mut self) -> Result<(), MigrateError>;
+
+    /// run `migration.sql` and record it
+    fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError>;
+
+    /// run `migration.down_sql` and delete its record
+    fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError>;
+}
+
+impl<DB: Database> Migrate for PoolConnection<DB>
+    where DB::Connection: Migrate {
+    fn ensure_migrations_table(&mut self) -> Result<(), MigrateError> {
+        (**self).ensure_migrations_table()
+    }
+
+    fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError> {
+        (**self).dirty_version()
+    }
+
+    fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError> {
+        (**self).list_applied_migrations()
+    }
+
+    fn lock(&mut self) -> Result<(), MigrateError> {
+        (**self).lock()
+    }
+
+    fn unlock(&mut self) -> Result<(), MigrateError> {
+        (**self).unlock()
+    }
+
+    fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        (**self).apply(migration)
+    }
+
+    fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+        (**self).revert(migration)
+    }
+}
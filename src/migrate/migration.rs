@@ -0,0 +1,72 @@
+use std::borrow::Cow;
+use sha2::{Digest, Sha384};
+use crate::migrate::MigrateError;
+
+/// One versioned migration, the `up` sql and the optional `down` sql used by [`Migrator::undo`].
+///
+/// [`Migrator::undo`]: crate::migrate::Migrator::undo
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub version: i64,
+    pub description: Cow<'static, str>,
+    pub sql: Cow<'static, str>,
+    pub down_sql: Option<Cow<'static, str>>,
+    /// hex encoded SHA-384 of `sql`, recorded in `_cdbc_migrations` to detect modified migrations
+    pub checksum: Cow<'static, str>,
+}
+
+impl Migration {
+    pub fn new(
+        version: i64,
+        description: Cow<'static, str>,
+        sql: Cow<'static, str>,
+        down_sql: Option<Cow<'static, str>>,
+    ) -> Self {
+        let checksum = checksum(&sql).into();
+        Migration {
+            version,
+            description,
+            sql,
+            down_sql,
+            checksum,
+        }
+    }
+}
+
+/// A migration recorded in `_cdbc_migrations`.
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub checksum: Cow<'static, str>,
+}
+
+/// hex encoded SHA-384 of the sql
+pub fn checksum(sql: &str) -> String {
+    let mut hex = String::with_capacity(96);
+    for b in Sha384::digest(sql.as_bytes()).iter() {
+        hex.push_str(&format!("{:02x}", b));
+    }
+    hex
+}
+
+/// Parse a migration file name: `<VERSION>_<DESCRIPTION>.sql` (or `.up.sql`) is the up migration,
+/// `<VERSION>_<DESCRIPTION>.down.sql` is the down migration of the same version.
+/// returns `(version, description, is_down)`.
+pub fn parse_file_name(file_name: &str) -> Result<(i64, String, bool), MigrateError> {
+    let invalid = || MigrateError::InvalidFileName(file_name.to_string());
+    let stem = file_name.strip_suffix(".sql").ok_or_else(invalid)?;
+    let (stem, down) = if let Some(stem) = stem.strip_suffix(".down") {
+        (stem, true)
+    } else {
+        (stem.strip_suffix(".up").unwrap_or(stem), false)
+    };
+    let (version, description) = match stem.split_once('_') {
+        Some((version, description)) => (version, description),
+        None => (stem, ""),
+    };
+    let version: i64 = version.parse().map_err(|_| invalid())?;
+    if version < 0 {
+        return Err(invalid());
+    }
+    Ok((version, description.replace('_', " "), down))
+}
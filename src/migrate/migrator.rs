@@ -0,0 +1,254 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::slice;
+use crate::migrate::{parse_file_name, AppliedMigration, Migrate, MigrateError, Migration};
+
+/// Runs versioned migrations against a connection.
+///
+/// migrations are `<VERSION>_<DESCRIPTION>.sql` files, with an optional `<VERSION>_<DESCRIPTION>.down.sql`
+/// to revert them. load them at runtime with [`Migrator::new`] or embed them with [`migrate!`](crate::migrate!).
+///
+/// ```rust,ignore
+/// let migrator = Migrator::new("./migrations")?;
+/// migrator.run(&mut conn)?;
+/// // or with a pool
+/// migrator.run(&mut pool.acquire()?)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    pub migrations: Vec<Migration>,
+    /// take a database level lock while migrating, default true
+    pub locking: bool,
+}
+
+impl Migrator {
+    /// load the migrations from a directory, files not ending with `.sql` are ignored.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self, MigrateError> {
+        let dir = dir.as_ref();
+        let entries = fs::read_dir(dir)
+            .map_err(|e| MigrateError::Source(format!("read {}: {}", dir.display(), e).into()))?;
+        let mut files = vec![];
+        for entry in entries {
+            let entry = entry.map_err(|e| MigrateError::Source(Box::new(e)))?;
+            if !entry.file_type().map_err(|e| MigrateError::Source(Box::new(e)))?.is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.ends_with(".sql") {
+                continue;
+            }
+            let sql = fs::read_to_string(entry.path())
+                .map_err(|e| MigrateError::Source(format!("read {}: {}", entry.path().display(), e).into()))?;
+            files.push((name, Cow::Owned(sql)));
+        }
+        Self::resolve(files)
+    }
+
+    /// build from `(file name, sql)` pairs, used by [`migrate!`](crate::migrate!) to embed migrations.
+    pub fn from_files(files: &[(&str, &'static str)]) -> Result<Self, MigrateError> {
+        Self::resolve(files.iter().map(|(name, sql)| (name.to_string(), Cow::Borrowed(*sql))).collect())
+    }
+
+    fn resolve(files: Vec<(String, Cow<'static, str>)>) -> Result<Self, MigrateError> {
+        let mut ups = BTreeMap::new();
+        let mut downs = BTreeMap::new();
+        for (name, sql) in files {
+            let (version, description, down) = parse_file_name(&name)?;
+            let map = if down { &mut downs } else { &mut ups };
+            if map.insert(version, (description, sql)).is_some() {
+                return Err(MigrateError::DuplicateVersion(version));
+            }
+        }
+        if let Some(version) = downs.keys().find(|v| !ups.contains_key(v)) {
+            return Err(MigrateError::Source(format!("migration {} has a `.down.sql` but no `.sql`", version).into()));
+        }
+        let migrations = ups
+            .into_iter()
+            .map(|(version, (description, sql))| {
+                let down_sql = downs.remove(&version).map(|(_, sql)| sql);
+                Migration::new(version, description.into(), sql, down_sql)
+            })
+            .collect();
+        Ok(Migrator {
+            migrations,
+            locking: true,
+        })
+    }
+
+    pub fn set_locking(&mut self, locking: bool) -> &mut Self {
+        self.locking = locking;
+        self
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Migration> {
+        self.migrations.iter()
+    }
+
+    /// apply all pending migrations in version order.
+    ///
+    /// fails with [`MigrateError::Dirty`] if a previous run did not finish, and with
+    /// [`MigrateError::VersionMismatch`] / [`MigrateError::VersionMissing`] if an applied
+    /// migration was modified or removed.
+    pub fn run<C: Migrate + ?Sized>(&self, conn: &mut C) -> Result<(), MigrateError> {
+        self.locked(conn, |conn| {
+            let applied = self.check_applied(conn)?;
+            for migration in self.iter() {
+                if applied.iter().any(|m| m.version == migration.version) {
+                    continue;
+                }
+                let elapsed = conn.apply(migration)?;
+                log::info!("applied migration {}/{} ({:?})", migration.version, migration.description, elapsed);
+            }
+            Ok(())
+        })
+    }
+
+    /// revert the applied migrations newer than `target`, newest first.
+    /// `undo(conn, 0)` reverts everything.
+    pub fn undo<C: Migrate + ?Sized>(&self, conn: &mut C, target: i64) -> Result<(), MigrateError> {
+        self.locked(conn, |conn| {
+            let applied = self.check_applied(conn)?;
+            for migration in self.iter().rev() {
+                if migration.version <= target || !applied.iter().any(|m| m.version == migration.version) {
+                    continue;
+                }
+                if migration.down_sql.is_none() {
+                    return Err(MigrateError::Irreversible(migration.version));
+                }
+                let elapsed = conn.revert(migration)?;
+                log::info!("reverted migration {}/{} ({:?})", migration.version, migration.description, elapsed);
+            }
+            Ok(())
+        })
+    }
+
+    fn locked<C, F>(&self, conn: &mut C, f: F) -> Result<(), MigrateError>
+        where C: Migrate + ?Sized,
+              F: FnOnce(&mut C) -> Result<(), MigrateError> {
+        if self.locking {
+            conn.lock()?;
+        }
+        let result = conn.ensure_migrations_table().and_then(|_| f(conn));
+        if self.locking {
+            let unlocked = conn.unlock();
+            result?;
+            unlocked?;
+            Ok(())
+        } else {
+            result
+        }
+    }
+
+    /// checks that no migration is dirty and every applied migration is unchanged
+    fn check_applied<C: Migrate + ?Sized>(&self, conn: &mut C) -> Result<Vec<AppliedMigration>, MigrateError> {
+        if let Some(version) = conn.dirty_version()? {
+            return Err(MigrateError::Dirty(version));
+        }
+        let applied = conn.list_applied_migrations()?;
+        for m in &applied {
+            match self.iter().find(|migration| migration.version == m.version) {
+                None => return Err(MigrateError::VersionMissing(m.version)),
+                Some(migration) if migration.checksum != m.checksum => {
+                    return Err(MigrateError::VersionMismatch(m.version));
+                }
+                _ => {}
+            }
+        }
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use crate::migrate::{parse_file_name, AppliedMigration, Migrate, MigrateError, Migration, Migrator};
+
+    #[derive(Default)]
+    struct Memory {
+        applied: Vec<AppliedMigration>,
+        dirty: Option<i64>,
+        locked: bool,
+    }
+
+    impl Migrate for Memory {
+        fn ensure_migrations_table(&mut self) -> Result<(), MigrateError> {
+            Ok(())
+        }
+
+        fn dirty_version(&mut self) -> Result<Option<i64>, MigrateError> {
+            Ok(self.dirty)
+        }
+
+        fn list_applied_migrations(&mut self) -> Result<Vec<AppliedMigration>, MigrateError> {
+            Ok(self.applied.clone())
+        }
+
+        fn lock(&mut self) -> Result<(), MigrateError> {
+            assert!(!self.locked);
+            self.locked = true;
+            Ok(())
+        }
+
+        fn unlock(&mut self) -> Result<(), MigrateError> {
+            self.locked = false;
+            Ok(())
+        }
+
+        fn apply(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+            assert!(self.locked);
+            self.applied.push(AppliedMigration {
+                version: migration.version,
+                checksum: migration.checksum.clone(),
+            });
+            Ok(Duration::ZERO)
+        }
+
+        fn revert(&mut self, migration: &Migration) -> Result<Duration, MigrateError> {
+            self.applied.retain(|m| m.version != migration.version);
+            Ok(Duration::ZERO)
+        }
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        assert_eq!(parse_file_name("1_init.sql").unwrap(), (1, "init".to_string(), false));
+        assert_eq!(parse_file_name("20220101_add_users.up.sql").unwrap(), (20220101, "add users".to_string(), false));
+        assert_eq!(parse_file_name("2_add_users.down.sql").unwrap(), (2, "add users".to_string(), true));
+        assert!(parse_file_name("init.sql").is_err());
+        assert!(parse_file_name("1_init.txt").is_err());
+    }
+
+    #[test]
+    fn test_migrator() {
+        let migrator = Migrator::from_files(&[
+            ("2_users.sql", "create table users(id int)"),
+            ("1_init.sql", "create table init(id int)"),
+            ("2_users.down.sql", "drop table users"),
+        ]).unwrap();
+        assert_eq!(migrator.iter().map(|m| m.version).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(migrator.migrations[0].down_sql.is_none());
+        assert!(matches!(Migrator::from_files(&[("1_a.sql", ""), ("1_b.sql", "")]), Err(MigrateError::DuplicateVersion(1))));
+
+        let mut conn = Memory::default();
+        migrator.run(&mut conn).unwrap();
+        assert_eq!(conn.applied.len(), 2);
+        assert!(!conn.locked);
+        // running again applies nothing
+        migrator.run(&mut conn).unwrap();
+        assert_eq!(conn.applied.len(), 2);
+
+        migrator.undo(&mut conn, 1).unwrap();
+        assert_eq!(conn.applied.len(), 1);
+        assert!(matches!(migrator.undo(&mut conn, 0), Err(MigrateError::Irreversible(1))));
+        assert!(!conn.locked);
+
+        conn.applied[0].checksum = "changed".into();
+        assert!(matches!(migrator.run(&mut conn), Err(MigrateError::VersionMismatch(1))));
+        conn.applied[0].version = 3;
+        assert!(matches!(migrator.run(&mut conn), Err(MigrateError::VersionMissing(3))));
+        conn.dirty = Some(3);
+        assert!(matches!(migrator.run(&mut conn), Err(MigrateError::Dirty(3))));
+    }
+}
@@ -0,0 +1,11 @@
+//! Versioned sql migrations, see [`Migrator`], and creating or dropping databases, see [`MigrateDatabase`].
+
+mod error;
+mod migrate;
+mod migration;
+mod migrator;
+
+pub use error::MigrateError;
+pub use migrate::{Migrate, MigrateDatabase};
+pub use migration::{checksum, parse_file_name, AppliedMigration, Migration};
+pub use migrator::Migrator;
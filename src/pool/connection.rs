@@ -113,7 +113,11 @@ impl<DB: Database> PoolConnection<DB> {
             // returned to the pool; also of course, if it was dropped due to an error
             // this is simply a band-aid as SQLx-next (0.6) connections should be able
             // to recover from cancellations
-            if let Err(e) = floating.raw.ping() {
+            if floating.raw.is_broken() {
+                log::warn!("connection is broken, closing it instead of returning it to the pool");
+
+                drop(floating);
+            } else if let Err(e) = floating.raw.ping() {
                 log::warn!(
                     "error occurred while testing the connection on-release: {}",
                     e
@@ -189,13 +193,15 @@ impl<'s, DB: Database> Floating<'s, Live<DB>> {
     }
 
     pub fn attach(self, pool: &Arc<SharedPool<DB>>) -> PoolConnection<DB> {
-        let Floating { inner, guard } = self;
+        let Floating { mut inner, guard } = self;
 
         debug_assert!(
             guard.same_pool(pool),
             "BUG: attaching connection to different pool"
         );
 
+        inner.raw.set_default_timeout(pool.options.query_timeout);
+
         guard.cancel();
         PoolConnection {
             live: Some(inner),
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use either::Either;
 
 use crate::database::{Database, HasStatement};
@@ -6,8 +8,6 @@ use crate::error::Error;
 use crate::executor::{Execute, Executor};
 use crate::io::chan_stream::ChanStream;
 use crate::pool::{Pool, PoolConnection};
-use crate::{chan_stream};
-use crate::io::chan_stream::TryStream;
 
 impl<DB: Database> Executor for &'_ Pool<DB>
 where
@@ -15,25 +15,24 @@ where
 {
     type Database = DB;
 
-    fn fetch_many<'q, E: 'q>(
-        &mut self,
+    fn default_timeout(&self) -> Option<Duration> {
+        self.0.options.query_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         query: E,
-    ) -> ChanStream<Either<DB::QueryResult, DB::Row>>
+    ) -> ChanStream<'e, Either<DB::QueryResult, DB::Row>>
     where
         E: Execute<'q, Self::Database>,
     {
-        let pool = self.clone();
-
-        chan_stream! {
-            let mut conn = pool.acquire()?;
-            let mut s = conn.fetch_many(query);
-
-            while let Some(v) = s.try_next()? {
-                r#yield!(v);
-            }
-
-            Ok(())
-        }
+        let conn = match self.acquire() {
+            Ok(conn) => conn,
+            Err(e) => return ChanStream::new(|_| Err(e)),
+        };
+        ChanStream::owned(conn, move |conn, _| {
+            ChanStream::owned(&mut **conn, move |conn, _| conn.fetch_many(query))
+        })
     }
 
     fn fetch_optional<'q, E: 'q>(
@@ -77,25 +76,24 @@ impl<DB: Database> Executor for Pool<DB>
 {
     type Database = DB;
 
-    fn fetch_many<'q, E: 'q>(
-        &mut self,
+    fn default_timeout(&self) -> Option<Duration> {
+        self.0.options.query_timeout
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
         query: E,
-    ) -> ChanStream<Either<DB::QueryResult, DB::Row>>
+    ) -> ChanStream<'e, Either<DB::QueryResult, DB::Row>>
         where
             E: Execute<'q, Self::Database>,
     {
-        let pool = self.clone();
-
-        chan_stream! {
-            let mut conn = pool.acquire()?;
-            let mut s = conn.fetch_many(query);
-
-            while let Some(v) = s.try_next()? {
-                r#yield!(v);
-            }
-
-            Ok(())
-        }
+        let conn = match self.acquire() {
+            Ok(conn) => conn,
+            Err(e) => return ChanStream::new(|_| Err(e)),
+        };
+        ChanStream::owned(conn, move |conn, _| {
+            ChanStream::owned(&mut **conn, move |conn, _| conn.fetch_many(query))
+        })
     }
 
     fn fetch_optional<'q, E: 'q>(
@@ -2,6 +2,7 @@ use super::connection::{Floating, Idle, Live};
 use crate::connection::ConnectOptions;
 use crate::connection::Connection;
 use crate::database::Database;
+use crate::deadline;
 use crate::error::Error;
 use crate::pool::{deadline_as_timeout, PoolOptions};
 use crossbeam_queue::ArrayQueue;
@@ -159,25 +160,29 @@ impl<DB: Database> SharedPool<DB> {
     }
 
     #[allow(clippy::needless_lifetimes)]
-    pub(super) fn acquire<'s>(&'s self) -> Result<Floating<'s, Live<DB>>, Error> {
+    pub(super) fn acquire<'s>(&'s self, timeout: Duration) -> Result<Floating<'s, Live<DB>>, Error> {
         if self.is_closed() {
             return Err(Error::PoolClosed);
         }
 
-        let deadline = Instant::now() + self.options.connect_timeout;
+        let mut deadline = Instant::now() + timeout;
 
-        // sqlx_rt::timeout(
-        //     self.options.connect_timeout,
-        //     async {
-        //
-        //     }
-        // )
-        //     .map_err(|_| Error::PoolTimedOut)?
+        // the deadline of the coroutine bounds waiting for a connection too
+        if let Some(coroutine_deadline) = deadline::deadline() {
+            deadline = deadline.min(coroutine_deadline);
+            deadline_as_timeout::<DB>(deadline)?;
+        }
 
         loop {
-            let permit = self.semaphore.acquire();
+            // waiters are served in the order they came
+            let permit = self
+                .semaphore
+                .acquire_until(Some(deadline))
+                .ok_or(Error::PoolTimedOut)?;
 
             if self.is_closed() {
+                // pass the permit on, to wake the next waiter
+                permit.release();
                 return Err(Error::PoolClosed);
             }
 
@@ -194,17 +199,20 @@ impl<DB: Database> SharedPool<DB> {
                     // we get the `DecrementSizeGuard` back to open a new one
                     Err(guard) => guard,
                 },
-                Err(permit) => if let Ok(guard) = self.try_increment_size(permit) {
+                Err(permit) => match self.try_increment_size(permit) {
                     // we can open a new connection
-                    guard
-                } else {
-                    log::debug!("woke but was unable to acquire idle connection or open new one; retrying");
-                    continue;
+                    Ok(guard) => guard,
+                    Err(permit) => {
+                        log::debug!("woke but was unable to acquire idle connection or open new one; retrying");
+                        permit.release();
+                        continue;
+                    }
                 }
             };
 
-            // Attempt to connect...
-            return self.connection(deadline, guard);
+            // Attempt to connect, opening the connection is bounded by `connect_timeout` too
+            let connect_deadline = Instant::now() + self.options.connect_timeout;
+            return self.connection(deadline.min(connect_deadline), guard);
         }
     }
 
@@ -385,10 +393,11 @@ impl<'a> DecrementSizeGuard<'a> {
 
     pub fn from_permit<DB: Database>(
         pool: &'a SharedPool<DB>,
-        mut permit: PermitGuard<'a>,
+        permit: PermitGuard<'a>,
     ) -> Self {
-        // here we effectively take ownership of the permit
-        permit.release();
+        // here we effectively take ownership of the permit,
+        // it is released when this guard is dropped (or by `release_permit`)
+        let _ = permit;
         Self::new_permit(pool)
     }
 
@@ -58,7 +58,7 @@ use self::inner::SharedPool;
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
-use crate::transaction::Transaction;
+use crate::transaction::{RetryPolicy, Transaction, TransactionOptions};
 use std::fmt;
 
 use std::sync::Arc;
@@ -259,10 +259,18 @@ impl<DB: Database> Pool<DB> {
 
     /// Retrieves a connection from the pool.
     ///
-    /// Waits for at most the configured connection timeout before returning an error.
+    /// Waits for at most the configured [`acquire_timeout`] before returning [`Error::PoolTimedOut`].
+    ///
+    /// [`acquire_timeout`]: PoolOptions::acquire_timeout
     pub fn acquire(&self) ->  Result<PoolConnection<DB>, Error> {
+        self.acquire_with_timeout(self.0.options.acquire_timeout)
+    }
+
+    /// Retrieves a connection from the pool, waiting for at most `timeout`
+    /// instead of the configured acquire timeout.
+    pub fn acquire_with_timeout(&self, timeout: Duration) -> Result<PoolConnection<DB>, Error> {
         let shared = self.0.clone();
-        shared.acquire().map(|conn| conn.attach(&shared))
+        shared.acquire(timeout).map(|conn| conn.attach(&shared))
     }
 
     /// Attempts to retrieve a connection from the pool if there is one available.
@@ -279,6 +287,22 @@ impl<DB: Database> Pool<DB> {
         Ok(Transaction::begin(MaybePoolConnection::PoolConnection(self.acquire()?))?)
     }
 
+    /// Retrieves a new connection and immediately begins a new transaction with the given options.
+    pub fn begin_with(&self, options: TransactionOptions) -> Result<Transaction<'static, DB>, Error> {
+        Transaction::begin_with(MaybePoolConnection::PoolConnection(self.acquire()?), options)
+    }
+
+    /// Retrieves a connection and runs the function inside a transaction on it, again
+    /// if it fails with a retryable error, see [`Connection::transaction_with_retry`].
+    pub fn transaction_with_retry<F, R>(&self, policy: &RetryPolicy, callback: F) -> Result<R, Error>
+        where
+                for<'c> F: FnMut(&'c mut Transaction<'_, DB>) -> Result<R, Error> + Send,
+                R: Send,
+    {
+        let mut conn = self.acquire()?;
+        conn.transaction_with_retry(policy, callback)
+    }
+
     /// Attempts to retrieve a new connection and immediately begins a new transaction if there
     /// is one available.
     pub fn try_begin(&self) -> Result<Option<Transaction<'static, DB>>, Error> {
@@ -28,9 +28,11 @@ pub struct PoolOptions<DB: Database> {
         Option<Box<dyn Fn(&mut DB::Connection) -> bool + 'static + Send + Sync>>,
     pub max_connections: u32,
     pub connect_timeout: Duration,
+    pub acquire_timeout: Duration,
     pub min_connections: u32,
     pub max_lifetime: Option<Duration>,
     pub idle_timeout: Option<Duration>,
+    pub query_timeout: Option<Duration>,
 }
 
 impl<DB: Database> Default for PoolOptions<DB> {
@@ -49,8 +51,10 @@ impl<DB: Database> PoolOptions<DB> {
             max_connections: 10,
             min_connections: 0,
             connect_timeout: Duration::from_secs(30),
+            acquire_timeout: Duration::from_secs(30),
             idle_timeout: Some(Duration::from_secs(10 * 60)),
             max_lifetime: Some(Duration::from_secs(30 * 60)),
+            query_timeout: None,
         }
     }
 
@@ -62,12 +66,23 @@ impl<DB: Database> PoolOptions<DB> {
 
     /// Set the amount of time to attempt connecting to the database.
     ///
-    /// If this timeout elapses, [`Pool::acquire`] will return an error.
+    /// If this timeout elapses while opening a new connection, [`Pool::acquire`] will return an error.
     pub fn connect_timeout(mut self, timeout: Duration) -> Self {
         self.connect_timeout = timeout;
         self
     }
 
+    /// Set the maximum amount of time to spend waiting for a connection in [`Pool::acquire`].
+    ///
+    /// Callers waiting for a connection are served in the order they came. If this timeout
+    /// elapses, [`Pool::acquire`] will return [`Error::PoolTimedOut`].
+    ///
+    /// Use [`Pool::acquire_with_timeout`] to override it for a single call.
+    pub fn acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.acquire_timeout = timeout;
+        self
+    }
+
     /// Set the minimum number of connections to maintain at all times.
     ///
     /// When the pool is built, this many connections will be automatically spun up.
@@ -111,6 +126,16 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// Set the [`default_timeout`](crate::executor::Executor::default_timeout) of the queries
+    /// run on the pool and its connections.
+    ///
+    /// A query running longer than this is cancelled on the server and fails with
+    /// [`Error::QueryTimedOut`].
+    pub fn query_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.query_timeout = timeout.into();
+        self
+    }
+
     /// If true, the health of a connection will be verified by a call to [`Connection::ping`]
     /// before returning the connection.
     ///
@@ -212,10 +237,13 @@ fn init_min_connections<DB: Database>(pool: &SharedPool<DB>) -> Result<(), Error
         let permit = pool.semaphore.acquire();
 
         // this guard will prevent us from exceeding `max_size`
-        if let Ok(guard) = pool.try_increment_size(permit) {
-            // [connect] will raise an error when past deadline
-            let conn = pool.connection(deadline, guard)?;
-            pool.release(conn);
+        match pool.try_increment_size(permit) {
+            Ok(guard) => {
+                // [connect] will raise an error when past deadline
+                let conn = pool.connection(deadline, guard)?;
+                pool.release(conn);
+            }
+            Err(permit) => permit.release(),
         }
     }
 
@@ -228,6 +256,7 @@ impl<DB: Database> Debug for PoolOptions<DB> {
             .field("max_connections", &self.max_connections)
             .field("min_connections", &self.min_connections)
             .field("connect_timeout", &self.connect_timeout)
+            .field("acquire_timeout", &self.acquire_timeout)
             .field("max_lifetime", &self.max_lifetime)
             .field("idle_timeout", &self.idle_timeout)
             .field("test_before_acquire", &self.test_before_acquire)
@@ -1,14 +1,12 @@
-use std::sync::Arc;
-use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
-use mco::std::queue::seg_queue::SegQueue;
-use mco::std::sync::{Blocker, Semphore};
-use crate::Error;
-use crate::error::Result;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use mco::std::sync::Blocker;
 
 /// permit guard
 pub struct PermitGuard<'a> {
     inner: &'a BoxSemaphore,
-    blocker: Arc<mco::std::sync::Blocker>,
 }
 
 impl <'a>PermitGuard<'a>{
@@ -17,77 +15,121 @@ impl <'a>PermitGuard<'a>{
     }
 }
 
+/// A counting semaphore, waiters are served in FIFO order.
 pub struct BoxSemaphore {
     /// permit total num
-    total: i64,
+    total: usize,
+    state: Mutex<State>,
+}
+
+struct State {
     ///permit
-    permit: AtomicI64,
+    permits: usize,
     ///wait queue
-    waiters: SegQueue<Arc<mco::std::sync::Blocker>>,
+    waiters: VecDeque<Arc<Waiter>>,
+}
+
+struct Waiter {
+    blocker: Blocker,
+    /// set when a released permit was handed over to this waiter
+    granted: AtomicBool,
 }
 
 impl BoxSemaphore {
     pub fn new(size: usize) -> Self {
         Self {
-            total: size as i64,
-            permit: AtomicI64::new(size as i64),
-            waiters: SegQueue::new(),
+            total: size,
+            state: Mutex::new(State {
+                permits: size,
+                waiters: VecDeque::new(),
+            }),
         }
     }
 
     pub fn permit(&self) -> i64 {
-        self.permit.fetch_or(0, Ordering::Relaxed)
+        self.state.lock().unwrap().permits as i64
+    }
+
+    /// The number of waiters in the queue.
+    pub fn waiters(&self) -> usize {
+        self.state.lock().unwrap().waiters.len()
     }
 
     pub fn acquire(&self) -> PermitGuard {
-        if self.permit() > 0 {
-            self.permit.fetch_sub(1,Ordering::Relaxed);
-            PermitGuard {
-                inner: &self,
-                blocker: Blocker::current(),
+        self.acquire_until(None)
+            .expect("BUG: acquire without a deadline timed out")
+    }
+
+    /// Wait for a permit until `deadline`, `None` if it passed first.
+    ///
+    /// A permit is only taken right away if nobody is waiting for one,
+    /// released permits are handed over to the waiters in the order they came.
+    pub fn acquire_until(&self, deadline: Option<Instant>) -> Option<PermitGuard> {
+        let waiter = {
+            let mut state = self.state.lock().unwrap();
+            if state.permits > 0 && state.waiters.is_empty() {
+                state.permits -= 1;
+                return Some(PermitGuard { inner: self });
             }
-        } else {
-            let b = Blocker::current();
-            self.waiters.push(b.clone());
-            b.park(None);
-            PermitGuard {
-                inner: &self,
-                blocker: b,
+            let waiter = Arc::new(Waiter {
+                blocker: Blocker::new(false),
+                granted: AtomicBool::new(false),
+            });
+            state.waiters.push_back(waiter.clone());
+            waiter
+        };
+
+        loop {
+            let timeout = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(timeout) => Some(timeout),
+                    None => break,
+                },
+                None => None,
+            };
+            let _ = waiter.blocker.park(timeout);
+            if waiter.granted.load(Ordering::Acquire) {
+                return Some(PermitGuard { inner: self });
             }
         }
+
+        // timed out, unless a permit was handed over in the meantime
+        let mut state = self.state.lock().unwrap();
+        if waiter.granted.load(Ordering::Acquire) {
+            return Some(PermitGuard { inner: self });
+        }
+        state.waiters.retain(|w| !Arc::ptr_eq(w, &waiter));
+        None
     }
 
     pub fn try_acquire(&self) -> Option<PermitGuard> {
-        if self.permit() > 0 {
-            Some(self.acquire())
+        let mut state = self.state.lock().unwrap();
+        if state.permits > 0 && state.waiters.is_empty() {
+            state.permits -= 1;
+            Some(PermitGuard { inner: self })
         } else {
             None
         }
     }
 
     pub fn release(&self) {
-        let per = self.permit();
-        if per >= self.total {
-            return;
-        }
-        if self.waiters.is_empty() {
-            // If there are no waiters, just decrement and we're done
-            self.permit.fetch_add(1,Ordering::Relaxed);
-        } else {
-            let w = self.waiters.pop();
-            if let Some(w) = w {
-                self.permit.fetch_add(1,Ordering::Relaxed);
-                w.unpark();
-            }
+        let mut state = self.state.lock().unwrap();
+        if let Some(waiter) = state.waiters.pop_front() {
+            // hand the permit over to the first waiter
+            waiter.granted.store(true, Ordering::Release);
+            drop(state);
+            let _ = waiter.blocker.unpark();
+        } else if state.permits < self.total {
+            state.permits += 1;
         }
     }
 
     pub fn release_left(&self, mut num: usize) -> usize {
-        if self.permit() == self.total {
-            return 0;
-        }
-        if num > self.total as usize {
-            num = self.total as usize;
+        let state = self.state.lock().unwrap();
+        let left = self.total - state.permits + state.waiters.len();
+        drop(state);
+        if num > left {
+            num = left;
         }
         for _ in 0..num {
             self.release();
@@ -100,7 +142,7 @@ impl BoxSemaphore {
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
     use mco::coroutine::sleep;
     use mco::{chan, co};
     use crate::pool::semaphore::{BoxSemaphore};
@@ -172,6 +214,36 @@ mod test {
         sleep(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_acquire_until_fifo() {
+        let b = Arc::new(BoxSemaphore::new(1));
+        let permit = b.acquire();
+        let start = Instant::now();
+        assert!(b.acquire_until(Some(start + Duration::from_millis(100))).is_none());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(b.waiters(), 0);
+
+        let (s, r) = chan!();
+        for idx in 0..3 {
+            let s1 = s.clone();
+            let b1 = b.clone();
+            co!(move ||{
+                let permit = b1.acquire_until(Some(Instant::now() + Duration::from_secs(5)));
+                s1.send(idx);
+                permit.unwrap().release();
+            });
+            sleep(Duration::from_millis(50));
+        }
+        assert_eq!(b.waiters(), 3);
+        // a waiter is queued, no one may overtake it
+        assert!(b.try_acquire().is_none());
+        permit.release();
+        let order: Vec<i32> = (0..3).map(|_| r.recv().unwrap()).collect();
+        assert_eq!(order, vec![0, 1, 2]);
+        sleep(Duration::from_millis(50));
+        assert_eq!(b.permit(), 1);
+    }
+
     #[test]
     fn test_acq_mult() {
         let total = 1000;
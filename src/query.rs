@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 pub use either::Either;
 
@@ -11,8 +12,7 @@ use crate::executor::{Execute, Executor};
 use crate::io::chan_stream::ChanStream;
 use crate::statement::Statement;
 use crate::types::Type;
-use crate::chan_stream;
-use crate::io::chan_stream::TryStream;
+use crate::utils::sql::expand_placeholder;
 
 
 /// Raw SQL query with bind parameters. Returned by [`query`][crate::query::query].
@@ -22,6 +22,12 @@ pub struct Query<DB: Database, A> {
     pub arguments: Option<A>,
     pub database: PhantomData<DB>,
     pub persistent: bool,
+    pub timeout: Option<Duration>,
+    /// The number of values bound with [`bind`](Query::bind) and [`bind_list`](Query::bind_list).
+    pub bound: usize,
+    /// The error of a [`bind_list`](Query::bind_list) without its placeholder, returned when the
+    /// query is executed.
+    pub(crate) error: Option<Error>,
 }
 
 /// SQL query that will map its results to owned Rust types.
@@ -68,6 +74,11 @@ impl<'q, DB, A> Execute<'q, DB> for Query<DB, A>
     fn persistent(&self) -> bool {
         self.persistent
     }
+
+    #[inline]
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
 }
 
 impl<'q, DB: Database> Query<DB, <DB as HasArguments<'q>>::Arguments> {
@@ -82,11 +93,64 @@ impl<'q, DB: Database> Query<DB, <DB as HasArguments<'q>>::Arguments> {
     pub fn bind<T: 'q + Send + Encode<'q, DB> + Type<DB>>(mut self, value: T) -> Self {
         if let Some(arguments) = &mut self.arguments {
             arguments.add(value);
+            self.bound += 1;
         }
 
         self
     }
 
+    /// Bind every value of `values` for use with an `IN (..)` list of this SQL query.
+    ///
+    /// The placeholder of the next bind parameter is expanded into one placeholder per value,
+    /// later `$N`(Postgres) or `@pN`(MSSQL) placeholders are renumbered, so the query keeps
+    /// binding its other parameters as written:
+    ///
+    /// ```ignore
+    /// let rows = query("select * from biz_activity where status = $1 and id in ($2) and version = $3")
+    ///     .bind(1)
+    ///     .bind_list(vec!["1", "2"]) // `id in ($2, $3)`
+    ///     .bind(0) // `version = $4`
+    ///     .fetch_all(&mut conn)?;
+    /// ```
+    ///
+    /// An empty list is written as `NULL`, `id in (NULL)` matches no row(note `not in (NULL)`
+    /// doesn't match either).
+    ///
+    /// The placeholders are counted from the values bound with [`bind`](Query::bind) and
+    /// `bind_list`, not from the arguments given to [`query_with`].
+    ///
+    /// If the query has no placeholder for the next bind parameter, or uses its numbered
+    /// placeholder more than once (`id in ($1) or parent in ($1)`), nothing is bound and the query
+    /// fails with [`Error::Configuration`] when it is executed.
+    pub fn bind_list<T, I>(mut self, values: I) -> Self
+        where
+            T: 'q + Send + Encode<'q, DB> + Type<DB>,
+            I: IntoIterator<Item=T>,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+        let values: Vec<T> = values.into_iter().collect();
+        let sql = match &mut self.statement {
+            Either::Left(l) => l,
+            Either::Right(r) => r.sql_mut(),
+        };
+        match expand_placeholder(sql, DB::holder(), self.bound + 1, values.len()) {
+            Ok(expanded) => *sql = expanded,
+            Err(e) => {
+                self.error = Some(Error::Configuration(format!("bind_list: {} in `{}`", e, sql).into()));
+                return self;
+            }
+        }
+        if let Some(arguments) = &mut self.arguments {
+            self.bound += values.len();
+            for value in values {
+                arguments.add(value);
+            }
+        }
+        self
+    }
+
     pub fn reset_sql(mut self, sql: String) -> Self {
         match &mut self.statement {
             Either::Left(l) => {
@@ -112,6 +176,26 @@ impl<'q, DB: Database> Query<DB, <DB as HasArguments<'q>>::Arguments> {
     }
 }
 
+impl<DB: Database, A> Query<DB, A> {
+    /// The error of a [`bind_list`](Query::bind_list), taken before the query is executed.
+    fn check(&mut self) -> Result<(), Error> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Cancel the statement on the server and fail with [`Error::QueryTimedOut`]
+    /// if it runs longer than `timeout`.
+    ///
+    /// Overrides the [`default_timeout`](Executor::default_timeout) of the executor,
+    /// the [`deadline`](crate::deadline) of the coroutine still applies.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
 impl<'q, DB, A> Query<DB, A>
     where
         DB: Database + HasStatementCache,
@@ -171,64 +255,83 @@ impl<'q, DB, A: Send> Query<DB, A>
 
     /// Execute the query and return the total number of rows affected.
     #[inline]
-    pub fn execute<'c, E>(self, mut executor: E) -> Result<DB::QueryResult, Error>
+    pub fn execute<'c, E>(mut self, mut executor: E) -> Result<DB::QueryResult, Error>
         where E: Executor<Database=DB>,
     {
+        self.check()?;
         executor.execute(self)
     }
 
     /// Execute multiple queries and return the rows affected from each query, in a stream.
     #[inline]
     pub fn execute_many<'c, E>(
-        self,
-        mut executor: E,
-    ) -> ChanStream<DB::QueryResult>
-        where E: Executor<Database=DB>,
+        mut self,
+        executor: E,
+    ) -> ChanStream<'c, DB::QueryResult>
+        where E: 'c + Executor<Database=DB>,
+              'q: 'c,
+              A: 'c,
     {
-        executor.execute_many(self)
+        if let Err(e) = self.check() {
+            return ChanStream::new(move |_| Err(e));
+        }
+        ChanStream::owned(executor, move |executor, _| executor.execute_many(self))
     }
 
     /// Execute the query and return the generated results as a stream.
     #[inline]
-    pub fn fetch<'c, E>(self, mut executor: E) -> ChanStream<DB::Row>
-        where E: Executor<Database=DB>,
+    pub fn fetch<'c, E>(mut self, executor: E) -> ChanStream<'c, DB::Row>
+        where E: 'c + Executor<Database=DB>,
+              'q: 'c,
+              A: 'c,
     {
-        executor.fetch(self)
+        if let Err(e) = self.check() {
+            return ChanStream::new(move |_| Err(e));
+        }
+        ChanStream::owned(executor, move |executor, _| executor.fetch(self))
     }
 
     /// Execute multiple queries and return the generated results as a stream
     /// from each query, in a stream.
     #[inline]
     pub fn fetch_many<'c, E>(
-        self,
-        mut executor: E,
-    ) -> ChanStream<Either<DB::QueryResult, DB::Row>>
-        where E: Executor<Database=DB>,
+        mut self,
+        executor: E,
+    ) -> ChanStream<'c, Either<DB::QueryResult, DB::Row>>
+        where E: 'c + Executor<Database=DB>,
+              'q: 'c,
+              A: 'c,
     {
-        executor.fetch_many(self)
+        if let Err(e) = self.check() {
+            return ChanStream::new(move |_| Err(e));
+        }
+        ChanStream::owned(executor, move |executor, _| executor.fetch_many(self))
     }
 
     /// Execute the query and return all the generated results, collected into a [`Vec`].
     #[inline]
-    pub fn fetch_all<'c, E>(self, mut executor: E) -> Result<Vec<DB::Row>, Error>
+    pub fn fetch_all<'c, E>(mut self, mut executor: E) -> Result<Vec<DB::Row>, Error>
         where E: Executor<Database=DB>,
     {
+        self.check()?;
         executor.fetch_all(self)
     }
 
     /// Execute the query and returns exactly one row.
     #[inline]
-    pub fn fetch_one<'c, E>(self, mut executor: E) -> Result<DB::Row, Error>
+    pub fn fetch_one<'c, E>(mut self, mut executor: E) -> Result<DB::Row, Error>
         where E: Executor<Database=DB>,
     {
+        self.check()?;
         executor.fetch_one(self)
     }
 
     /// Execute the query and returns at most one row.
     #[inline]
-    pub fn fetch_optional<'c, E>(self, mut executor: E) -> Result<Option<DB::Row>, Error>
+    pub fn fetch_optional<'c, E>(mut self, mut executor: E) -> Result<Option<DB::Row>, Error>
         where E: Executor<Database=DB>,
     {
+        self.check()?;
         executor.fetch_optional(self)
     }
 }
@@ -257,6 +360,11 @@ impl<'q, DB, F: Send, A: Send> Execute<'q, DB> for Map<DB, F, A>
     fn persistent(&self) -> bool {
         self.inner.arguments.is_some()
     }
+
+    #[inline]
+    fn timeout(&self) -> Option<Duration> {
+        self.inner.timeout
+    }
 }
 
 impl<'q, DB, F, O, A> Map<DB, F, A>
@@ -266,6 +374,14 @@ impl<'q, DB, F, O, A> Map<DB, F, A>
         O: Send,
         A: 'q + Send + IntoArguments<'q, DB>,
 {
+    /// Fail the query if it runs longer than `timeout`.
+    ///
+    /// See [`Query::timeout`](Query::timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner.timeout = Some(timeout);
+        self
+    }
+
     /// Map each row in the result to another type.
     ///
     /// See [`try_map`](Map::try_map) for a fallible version of this method.
@@ -303,14 +419,15 @@ impl<'q, DB, F, O, A> Map<DB, F, A>
     }
 
     /// Execute the query and return the generated results as a stream.
-    pub fn fetch<'c, E>(self, mut executor: E) -> ChanStream<O>
+    pub fn fetch<'c, E>(self, executor: E) -> ChanStream<'c, O>
         where E: 'c + Executor<Database=DB>,
+              'q: 'c,
               DB: 'c,
               F: 'c,
               O: 'c,
     {
         self.fetch_many(executor)
-            .map(|step| {
+            .filter_map(|step| {
                 match step {
                     Either::Left(_) => None,
                     Either::Right(o) => Some(o),
@@ -321,28 +438,24 @@ impl<'q, DB, F, O, A> Map<DB, F, A>
     /// Execute multiple queries and return the generated results as a stream
     /// from each query, in a stream.
     pub fn fetch_many<'c, E>(
-        mut self,
-        mut executor: E,
-    ) -> ChanStream<Either<DB::QueryResult, O>>
+        self,
+        executor: E,
+    ) -> ChanStream<'c, Either<DB::QueryResult, O>>
         where E: 'c + Executor<Database=DB>,
+              'q: 'c,
               DB: 'c,
               F: 'c,
               O: 'c,
     {
-        chan_stream! {
-            let mut s = executor.fetch_many(self.inner);
-
-            while let Some(v) = s.try_next()? {
-                r#yield!(match v {
+        let mut mapper = self.mapper;
+        self.inner
+            .fetch_many(executor)
+            .try_map(move |v| {
+                Ok(match v {
                     Either::Left(v) => Either::Left(v),
-                    Either::Right(row) => {
-                        Either::Right((self.mapper)(row)?)
-                    }
-                });
-            }
-
-            Ok(())
-        }
+                    Either::Right(row) => Either::Right(mapper(row)?),
+                })
+            })
     }
 
     /// Execute the query and return all the generated results, collected into a [`Vec`].
@@ -372,13 +485,13 @@ impl<'q, DB, F, O, A> Map<DB, F, A>
     }
 
     /// Execute the query and returns at most one row.
-    pub fn fetch_optional<'c, E>(mut self, mut executor: E) -> Result<Option<O>, Error>
+    pub fn fetch_optional<'c, E>(mut self, executor: E) -> Result<Option<O>, Error>
         where E: 'c + Executor<Database=DB>,
               DB: 'c,
               F: 'c,
               O: 'c,
     {
-        let row = executor.fetch_optional(self.inner)?;
+        let row = self.inner.fetch_optional(executor)?;
 
         if let Some(row) = row {
             (self.mapper)(row).map(Some)
@@ -400,6 +513,9 @@ pub fn query_statement<'q, DB>(
         arguments: Some(Default::default()),
         statement: Either::Right(statement),
         persistent: true,
+        timeout: None,
+        bound: 0,
+        error: None,
     }
 }
 
@@ -417,6 +533,9 @@ pub fn query_statement_with<'q, DB, A>(
         arguments: Some(arguments),
         statement: Either::Right(statement),
         persistent: true,
+        timeout: None,
+        bound: 0,
+        error: None,
     }
 }
 
@@ -430,6 +549,9 @@ pub fn query<DB>(sql: &str) -> Query<DB, <DB as HasArguments<'_>>::Arguments>
         arguments: Some(Default::default()),
         statement: Either::Left(sql.to_string()),
         persistent: true,
+        timeout: None,
+        bound: 0,
+        error: None,
     }
 }
 
@@ -444,6 +566,9 @@ pub fn query_with<'q, DB, A>(sql: &str, arguments: A) -> Query<DB, A>
         arguments: Some(arguments),
         statement: Either::Left(sql.to_string()),
         persistent: true,
+        timeout: None,
+        bound: 0,
+        error: None,
     }
 }
 
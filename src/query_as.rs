@@ -1,8 +1,7 @@
 use std::marker::PhantomData;
+use std::time::Duration;
 use either::Either;
 use crate::arguments::IntoArguments;
-use crate::chan_stream;
-use crate::io::chan_stream::TryStream;
 use crate::database::{Database, HasArguments, HasStatement, HasStatementCache};
 use crate::encode::Encode;
 use crate::error::Error;
@@ -44,6 +43,11 @@ impl<'q, DB, O: Send, A: Send> Execute<'q, DB> for QueryAs<DB, O, A>
     fn persistent(&self) -> bool {
         self.inner.persistent()
     }
+
+    #[inline]
+    fn timeout(&self) -> Option<Duration> {
+        self.inner.timeout
+    }
 }
 
 impl<'q, DB: Database, O> QueryAs< DB, O, <DB as HasArguments<'q>>::Arguments> {
@@ -54,6 +58,28 @@ impl<'q, DB: Database, O> QueryAs< DB, O, <DB as HasArguments<'q>>::Arguments> {
         self.inner = self.inner.bind(value);
         self
     }
+
+    /// Bind every value of `values` for use with an `IN (..)` list of this SQL query.
+    ///
+    /// See [`Query::bind_list`](Query::bind_list).
+    pub fn bind_list<T, I>(mut self, values: I) -> Self
+        where
+            T: 'q + Send + Encode<'q, DB> + Type<DB>,
+            I: IntoIterator<Item=T>,
+    {
+        self.inner = self.inner.bind_list(values);
+        self
+    }
+}
+
+impl<DB: Database, O, A> QueryAs< DB, O, A> {
+    /// Fail the query if it runs longer than `timeout`.
+    ///
+    /// See [`Query::timeout`](Query::timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.timeout(timeout);
+        self
+    }
 }
 
 impl<'q, DB, O, A> QueryAs< DB, O, A>
@@ -83,7 +109,7 @@ impl<'q, DB, O, A> QueryAs< DB, O, A>
         O: Send + for<'r> FromRow<'r, DB::Row>,
 {
     /// Execute the query and return the generated results as a stream.
-    pub fn fetch<'e, 'c: 'e, E>(self, mut executor: E) -> ChanStream<O>
+    pub fn fetch<'e, 'c: 'e, E>(self, executor: E) -> ChanStream<'e, O>
         where
             'q: 'e,
             E: 'e + Executor< Database=DB>,
@@ -92,7 +118,7 @@ impl<'q, DB, O, A> QueryAs< DB, O, A>
             A: 'e,
     {
         self.fetch_many(executor)
-            .map(|v| {
+            .filter_map(|v| {
                 v.right()
             })
     }
@@ -101,8 +127,8 @@ impl<'q, DB, O, A> QueryAs< DB, O, A>
     /// from each query, in a stream.
     pub fn fetch_many<'e, 'c: 'e, E>(
         self,
-        mut executor: E,
-    ) -> ChanStream<Either<DB::QueryResult, O>>
+        executor: E,
+    ) -> ChanStream<'e, Either<DB::QueryResult, O>>
         where
             'q: 'e,
             E: 'e + Executor< Database=DB>,
@@ -110,18 +136,14 @@ impl<'q, DB, O, A> QueryAs< DB, O, A>
             O: 'e,
             A: 'e,
     {
-        chan_stream! {
-            let mut s = executor.fetch_many(self.inner);
-
-            while let Some(v) = s.try_next()? {
-                r#yield!(match v {
+        self.inner
+            .fetch_many(executor)
+            .try_map(|v| {
+                Ok(match v {
                     Either::Left(v) => Either::Left(v),
                     Either::Right(row) => Either::Right(O::from_row(&row)?),
-                });
-            }
-
-            Ok(())
-        }
+                })
+            })
     }
 
     /// Execute the query and return all the generated results, collected into a [`Vec`].
@@ -154,11 +176,11 @@ impl<'q, DB, O, A> QueryAs< DB, O, A>
     }
 
     /// Execute the query and returns at most one row.
-    pub fn fetch_optional<'c, E>(self, mut executor: E) -> Result<Option<O>, Error>
+    pub fn fetch_optional<'c, E>(self, executor: E) -> Result<Option<O>, Error>
         where
             E: Executor< Database=DB>,
     {
-        let row = executor.fetch_optional(self.inner)?;
+        let row = self.inner.fetch_optional(executor)?;
         if let Some(row) = row {
             O::from_row(&row).map(Some)
         } else {
@@ -0,0 +1,244 @@
+//! Runtime query-builder API.
+
+use std::fmt::Display;
+use std::fmt::Write;
+use std::marker::PhantomData;
+
+use either::Either;
+
+use crate::arguments::Arguments;
+use crate::database::{Database, HasArguments};
+use crate::encode::Encode;
+use crate::query::Query;
+use crate::types::Type;
+use crate::utils::sql::push_placeholder;
+
+/// A builder type for constructing queries at runtime.
+///
+/// The placeholders of [`push_bind`](QueryBuilder::push_bind) are written in the syntax of the
+/// database (see [`Database::holder`]): `?` for MySQL/SQLite, `$1, $2..` for Postgres
+/// and `@p1, @p2..` for MSSQL, so the bind order always matches the sql.
+///
+/// ```ignore
+/// let mut builder: QueryBuilder<Postgres> = QueryBuilder::new("select * from biz_activity where id in (");
+/// let mut separated = builder.separated(", ");
+/// for id in ids {
+///     separated.push_bind(id);
+/// }
+/// separated.push_unseparated(")");
+/// let rows = builder.build().fetch_all(&mut conn)?;
+/// ```
+pub struct QueryBuilder<'args, DB>
+    where
+        DB: Database,
+{
+    query: String,
+    init_len: usize,
+    arguments: Option<<DB as HasArguments<'args>>::Arguments>,
+    count: usize,
+}
+
+impl<'args, DB: Database> QueryBuilder<'args, DB>
+    where
+        DB: Database,
+{
+    /// Start building a query with an initial SQL fragment, which may be an empty string.
+    pub fn new(init: impl Into<String>) -> Self {
+        let init = init.into();
+        QueryBuilder {
+            init_len: init.len(),
+            query: init,
+            arguments: Some(Default::default()),
+            count: 0,
+        }
+    }
+
+    #[inline]
+    fn sanity_check(&self) {
+        assert!(
+            self.arguments.is_some(),
+            "QueryBuilder must be reset before reuse after `.build()`"
+        );
+    }
+
+    /// Append a SQL fragment to the query.
+    ///
+    /// The fragment is not escaped, never push untrusted input here, use
+    /// [`push_bind`](QueryBuilder::push_bind) for values.
+    pub fn push(&mut self, sql: impl Display) -> &mut Self {
+        self.sanity_check();
+        write!(self.query, "{}", sql).expect("error formatting `sql`");
+        self
+    }
+
+    /// Push a bind argument placeholder (`?`, `$N` or `@pN` for the database) and bind a value to it.
+    pub fn push_bind<T>(&mut self, value: T) -> &mut Self
+        where
+            T: 'args + Send + Encode<'args, DB> + Type<DB>,
+    {
+        self.sanity_check();
+        let arguments = self
+            .arguments
+            .as_mut()
+            .expect("BUG: Arguments taken already");
+        arguments.add(value);
+        self.count += 1;
+        push_placeholder(&mut self.query, DB::holder(), self.count);
+        self
+    }
+
+    /// Start a list separated by `separator`.
+    ///
+    /// The returned type exposes identical [`push`](Separated::push) and
+    /// [`push_bind`](Separated::push_bind) methods which push `separator` to the query
+    /// before their normal behavior. [`push_unseparated`](Separated::push_unseparated) and
+    /// [`push_bind_unseparated`](Separated::push_bind_unseparated) are also provided to push
+    /// a SQL fragment without the separator.
+    pub fn separated<'qb, Sep>(&'qb mut self, separator: Sep) -> Separated<'qb, 'args, DB, Sep>
+        where
+            'args: 'qb,
+            Sep: Display,
+    {
+        self.sanity_check();
+        Separated {
+            query_builder: self,
+            separator,
+            push_separator: false,
+        }
+    }
+
+    /// Push a `VALUES` clause where each item in `tuples` represents a tuple/row in the clause,
+    /// for a multi-row `INSERT`.
+    ///
+    /// `push_tuple` is called once for each item, the [`Separated`] it gets is separated by `, `
+    /// and is already wrapped in parentheses.
+    ///
+    /// ```ignore
+    /// let mut builder: QueryBuilder<MySql> = QueryBuilder::new("insert into biz_activity(id, name) ");
+    /// builder.push_values(activities, |mut b, arg| {
+    ///     b.push_bind(arg.id).push_bind(arg.name);
+    /// });
+    /// builder.build().execute(&mut conn)?;
+    /// ```
+    ///
+    /// Note the total number of bind parameters of a statement is limited by every database,
+    /// split large inserts into chunks.
+    pub fn push_values<I, F>(&mut self, tuples: I, mut push_tuple: F) -> &mut Self
+        where
+            I: IntoIterator,
+            F: FnMut(Separated<'_, 'args, DB, &'static str>, I::Item),
+    {
+        self.sanity_check();
+
+        self.push("VALUES ");
+
+        let mut separated = self.separated(", ");
+
+        for tuple in tuples {
+            separated.push("(");
+
+            push_tuple(separated.query_builder.separated(", "), tuple);
+
+            separated.push_unseparated(")");
+        }
+
+        separated.query_builder
+    }
+
+    /// Produce an executable query from this builder.
+    ///
+    /// The builder takes its arguments into the query, it must be [`reset`](QueryBuilder::reset)
+    /// before it is used again.
+    ///
+    /// ### Panics
+    /// If the builder was already built and not reset.
+    pub fn build(&mut self) -> Query<DB, <DB as HasArguments<'args>>::Arguments> {
+        self.sanity_check();
+
+        Query {
+            statement: Either::Left(self.query.clone()),
+            arguments: self.arguments.take(),
+            database: PhantomData,
+            persistent: true,
+            timeout: None,
+            bound: self.count,
+            error: None,
+        }
+    }
+
+    /// Reset this builder to the initial SQL fragment given to [`new`](QueryBuilder::new),
+    /// dropping the bound arguments.
+    pub fn reset(&mut self) -> &mut Self {
+        self.query.truncate(self.init_len);
+        self.arguments = Some(Default::default());
+        self.count = 0;
+        self
+    }
+
+    /// Get the current SQL of this builder.
+    pub fn sql(&self) -> &str {
+        &self.query
+    }
+
+    /// Deconstruct this builder, returning the built SQL. May not be syntactically correct.
+    pub fn into_sql(self) -> String {
+        self.query
+    }
+}
+
+/// A wrapper around [`QueryBuilder`] for creating comma(or other token)-separated lists.
+///
+/// See [`QueryBuilder::separated`] for details.
+pub struct Separated<'qb, 'args: 'qb, DB, Sep>
+    where
+        DB: Database,
+{
+    query_builder: &'qb mut QueryBuilder<'args, DB>,
+    separator: Sep,
+    push_separator: bool,
+}
+
+impl<'qb, 'args: 'qb, DB: Database, Sep: Display> Separated<'qb, 'args, DB, Sep> {
+    /// Push the separator if applicable, and then the given SQL fragment.
+    pub fn push(&mut self, sql: impl Display) -> &mut Self {
+        if self.push_separator {
+            self.query_builder
+                .push(format_args!("{}{}", self.separator, sql));
+        } else {
+            self.query_builder.push(sql);
+            self.push_separator = true;
+        }
+
+        self
+    }
+
+    /// Push a SQL fragment without a separator.
+    pub fn push_unseparated(&mut self, sql: impl Display) -> &mut Self {
+        self.query_builder.push(sql);
+        self
+    }
+
+    /// Push the separator if applicable, then a bind argument placeholder and bind a value to it.
+    pub fn push_bind<T>(&mut self, value: T) -> &mut Self
+        where
+            T: 'args + Send + Encode<'args, DB> + Type<DB>,
+    {
+        if self.push_separator {
+            self.query_builder.push(&self.separator);
+        }
+
+        self.query_builder.push_bind(value);
+        self.push_separator = true;
+
+        self
+    }
+
+    /// Push a bind argument placeholder and bind a value to it, without a separator.
+    pub fn push_bind_unseparated<T>(&mut self, value: T) -> &mut Self
+        where
+            T: 'args + Send + Encode<'args, DB> + Type<DB>,
+    {
+        self.query_builder.push_bind(value);
+        self
+    }
+}
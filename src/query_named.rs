@@ -0,0 +1,303 @@
+//! Queries with named bind parameters.
+
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use either::Either;
+
+use crate::arguments::{Arguments, IntoArguments};
+use crate::database::{Database, HasArguments};
+use crate::encode::Encode;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::from_row::FromRow;
+use crate::io::chan_stream::ChanStream;
+use crate::query::Query;
+use crate::query_as::QueryAs;
+use crate::types::Type;
+use crate::utils::sql::{is_ident, is_ident_start, literal_end, push_placeholder};
+
+type Bind<'q, DB> = Box<dyn Fn(&mut <DB as HasArguments<'q>>::Arguments) + Send + 'q>;
+
+/// SQL query with named bind parameters. Returned by [`query_named`].
+///
+/// The parameters are written `:name` and bound by name with
+/// [`bind_named`](NamedQuery::bind_named), in any order. The SQL is rewritten into the
+/// placeholders of the database (see [`Database::holder`]): `?` for MySQL/SQLite, `$1, $2..` for
+/// Postgres and `@p1, @p2..` for MSSQL. A name may appear more than once.
+///
+/// Names in string literals, quoted identifiers, comments and Postgres dollar-quoted strings are
+/// left alone, as are Postgres casts (`::int`) and a `:` right after a name or number
+/// (`arr[1:n]`). `@name` is not a parameter, MySQL user variables and MSSQL variables
+/// (`@x`, `@@rowcount`) are sent as written.
+///
+/// A parameter without a bound value, or a value bound to a name the SQL doesn't use, fails the
+/// query with [`Error::Configuration`] before anything is sent to the database.
+///
+/// ```ignore
+/// let rows = cdbc::query_named::<Postgres>(
+///     "select * from biz_activity where status = :status and (name = :name or remark = :name)",
+/// )
+/// .bind_named("name", "tom")
+/// .bind_named("status", 1)
+/// .fetch_all(&mut conn)?;
+/// ```
+#[must_use = "query must be executed to affect database"]
+pub struct NamedQuery<'q, DB: Database> {
+    sql: String,
+    /// the name of every bind argument, in the order they are sent
+    names: Vec<String>,
+    binds: Vec<(String, Bind<'q, DB>)>,
+    persistent: bool,
+    timeout: Option<Duration>,
+}
+
+impl<'q, DB: Database> NamedQuery<'q, DB> {
+    /// Bind a value to the parameter `name`, written with or without its `:` prefix.
+    ///
+    /// The value is cloned when the name appears more than once in a MySQL/SQLite query.
+    pub fn bind_named<T>(mut self, name: &str, value: T) -> Self
+        where
+            T: 'q + Send + Clone + Encode<'q, DB> + Type<DB>,
+    {
+        let name = name.trim_start_matches(':');
+        self.binds.push((
+            name.to_owned(),
+            Box::new(move |arguments| arguments.add(value.clone())),
+        ));
+        self
+    }
+
+    /// The SQL rewritten into the placeholders of the database.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// See [`Query::persistent`].
+    pub fn persistent(mut self, value: bool) -> Self {
+        self.persistent = value;
+        self
+    }
+
+    /// See [`Query::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Check the bound names and produce the [`Query`] with its arguments in placeholder order.
+    pub fn build(self) -> Result<Query<DB, <DB as HasArguments<'q>>::Arguments>, Error> {
+        let mut errors = Vec::new();
+        for (i, (name, _)) in self.binds.iter().enumerate() {
+            if self.binds[..i].iter().any(|(bound, _)| bound == name) {
+                errors.push(format!("`{}` is bound more than once", name));
+            } else if !self.names.contains(name) {
+                errors.push(format!("`{}` is bound but not used by the query", name));
+            }
+        }
+        let mut missing = Vec::new();
+        for name in &self.names {
+            if !missing.contains(name) && !self.binds.iter().any(|(bound, _)| bound == name) {
+                missing.push(name.clone());
+                errors.push(format!("`{}` is not bound", name));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(Error::Configuration(
+                format!("named parameters: {}", errors.join(", ")).into(),
+            ));
+        }
+
+        let mut arguments = <DB as HasArguments<'q>>::Arguments::default();
+        for name in &self.names {
+            if let Some((_, bind)) = self.binds.iter().find(|(bound, _)| bound == name) {
+                bind(&mut arguments);
+            }
+        }
+        Ok(Query {
+            statement: Either::Left(self.sql),
+            arguments: Some(arguments),
+            database: PhantomData,
+            persistent: self.persistent,
+            timeout: self.timeout,
+            bound: self.names.len(),
+            error: None,
+        })
+    }
+
+    /// Like [`build`](NamedQuery::build), mapping the rows to `O` with [`FromRow`].
+    pub fn build_as<O>(self) -> Result<QueryAs<DB, O, <DB as HasArguments<'q>>::Arguments>, Error>
+        where
+            O: for<'r> FromRow<'r, DB::Row>,
+    {
+        Ok(QueryAs {
+            inner: self.build()?,
+            output: PhantomData,
+        })
+    }
+}
+
+impl<'q, DB: Database> NamedQuery<'q, DB>
+    where
+        <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+{
+    /// Execute the query and return the total number of rows affected.
+    pub fn execute<E>(self, executor: E) -> Result<DB::QueryResult, Error>
+        where E: Executor<Database=DB>,
+    {
+        self.build()?.execute(executor)
+    }
+
+    /// Execute the query and return the generated results as a stream.
+    pub fn fetch<'c, E>(self, executor: E) -> ChanStream<'c, DB::Row>
+        where E: 'c + Executor<Database=DB>,
+              'q: 'c,
+    {
+        match self.build() {
+            Ok(query) => query.fetch(executor),
+            Err(e) => ChanStream::new(move |_| Err(e)),
+        }
+    }
+
+    /// Execute the query and return all the generated results, collected into a [`Vec`].
+    pub fn fetch_all<E>(self, executor: E) -> Result<Vec<DB::Row>, Error>
+        where E: Executor<Database=DB>,
+    {
+        self.build()?.fetch_all(executor)
+    }
+
+    /// Execute the query and returns exactly one row.
+    pub fn fetch_one<E>(self, executor: E) -> Result<DB::Row, Error>
+        where E: Executor<Database=DB>,
+    {
+        self.build()?.fetch_one(executor)
+    }
+
+    /// Execute the query and returns at most one row.
+    pub fn fetch_optional<E>(self, executor: E) -> Result<Option<DB::Row>, Error>
+        where E: Executor<Database=DB>,
+    {
+        self.build()?.fetch_optional(executor)
+    }
+}
+
+/// Make a SQL query with named bind parameters, see [`NamedQuery`].
+pub fn query_named<'q, DB>(sql: &str) -> NamedQuery<'q, DB>
+    where
+        DB: Database,
+{
+    let (sql, names) = parse_named(sql, DB::holder());
+    NamedQuery {
+        sql,
+        names,
+        binds: Vec::new(),
+        persistent: true,
+        timeout: None,
+    }
+}
+
+/// Rewrite the `:name` parameters of `sql` into the placeholders of `holder`, returning
+/// the sql and the name of every bind argument in order.
+///
+/// With `?` placeholders every occurrence of a name is an argument, with numbered placeholders
+/// a name is one argument however often it appears.
+fn parse_named(sql: &str, holder: &str) -> (String, Vec<String>) {
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len());
+    let mut names: Vec<String> = Vec::new();
+    let mut distinct: Vec<&str> = Vec::new();
+    let mut last = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let prev_ident = i > 0 && is_ident(bytes[i - 1]);
+        let next = bytes.get(i + 1).copied();
+        if let Some(end) = literal_end(sql, i) {
+            i = end;
+            continue;
+        }
+        let end = match bytes[i] {
+            b':' if next == Some(b':') => i + 2,
+            b':' if next.is_some_and(is_ident_start) && !prev_ident => {
+                let mut end = i + 1;
+                while end < bytes.len() && is_ident(bytes[end]) {
+                    end += 1;
+                }
+                let name = &sql[i + 1..end];
+                out.push_str(&sql[last..i]);
+                let index = match distinct.iter().position(|n| *n == name) {
+                    Some(index) => index,
+                    None => {
+                        distinct.push(name);
+                        distinct.len() - 1
+                    }
+                };
+                if holder == "?" {
+                    names.push(name.to_owned());
+                    push_placeholder(&mut out, holder, names.len());
+                } else {
+                    push_placeholder(&mut out, holder, index + 1);
+                }
+                last = end;
+                end
+            }
+            _ => i + 1,
+        };
+        i = end;
+    }
+    out.push_str(&sql[last..]);
+    if holder != "?" {
+        names = distinct.into_iter().map(ToOwned::to_owned).collect();
+    }
+    (out, names)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::query_named::parse_named;
+
+    #[test]
+    fn test_parse_named() {
+        let sql = "select * from t where a = :a and b = :b or a = :a";
+        assert_eq!(
+            parse_named(sql, "?"),
+            ("select * from t where a = ? and b = ? or a = ?".to_string(),
+             vec!["a".to_string(), "b".to_string(), "a".to_string()])
+        );
+        assert_eq!(
+            parse_named(sql, "$"),
+            ("select * from t where a = $1 and b = $2 or a = $1".to_string(),
+             vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(
+            parse_named(sql, "p").0,
+            "select * from t where a = @p1 and b = @p2 or a = @p1"
+        );
+    }
+
+    #[test]
+    fn test_parse_named_skips_literals() {
+        let sql = "select ':a', 'it''s :a', E'\\' :a', \"col:a\", `:a`, x::int, @@version, \
+                   $$ :a $$, $tag$ :a $$ $tag$, $1 -- :a\n/* :a /* :a */ :a */ from t where a = :b";
+        let expected = "select ':a', 'it''s :a', E'\\' :a', \"col:a\", `:a`, x::int, @@version, \
+                        $$ :a $$, $tag$ :a $$ $tag$, $1 -- :a\n/* :a /* :a */ :a */ from t where a = $1";
+        assert_eq!(parse_named(sql, "$"), (expected.to_string(), vec!["b".to_string()]));
+        assert_eq!(parse_named("select a:=1, 'x'@'localhost', user@host, arr[1:n]", "?").1, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_parse_named_keeps_variables() {
+        // MySQL user variables
+        let sql = "set @x := :x; select @x, @@version from t where a = :a and b = @x";
+        assert_eq!(
+            parse_named(sql, "?"),
+            ("set @x := ?; select @x, @@version from t where a = ? and b = @x".to_string(),
+             vec!["x".to_string(), "a".to_string()])
+        );
+        // MSSQL variables
+        let sql = "declare @n int = :n; update t set a = @n where b = :b; select @@rowcount";
+        assert_eq!(
+            parse_named(sql, "p").0,
+            "declare @n int = @p1; update t set a = @n where b = @p2; select @@rowcount"
+        );
+    }
+}
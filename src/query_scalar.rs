@@ -1,3 +1,4 @@
+use std::time::Duration;
 use either::Either;
 use crate::arguments::IntoArguments;
 use crate::database::{Database, HasArguments, HasStatement, HasStatementCache};
@@ -40,6 +41,11 @@ where
     fn persistent(&self) -> bool {
         self.inner.persistent()
     }
+
+    #[inline]
+    fn timeout(&self) -> Option<Duration> {
+        self.inner.inner.timeout
+    }
 }
 
 impl<'q, DB: Database, O> QueryScalar< DB, O, <DB as HasArguments<'q>>::Arguments> {
@@ -50,6 +56,28 @@ impl<'q, DB: Database, O> QueryScalar< DB, O, <DB as HasArguments<'q>>::Argument
         self.inner = self.inner.bind(value);
         self
     }
+
+    /// Bind every value of `values` for use with an `IN (..)` list of this SQL query.
+    ///
+    /// See [`Query::bind_list`](crate::query::Query::bind_list).
+    pub fn bind_list<T, I>(mut self, values: I) -> Self
+        where
+            T: 'q + Send + Encode<'q, DB> + Type<DB>,
+            I: IntoIterator<Item=T>,
+    {
+        self.inner = self.inner.bind_list(values);
+        self
+    }
+}
+
+impl<DB: Database, O, A> QueryScalar< DB, O, A> {
+    /// Fail the query if it runs longer than `timeout`.
+    ///
+    /// See [`Query::timeout`](crate::query::Query::timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.timeout(timeout);
+        self
+    }
 }
 
 impl<'q, DB, O, A> QueryScalar< DB, O, A>
@@ -81,7 +109,7 @@ where
 {
     /// Execute the query and return the generated results as a stream.
     #[inline]
-    pub fn fetch<'e, 'c: 'e, E>(self, executor: E) -> ChanStream<O>
+    pub fn fetch<'e, 'c: 'e, E>(self, executor: E) -> ChanStream<'e, O>
     where
         'q: 'e,
         E: 'e + Executor< Database = DB>,
@@ -91,7 +119,7 @@ where
     {
         //self.inner.fetch(executor).map_ok(|it| it.0).boxed()
         self.inner.fetch(executor).map(|v|{
-            Some(v.0)
+            v.0
         })
     }
 
@@ -101,7 +129,7 @@ where
     pub fn fetch_many<'e, 'c: 'e, E>(
         self,
         executor: E,
-    ) -> ChanStream<Either<DB::QueryResult, O>>
+    ) -> ChanStream<'e, Either<DB::QueryResult, O>>
     where
         'q: 'e,
         E: 'e + Executor< Database = DB>,
@@ -112,7 +140,7 @@ where
         self.inner
             .fetch_many(executor)
             .map(|v| {
-                Some(v.map_right(|it| it.0))
+                v.map_right(|it| it.0)
             })
     }
 
@@ -128,7 +156,7 @@ where
     {
         self.inner
             .fetch(executor)
-            .map(|it| Some(it.0))
+            .map(|it| it.0)
             .collect(|v|{
                 Some(Ok(v))
             })
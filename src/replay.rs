@@ -0,0 +1,618 @@
+//! Record the traffic of a test run against a real database and replay it later without one.
+//!
+//! A [`Recorder`] wraps a [`Pool`] and captures the SQL, the bound arguments, the rows and the
+//! [`QueryResult`](Database::QueryResult)s of every statement run through it. The
+//! [`Recording`] is saved as JSON with [`Recorder::save`].
+//!
+//! A [`Replayer`] serves the recorded results again, in the same order, with no database. It
+//! panics when a statement or its arguments differ from the recording, so a test can't silently
+//! run against results that don't belong to it.
+//!
+//! Both are [`Executor`]s of the same database, so a test can pick one of them at runtime:
+//!
+//! ```rust,ignore
+//! use cdbc::replay::{Recorder, Replayer};
+//!
+//! fn count_users<E: Executor<Database = Postgres>>(executor: E) -> i64 { .. }
+//!
+//! const RECORDING: &str = "tests/recordings/count_users.json";
+//!
+//! if std::env::var("DATABASE_URL").is_ok() {
+//!     let recorder = Recorder::new(PgPool::connect(&std::env::var("DATABASE_URL")?)?);
+//!     assert_eq!(count_users(&recorder), 2);
+//!     recorder.save(RECORDING)?;
+//! } else {
+//!     let replayer = Replayer::<Postgres>::open(RECORDING)?;
+//!     assert_eq!(count_users(&replayer), 2);
+//!     replayer.verify();
+//! }
+//! ```
+//!
+//! Statements are recorded in the order they start. Replaying statements that ran concurrently
+//! needs them to start in the same order again. Transactions and explicitly prepared statements
+//! are not recorded.
+
+use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::fs;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use either::Either;
+
+use crate::chan_stream;
+use crate::database::{Database, HasArguments, HasStatement};
+use crate::describe::Describe;
+use crate::error::{DatabaseError, Error, ErrorKind};
+use crate::executor::{Execute, Executor};
+use crate::io::chan_stream::ChanStream;
+use crate::pool::Pool;
+
+/// A [`Database`] whose traffic can be recorded and replayed.
+///
+/// The driver turns its arguments, rows and results into the driver-neutral parts of a
+/// [`Recording`], and builds its rows and results back from them.
+pub trait Replay: Database {
+    /// The columns of a result, built once and shared by its replayed rows.
+    type Columns: Send + 'static;
+
+    /// The bound arguments as they were encoded for the database.
+    fn record_arguments(arguments: &<Self as HasArguments<'_>>::Arguments) -> Vec<RecordedValue>;
+
+    /// The columns of `row`, recorded before the first row and whenever they change.
+    fn record_columns(row: &Self::Row) -> serde_json::Value;
+
+    /// The values of `row`, as received from the database.
+    fn record_row(row: &Self::Row) -> Vec<Option<String>>;
+
+    fn record_result(result: &Self::QueryResult) -> RecordedResult;
+
+    fn replay_columns(columns: &serde_json::Value) -> Result<Self::Columns, Error>;
+
+    fn replay_row(columns: &Self::Columns, values: &[Option<String>]) -> Result<Self::Row, Error>;
+
+    fn replay_result(result: &RecordedResult) -> Self::QueryResult;
+}
+
+/// The statements recorded by a [`Recorder`], in the order they started.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Recording {
+    pub statements: Vec<RecordedStatement>,
+}
+
+impl Recording {
+    /// Read a recording saved by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| {
+            Error::Configuration(format!("invalid recording {}: {}", path.display(), e).into())
+        })
+    }
+
+    /// Write the recording as JSON, creating the directories of `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::from)?;
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+}
+
+/// A statement of a [`Recording`] with everything the database answered.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordedStatement {
+    pub sql: String,
+    /// `None` for a statement run without arguments, e.g. `execute("BEGIN")`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Vec<RecordedValue>>,
+    #[serde(default)]
+    pub items: Vec<RecordedItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RecordedError>,
+}
+
+/// An argument as it was sent to the database.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordedValue {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// The encoded value as written by the driver, `None` for `NULL`.
+    pub value: Option<String>,
+}
+
+/// What the database sent for a statement, in order.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedItem {
+    /// The columns of the rows that follow.
+    Columns(serde_json::Value),
+    Row(Vec<Option<String>>),
+    Result(RecordedResult),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordedResult {
+    pub rows_affected: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_insert_id: Option<i64>,
+}
+
+/// The error a statement failed with.
+///
+/// A database error is replayed as a [`DatabaseError`] with the same message, code, kind and
+/// names, any other error as [`Error::Protocol`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordedError {
+    pub message: String,
+    /// `Some` for an error returned by the database.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ErrorKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+}
+
+impl RecordedError {
+    pub fn new(error: &Error) -> Self {
+        match error.as_database_error() {
+            Some(e) => Self {
+                message: e.message().to_owned(),
+                kind: Some(e.kind()),
+                code: e.code().map(Cow::into_owned),
+                constraint: e.constraint().map(ToOwned::to_owned),
+                table: e.table().map(ToOwned::to_owned),
+                column: e.column().map(ToOwned::to_owned),
+            },
+            None => Self {
+                message: error.to_string(),
+                kind: None,
+                code: None,
+                constraint: None,
+                table: None,
+                column: None,
+            },
+        }
+    }
+
+    pub fn to_error(&self) -> Error {
+        match self.kind {
+            Some(_) => Error::Database(Box::new(ReplayedDatabaseError(self.clone()))),
+            None => Error::Protocol(self.message.clone()),
+        }
+    }
+}
+
+/// A database error served by a [`Replayer`].
+#[derive(Debug)]
+pub struct ReplayedDatabaseError(RecordedError);
+
+impl Display for ReplayedDatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0.message)
+    }
+}
+
+impl StdError for ReplayedDatabaseError {}
+
+impl DatabaseError for ReplayedDatabaseError {
+    fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    fn code(&self) -> Option<Cow<'_, str>> {
+        self.0.code.as_deref().map(Cow::Borrowed)
+    }
+
+    #[doc(hidden)]
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> ErrorKind {
+        self.0.kind.unwrap_or(ErrorKind::Other)
+    }
+
+    fn constraint(&self) -> Option<&str> {
+        self.0.constraint.as_deref()
+    }
+
+    fn table(&self) -> Option<&str> {
+        self.0.table.as_deref()
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.0.column.as_deref()
+    }
+}
+
+/// The query handed on by a [`Recorder`], its arguments were taken to be recorded.
+struct Rebound<'q, DB: Database, E> {
+    query: E,
+    arguments: Option<<DB as HasArguments<'q>>::Arguments>,
+}
+
+impl<'q, DB: Database, E: Execute<'q, DB>> Execute<'q, DB> for Rebound<'q, DB, E> {
+    #[inline]
+    fn sql(&self) -> &str {
+        self.query.sql()
+    }
+
+    #[inline]
+    fn statement(&self) -> Option<&<DB as HasStatement>::Statement> {
+        self.query.statement()
+    }
+
+    #[inline]
+    fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
+        self.arguments.take()
+    }
+
+    #[inline]
+    fn persistent(&self) -> bool {
+        self.query.persistent()
+    }
+
+    #[inline]
+    fn timeout(&self) -> Option<Duration> {
+        self.query.timeout()
+    }
+}
+
+// a failed assertion of another test must not hide the recording
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// An [`Executor`] running the statements on a [`Pool`] and recording them, see the
+/// [module documentation](self).
+pub struct Recorder<DB: Replay> {
+    pool: Pool<DB>,
+    recording: Mutex<Recording>,
+}
+
+impl<DB: Replay> Recorder<DB> {
+    pub fn new(pool: Pool<DB>) -> Self {
+        Self {
+            pool,
+            recording: Mutex::new(Recording::default()),
+        }
+    }
+
+    pub fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+
+    /// The statements recorded so far.
+    pub fn recording(&self) -> Recording {
+        lock(&self.recording).clone()
+    }
+
+    /// Save the statements recorded so far, see [`Recording::save`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        lock(&self.recording).save(path)
+    }
+
+    fn start(&self, sql: &str, arguments: Option<Vec<RecordedValue>>) -> usize {
+        let mut recording = lock(&self.recording);
+        recording.statements.push(RecordedStatement {
+            sql: sql.to_owned(),
+            arguments,
+            items: Vec::new(),
+            error: None,
+        });
+        recording.statements.len() - 1
+    }
+
+    fn record(&self, index: usize, f: impl FnOnce(&mut RecordedStatement)) {
+        f(&mut lock(&self.recording).statements[index])
+    }
+
+    /// Record `row`, after its columns if they differ from the `last` ones.
+    fn record_row(&self, index: usize, last: &mut Option<serde_json::Value>, row: &DB::Row) {
+        let columns = DB::record_columns(row);
+        let values = DB::record_row(row);
+        self.record(index, |statement| {
+            if last.as_ref() != Some(&columns) {
+                statement.items.push(RecordedItem::Columns(columns.clone()));
+                *last = Some(columns);
+            }
+            statement.items.push(RecordedItem::Row(values));
+        })
+    }
+}
+
+impl<DB: Replay> Debug for Recorder<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("pool", &self.pool)
+            .field("statements", &lock(&self.recording).statements.len())
+            .finish()
+    }
+}
+
+impl<'r, DB: Replay> Executor for &'r Recorder<DB>
+    where
+            for<'c> &'c mut DB::Connection: Executor<Database = DB>,
+{
+    type Database = DB;
+
+    fn default_timeout(&self) -> Option<Duration> {
+        (&self.pool).default_timeout()
+    }
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
+        mut query: E,
+    ) -> ChanStream<'e, Either<DB::QueryResult, DB::Row>>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let arguments = query.take_arguments();
+        let index = self.start(query.sql(), arguments.as_ref().map(DB::record_arguments));
+        let recorder: &'r Recorder<DB> = *self;
+        let mut pool = &recorder.pool;
+        chan_stream! {
+            let mut stream = pool.fetch_many(Rebound { query, arguments });
+            let mut columns = None;
+            while let Some(item) = stream.next() {
+                match &item {
+                    Ok(Either::Left(result)) => {
+                        let result = DB::record_result(result);
+                        recorder.record(index, |statement| statement.items.push(RecordedItem::Result(result)));
+                    }
+                    Ok(Either::Right(row)) => recorder.record_row(index, &mut columns, row),
+                    Err(e) => {
+                        let error = RecordedError::new(e);
+                        recorder.record(index, |statement| statement.error = Some(error));
+                    }
+                }
+                r#yield!(item?);
+            }
+            end!();
+            Ok(())
+        }
+    }
+
+    fn fetch_optional<'q, E: 'q>(&mut self, mut query: E) -> Result<Option<DB::Row>, Error>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let arguments = query.take_arguments();
+        let index = self.start(query.sql(), arguments.as_ref().map(DB::record_arguments));
+        let mut pool = &self.pool;
+        match pool.fetch_optional(Rebound { query, arguments }) {
+            Ok(row) => {
+                if let Some(row) = &row {
+                    self.record_row(index, &mut None, row);
+                }
+                Ok(row)
+            }
+            Err(e) => {
+                let error = RecordedError::new(&e);
+                self.record(index, |statement| statement.error = Some(error));
+                Err(e)
+            }
+        }
+    }
+
+    fn prepare_with<'q>(
+        &mut self,
+        sql: &'q str,
+        parameters: &'q [DB::TypeInfo],
+    ) -> Result<<DB as HasStatement>::Statement, Error> {
+        (&self.pool).prepare_with(sql, parameters)
+    }
+
+    #[doc(hidden)]
+    fn describe(&mut self, sql: &str) -> Result<Describe<DB>, Error> {
+        (&self.pool).describe(sql)
+    }
+}
+
+/// An [`Executor`] serving the statements of a [`Recording`] without a database, see the
+/// [module documentation](self).
+pub struct Replayer<DB: Replay> {
+    statements: Mutex<VecDeque<RecordedStatement>>,
+    database: PhantomData<fn() -> DB>,
+}
+
+impl<DB: Replay> Replayer<DB> {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            statements: Mutex::new(recording.statements.into()),
+            database: PhantomData,
+        }
+    }
+
+    /// Replay the recording saved at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Recording::load(path).map(Self::new)
+    }
+
+    /// The number of recorded statements that were not replayed yet.
+    pub fn remaining(&self) -> usize {
+        lock(&self.statements).len()
+    }
+
+    /// Assert that every recorded statement was replayed.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the statements that did not run.
+    pub fn verify(&self) {
+        let remaining = lock(&self.statements)
+            .iter()
+            .map(|statement| statement.sql.clone())
+            .collect::<Vec<_>>();
+        if !remaining.is_empty() {
+            panic!("recorded statements were not replayed: {:#?}", remaining);
+        }
+    }
+
+    /// Take the next recorded statement, it must be the one the `query` runs.
+    fn next<'q, E: Execute<'q, DB>>(&self, query: &mut E) -> RecordedStatement {
+        let arguments = query.take_arguments();
+        let arguments = arguments.as_ref().map(DB::record_arguments);
+        let sql = query.sql();
+        let statement = lock(&self.statements).pop_front().unwrap_or_else(|| {
+            panic!("replay: no recorded statement is left for `{}`", sql)
+        });
+        if statement.sql != sql {
+            panic!(
+                "replay: statement differs from the recording\n recorded: `{}`\n      ran: `{}`",
+                statement.sql, sql
+            );
+        }
+        if statement.arguments != arguments {
+            panic!(
+                "replay: arguments of `{}` differ from the recording\n recorded: {:?}\n      ran: {:?}",
+                sql, statement.arguments, arguments
+            );
+        }
+        statement
+    }
+}
+
+impl<DB: Replay> Debug for Replayer<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replayer")
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+impl<'r, DB: Replay> Executor for &'r Replayer<DB> {
+    type Database = DB;
+
+    fn fetch_many<'e, 'q: 'e, E: 'q>(
+        &'e mut self,
+        mut query: E,
+    ) -> ChanStream<'e, Either<DB::QueryResult, DB::Row>>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let statement = self.next(&mut query);
+        chan_stream! {
+            let mut columns = None;
+            for item in &statement.items {
+                match item {
+                    RecordedItem::Columns(recorded) => columns = Some(DB::replay_columns(recorded)?),
+                    RecordedItem::Row(values) => {
+                        let columns = columns
+                            .as_ref()
+                            .ok_or_else(|| Error::Protocol("recorded row without columns".into()))?;
+                        r#yield!(Either::Right(DB::replay_row(columns, values)?));
+                    }
+                    RecordedItem::Result(result) => r#yield!(Either::Left(DB::replay_result(result))),
+                }
+            }
+            if let Some(error) = &statement.error {
+                return Err(error.to_error());
+            }
+            end!();
+            Ok(())
+        }
+    }
+
+    fn fetch_optional<'q, E: 'q>(&mut self, mut query: E) -> Result<Option<DB::Row>, Error>
+        where
+            E: Execute<'q, Self::Database>,
+    {
+        let statement = self.next(&mut query);
+        let mut columns = None;
+        for item in &statement.items {
+            match item {
+                RecordedItem::Columns(recorded) => columns = Some(DB::replay_columns(recorded)?),
+                RecordedItem::Row(values) => {
+                    let columns = columns
+                        .as_ref()
+                        .ok_or_else(|| Error::Protocol("recorded row without columns".into()))?;
+                    return DB::replay_row(columns, values).map(Some);
+                }
+                RecordedItem::Result(_) => {}
+            }
+        }
+        match &statement.error {
+            Some(error) => Err(error.to_error()),
+            None => Ok(None),
+        }
+    }
+
+    fn prepare_with<'q>(
+        &mut self,
+        sql: &'q str,
+        _parameters: &'q [DB::TypeInfo],
+    ) -> Result<<DB as HasStatement>::Statement, Error> {
+        Err(Error::Configuration(
+            format!("can't prepare `{}` while replaying a recording", sql).into(),
+        ))
+    }
+
+    #[doc(hidden)]
+    fn describe(&mut self, sql: &str) -> Result<Describe<DB>, Error> {
+        Err(Error::Configuration(
+            format!("can't describe `{}` while replaying a recording", sql).into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::{Error, ErrorKind};
+    use crate::replay::{RecordedError, RecordedItem, RecordedResult, RecordedStatement, Recording};
+
+    #[test]
+    fn test_recording_save_load() {
+        let recording = Recording {
+            statements: vec![RecordedStatement {
+                sql: "select 1".to_string(),
+                arguments: None,
+                items: vec![RecordedItem::Result(RecordedResult { rows_affected: 1, last_insert_id: None })],
+                error: Some(RecordedError {
+                    message: "duplicate key".to_string(),
+                    kind: Some(ErrorKind::UniqueViolation),
+                    code: Some("23505".to_string()),
+                    constraint: Some("user_name_key".to_string()),
+                    table: None,
+                    column: None,
+                }),
+            }],
+        };
+        let path = std::env::temp_dir().join(format!("cdbc_recording_{}.json", std::process::id()));
+        recording.save(&path).unwrap();
+        let loaded = Recording::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, recording);
+
+        let error = loaded.statements[0].error.as_ref().unwrap().to_error();
+        let db = error.as_database_error().unwrap();
+        assert_eq!(db.kind(), ErrorKind::UniqueViolation);
+        assert_eq!(db.code().as_deref(), Some("23505"));
+        assert_eq!(db.constraint(), Some("user_name_key"));
+        assert_eq!(RecordedError::new(&error), *loaded.statements[0].error.as_ref().unwrap());
+        assert!(matches!(RecordedError::new(&Error::PoolClosed).to_error(), Error::Protocol(_)));
+    }
+}
@@ -0,0 +1,175 @@
+//! Support of the `#[cdbc::test]` attribute: every test gets a fresh database and a pool to it.
+//!
+//! ```ignore
+//! #[cdbc::test(fixtures("users.sql"))]
+//! fn test_users(pool: Pool<Postgres>) {
+//!     let count: i64 = cdbc::query_scalar("select count(*) from users").fetch_one(&pool).unwrap();
+//!     assert_eq!(count, 3);
+//! }
+//! ```
+//!
+//! postgres, mysql and mssql create a temporary database on the server of `DATABASE_URL` and drop it
+//! after the test, postgres copies it from `template` (`template1` by default). sqlite uses a named
+//! in-memory database. `template` fails the test on the other databases.
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::connection::Connection;
+use crate::database::Database;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::pool::{Pool, PoolOptions};
+
+static TEST_DB_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// The arguments of one `#[cdbc::test]`, built by the macro.
+#[derive(Debug, Clone)]
+pub struct TestArgs {
+    /// `module_path::test_name`
+    pub test_path: &'static str,
+    /// the database to copy, only supported by postgres
+    pub template: Option<&'static str>,
+    /// `(file name, sql)`, applied in order before the test body
+    pub fixtures: Vec<(&'static str, &'static str)>,
+}
+
+impl TestArgs {
+    pub fn new(test_path: &'static str) -> Self {
+        TestArgs {
+            test_path,
+            template: None,
+            fixtures: vec![],
+        }
+    }
+
+    pub fn template(mut self, template: &'static str) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub fn fixtures(mut self, fixtures: &[(&'static str, &'static str)]) -> Self {
+        self.fixtures.extend_from_slice(fixtures);
+        self
+    }
+
+    /// fail with [`Error::Configuration`] if a `template` is set, for the databases without template databases
+    pub fn reject_template(&self, database: &str) -> Result<(), Error> {
+        match self.template {
+            Some(template) => Err(Error::Configuration(
+                format!("`template = {:?}` is not supported by {}, only by postgres", template, database).into(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The database created for one test.
+pub struct TestContext<DB: Database> {
+    pub db_name: String,
+    /// options connecting to the test database
+    pub connect_options: <DB::Connection as Connection>::Options,
+    /// options connecting to the server, used to drop the test database
+    pub master_options: <DB::Connection as Connection>::Options,
+    /// a connection kept open for the lifetime of the test, e.g. to keep an in-memory database alive
+    pub keep_alive: Option<DB::Connection>,
+}
+
+/// Implemented by the [`Database`] of each driver to create and drop test databases.
+pub trait TestSupport: Database {
+    fn test_context(args: &TestArgs) -> Result<TestContext<Self>, Error>;
+
+    /// drop the test database, connecting with `master_options`
+    fn cleanup_test(db_name: &str, master_options: &<Self::Connection as Connection>::Options) -> Result<(), Error>;
+}
+
+/// a unique database name, short enough for every database
+pub fn test_db_name() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    format!(
+        "_cdbc_test_{}_{}_{:x}",
+        std::process::id(),
+        TEST_DB_SEQ.fetch_add(1, Ordering::Relaxed),
+        nanos
+    )
+}
+
+/// the server url of postgres, mysql and mssql tests
+pub fn database_url() -> Result<String, Error> {
+    std::env::var("DATABASE_URL").map_err(|_| {
+        Error::Configuration("`DATABASE_URL` must be set to run database tests".into())
+    })
+}
+
+/// The return type of a test body, `()` or a `Result` whose error fails the test.
+pub trait TestResult {
+    fn into_result(self) -> Result<(), String>;
+}
+
+impl TestResult for () {
+    fn into_result(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<T, E: Debug> TestResult for Result<T, E> {
+    fn into_result(self) -> Result<(), String> {
+        self.map(|_| ()).map_err(|e| format!("{:?}", e))
+    }
+}
+
+/// Run `f` in a coroutine.
+pub fn run_test_body<F, R>(args: TestArgs, f: F)
+    where F: FnOnce() -> R + Send + 'static,
+          R: TestResult + Send + 'static {
+    let handle = unsafe {
+        mco::coroutine::Builder::new()
+            .name(args.test_path.to_string())
+            .spawn(move || f().into_result())
+    };
+    match handle.join() {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => panic!("test failed: {}", e),
+        Err(panic) => panic::resume_unwind(panic),
+    }
+}
+
+/// Create the test database, apply the fixtures and run `f` with a pool to it in a coroutine.
+/// The database is dropped afterwards, also when the test fails.
+pub fn run_test<DB, F, R>(args: TestArgs, f: F)
+    where DB: TestSupport,
+          F: FnOnce(Pool<DB>) -> R + Send + 'static,
+          R: TestResult + Send + 'static {
+    let TestContext {
+        db_name,
+        connect_options,
+        master_options,
+        keep_alive,
+    } = DB::test_context(&args).unwrap_or_else(|e| panic!("create test database: {}", e));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let pool = PoolOptions::<DB>::new()
+            .connect_with(connect_options)
+            .unwrap_or_else(|e| panic!("connect test database: {}", e));
+        {
+            let mut conn = pool.acquire().unwrap_or_else(|e| panic!("connect test database: {}", e));
+            for (name, sql) in &args.fixtures {
+                if let Err(e) = conn.execute(*sql) {
+                    panic!("apply fixture {}: {}", name, e);
+                }
+            }
+        }
+        let test_pool = pool.clone();
+        run_test_body(args, move || f(test_pool));
+        pool.close();
+    }));
+    drop(keep_alive);
+    if let Err(e) = DB::cleanup_test(&db_name, &master_options) {
+        log::warn!("drop test database: {}", e);
+    }
+    if let Err(panic) = result {
+        panic::resume_unwind(panic);
+    }
+}
@@ -1,8 +1,12 @@
 use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
 use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hasher};
 use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
 
 
+use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
 use crate::pool::MaybePoolConnection;
@@ -17,6 +21,17 @@ pub trait TransactionManager {
     /// Begin a new transaction or establish a savepoint within the active transaction.
     fn begin(
         conn: &mut <Self::Database as Database>::Connection,
+    ) -> Result<(), Error> {
+        Self::begin_with(conn, &TransactionOptions::default())
+    }
+
+    /// Begin a new transaction with the given options or establish a savepoint
+    /// within the active transaction.
+    ///
+    /// Savepoints can't take options, see [`check_savepoint_options`].
+    fn begin_with(
+        conn: &mut <Self::Database as Database>::Connection,
+        options: &TransactionOptions,
     ) -> Result<(), Error>;
 
     /// Commit the active transaction or release the most recent savepoint.
@@ -31,6 +46,305 @@ pub trait TransactionManager {
 
     /// Starts to abort the active transaction or restore from the most recent snapshot.
     fn start_rollback(conn: &mut <Self::Database as Database>::Connection);
+
+    /// Establish the named savepoint within the active transaction.
+    fn savepoint(
+        conn: &mut <Self::Database as Database>::Connection,
+        name: &str,
+    ) -> Result<(), Error>;
+
+    /// Release the named savepoint, keeping what was done since it was established.
+    fn release_savepoint(
+        conn: &mut <Self::Database as Database>::Connection,
+        name: &str,
+    ) -> Result<(), Error>;
+
+    /// Undo what was done since the named savepoint was established, the savepoint stays.
+    fn rollback_to_savepoint(
+        conn: &mut <Self::Database as Database>::Connection,
+        name: &str,
+    ) -> Result<(), Error>;
+}
+
+/// The isolation level of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+    /// MSSQL's `SNAPSHOT` isolation.
+    ///
+    /// Postgres runs it as `REPEATABLE READ` (which is snapshot isolation there) and MySQL
+    /// as `REPEATABLE READ` started `WITH CONSISTENT SNAPSHOT`.
+    Snapshot,
+}
+
+impl IsolationLevel {
+    /// The name of the level in `SET TRANSACTION ISOLATION LEVEL`.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+            IsolationLevel::Snapshot => "SNAPSHOT",
+        }
+    }
+}
+
+/// Whether a transaction may write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessMode {
+    ReadWrite,
+    ReadOnly,
+}
+
+impl AccessMode {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            AccessMode::ReadWrite => "READ WRITE",
+            AccessMode::ReadOnly => "READ ONLY",
+        }
+    }
+}
+
+/// Options of a new transaction, see [`Connection::begin_with`].
+///
+/// Options left unset use the defaults of the database. Each database translates them into
+/// its own syntax:
+///
+/// * Postgres: `BEGIN ISOLATION LEVEL .. READ ONLY DEFERRABLE`
+/// * MySQL: `SET TRANSACTION ISOLATION LEVEL .., READ ONLY` before `START TRANSACTION`
+/// * SQLite: `BEGIN IMMEDIATE` for [`AccessMode::ReadWrite`], `BEGIN EXCLUSIVE` for
+///   [`IsolationLevel::Serializable`], the other levels are weaker than what SQLite always
+///   provides and run a plain `BEGIN`
+/// * MSSQL: `SET TRANSACTION ISOLATION LEVEL ..` before `BEGIN TRAN`, the level is reset to
+///   `READ COMMITTED` when the transaction ends
+///
+/// SQLite and MSSQL have no read-only transactions, asking for one is an error there.
+/// `DEFERRABLE` is only understood by Postgres and ignored by the other databases.
+///
+/// [`Connection::begin_with`]: crate::connection::Connection::begin_with()
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TransactionOptions {
+    pub isolation_level: Option<IsolationLevel>,
+    pub access_mode: Option<AccessMode>,
+    pub deferrable: bool,
+}
+
+impl TransactionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn isolation_level(mut self, level: IsolationLevel) -> Self {
+        self.isolation_level = Some(level);
+        self
+    }
+
+    pub fn access_mode(mut self, mode: AccessMode) -> Self {
+        self.access_mode = Some(mode);
+        self
+    }
+
+    pub fn read_only(self) -> Self {
+        self.access_mode(AccessMode::ReadOnly)
+    }
+
+    pub fn read_write(self) -> Self {
+        self.access_mode(AccessMode::ReadWrite)
+    }
+
+    /// Postgres' `DEFERRABLE`, a `SERIALIZABLE READ ONLY` transaction waits for a snapshot
+    /// that can't cause a serialization failure instead.
+    pub fn deferrable(mut self, deferrable: bool) -> Self {
+        self.deferrable = deferrable;
+        self
+    }
+
+    /// `true` if no option is set, a plain `BEGIN`.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How [`Connection::transaction_with_retry`] runs a transaction again that failed with
+/// a retryable error (see [`Error::is_retryable`]).
+///
+/// The delay before attempt `n + 1` is a random duration up to
+/// `min(max_backoff, initial_backoff * 2^(n - 1))` ("full jitter"), so transactions that
+/// conflicted with each other don't collide again.
+///
+/// [`Connection::transaction_with_retry`]: crate::connection::Connection::transaction_with_retry()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Wait a random part of the backoff instead of all of it.
+    pub jitter: bool,
+    /// The options every attempt begins its transaction with.
+    pub options: TransactionOptions,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            jitter: true,
+            options: TransactionOptions::default(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn options(mut self, options: TransactionOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The longest delay after the failed `attempt` (starting at 1).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        self.initial_backoff
+            .checked_mul(1 << exp)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+
+    /// The delay before running the transaction again, or `None` to give up and return `err`.
+    ///
+    /// Gives up once `max_attempts` is reached, on errors that are not retryable, and if the
+    /// deadline of the coroutine would pass while waiting.
+    pub fn retry_after(&self, attempt: u32, err: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts || !err.is_retryable() {
+            return None;
+        }
+        let mut delay = self.backoff(attempt);
+        if self.jitter {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u32(attempt);
+            let nanos = delay.as_nanos() as u64;
+            delay = Duration::from_nanos(hasher.finish() % nanos.saturating_add(1));
+        }
+        match crate::deadline::deadline() {
+            Some(deadline) if Instant::now() + delay >= deadline => None,
+            _ => Some(delay),
+        }
+    }
+}
+
+type Callback = Box<dyn FnOnce() + Send + 'static>;
+
+/// The callbacks registered with [`Transaction::on_commit`] and [`Transaction::on_rollback`].
+///
+/// Nested transactions are separate [`Transaction`]s on the same connection, so the callbacks
+/// are kept by the connection, one level for each open transaction or savepoint, see
+/// [`Connection::transaction_callbacks`](crate::connection::Connection::transaction_callbacks).
+#[doc(hidden)]
+#[derive(Default)]
+pub struct TransactionCallbacks {
+    levels: Vec<CallbackLevel>,
+}
+
+#[derive(Default)]
+struct CallbackLevel {
+    on_commit: Vec<Callback>,
+    on_rollback: Vec<Callback>,
+}
+
+impl TransactionCallbacks {
+    fn begin(&mut self) {
+        self.levels.push(CallbackLevel::default());
+    }
+
+    fn push(&mut self, on_commit: Option<Callback>, on_rollback: Option<Callback>) {
+        if let Some(level) = self.levels.last_mut() {
+            level.on_commit.extend(on_commit);
+            level.on_rollback.extend(on_rollback);
+        }
+    }
+
+    /// The callbacks to run after a commit, a savepoint hands its callbacks to the
+    /// enclosing transaction instead.
+    fn commit(&mut self) -> Vec<Callback> {
+        let level = match self.levels.pop() {
+            Some(level) => level,
+            None => return Vec::new(),
+        };
+        match self.levels.last_mut() {
+            Some(parent) => {
+                parent.on_commit.extend(level.on_commit);
+                parent.on_rollback.extend(level.on_rollback);
+                Vec::new()
+            }
+            None => level.on_commit,
+        }
+    }
+
+    /// The callbacks to run after a rollback, the callbacks of a savepoint are discarded.
+    fn rollback(&mut self) -> Vec<Callback> {
+        match self.levels.pop() {
+            Some(level) if self.levels.is_empty() => level.on_rollback,
+            _ => Vec::new(),
+        }
+    }
+
+    /// The number of callbacks registered in the current level, see [`truncate`](Self::truncate).
+    fn mark(&self) -> (usize, usize) {
+        self.levels
+            .last()
+            .map_or((0, 0), |level| (level.on_commit.len(), level.on_rollback.len()))
+    }
+
+    /// Discard the callbacks registered since `mark`, when a named savepoint is rolled back to.
+    fn truncate(&mut self, mark: (usize, usize)) {
+        if let Some(level) = self.levels.last_mut() {
+            level.on_commit.truncate(mark.0);
+            level.on_rollback.truncate(mark.1);
+        }
+    }
+}
+
+impl Debug for TransactionCallbacks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionCallbacks")
+            .field("depth", &self.levels.len())
+            .finish()
+    }
+}
+
+fn run_callbacks(callbacks: Vec<Callback>) {
+    for callback in callbacks {
+        callback();
+    }
 }
 
 /// An in-progress database transaction or savepoint.
@@ -55,6 +369,8 @@ pub struct Transaction<'c, DB>
 {
     connection: MaybePoolConnection<'c, DB>,
     open: bool,
+    // used if the connection doesn't keep the callbacks
+    callbacks: TransactionCallbacks,
 }
 
 impl<'c, DB> Transaction<'c, DB>
@@ -68,37 +384,235 @@ impl<'c, DB> Transaction<'c, DB>
 
         DB::TransactionManager::begin(&mut conn)?;
 
-        Ok(Self {
+        let mut transaction = Self {
             connection: conn,
             open: true,
-        })
+            callbacks: TransactionCallbacks::default(),
+        };
+        transaction.callbacks().begin();
+        Ok(transaction)
+    }
+
+    /// Begin a new transaction with the given options, see [`TransactionOptions`].
+    pub fn begin_with(
+        conn: impl Into<MaybePoolConnection<'c, DB>>,
+        options: TransactionOptions,
+    ) -> Result<Self, Error> {
+        let mut conn = conn.into();
+
+        DB::TransactionManager::begin_with(&mut conn, &options)?;
+
+        let mut transaction = Self {
+            connection: conn,
+            open: true,
+            callbacks: TransactionCallbacks::default(),
+        };
+        transaction.callbacks().begin();
+        Ok(transaction)
     }
 
     /// Commits this transaction or savepoint.
+    ///
+    /// Runs the [`on_commit`](Self::on_commit) callbacks if this is the outermost transaction.
     pub fn commit(&mut self) -> Result<(), Error> {
         if self.open == false {
             return Ok(());
         }
         DB::TransactionManager::commit(&mut self.connection)?;
         self.open = false;
+        let callbacks = self.callbacks().commit();
+        run_callbacks(callbacks);
 
         Ok(())
     }
 
     /// Aborts this transaction or savepoint.
+    ///
+    /// Runs the [`on_rollback`](Self::on_rollback) callbacks if this is the outermost transaction.
     pub fn rollback(&mut self) -> Result<(), Error> {
         if self.open == false {
             return Ok(());
         }
         DB::TransactionManager::rollback(&mut self.connection)?;
         self.open = false;
+        let callbacks = self.callbacks().rollback();
+        run_callbacks(callbacks);
 
         Ok(())
     }
 
+    /// Register a callback to run after the outermost transaction commits,
+    /// e.g. to publish events or invalidate caches.
+    ///
+    /// It is discarded if the transaction (or the savepoint it was registered in)
+    /// is rolled back. Registering on a finished transaction does nothing.
+    pub fn on_commit(&mut self, callback: impl FnOnce() + Send + 'static) {
+        if self.open {
+            self.callbacks().push(Some(Box::new(callback)), None);
+        }
+    }
+
+    /// Register a callback to run after the outermost transaction is rolled back,
+    /// including the implicit rollback when it is dropped.
+    ///
+    /// A dropped transaction may only queue its rollback, which is sent with the next use of
+    /// the connection (e.g. when it is returned to the pool). The callbacks run when the
+    /// rollback is queued, they don't wait for it to complete.
+    ///
+    /// It is discarded if the transaction commits, or if the savepoint it was registered
+    /// in is rolled back. Registering on a finished transaction does nothing.
+    pub fn on_rollback(&mut self, callback: impl FnOnce() + Send + 'static) {
+        if self.open {
+            self.callbacks().push(None, Some(Box::new(callback)));
+        }
+    }
+
+    /// The callbacks of the connection, or of this transaction alone if the connection
+    /// doesn't keep them.
+    fn callbacks(&mut self) -> &mut TransactionCallbacks {
+        match self.connection.transaction_callbacks() {
+            Some(callbacks) => callbacks,
+            None => &mut self.callbacks,
+        }
+    }
+
     pub fn is_done(&self) -> bool{
         !self.open
     }
+
+    /// Establish a named savepoint within this transaction.
+    ///
+    /// The returned [`Savepoint`] runs queries in this transaction (through `Deref` to the
+    /// connection, so it can't commit or roll back the transaction itself), and can
+    /// [`rollback_to`](Savepoint::rollback_to) the savepoint any number of times before it
+    /// is [`release`](Savepoint::release)d. A savepoint that is dropped without being released
+    /// is rolled back to and released.
+    ///
+    /// The name must be a plain identifier (letters, digits and `_`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut tx = conn.begin()?;
+    /// for row in rows {
+    ///     let mut sp = tx.savepoint("import_row")?;
+    ///     match insert(&mut *sp, row) {
+    ///         Ok(_) => sp.release()?,
+    ///         // skip the row, keep the rest of the import
+    ///         Err(_) => drop(sp),
+    ///     }
+    /// }
+    /// tx.commit()?;
+    /// ```
+    pub fn savepoint(&mut self, name: &str) -> Result<Savepoint<'_, 'c, DB>, Error> {
+        check_savepoint_name(name)?;
+        DB::TransactionManager::savepoint(&mut self.connection, name)?;
+        let callbacks = self.callbacks().mark();
+        Ok(Savepoint {
+            transaction: self,
+            name: name.to_owned(),
+            callbacks,
+            open: true,
+        })
+    }
+}
+
+/// A named savepoint within a [`Transaction`], see [`Transaction::savepoint`].
+pub struct Savepoint<'t, 'c, DB>
+    where
+        DB: Database,
+{
+    transaction: &'t mut Transaction<'c, DB>,
+    name: String,
+    // the callbacks registered before this savepoint
+    callbacks: (usize, usize),
+    open: bool,
+}
+
+impl<'t, 'c, DB> Savepoint<'t, 'c, DB>
+    where
+        DB: Database,
+{
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Undo everything done since this savepoint was established,
+    /// and discard the callbacks registered since.
+    ///
+    /// The savepoint stays established, so it can be rolled back to again or released.
+    pub fn rollback_to(&mut self) -> Result<(), Error> {
+        DB::TransactionManager::rollback_to_savepoint(&mut self.transaction.connection, &self.name)?;
+        self.transaction.callbacks().truncate(self.callbacks);
+        Ok(())
+    }
+
+    /// Release this savepoint, keeping what was done since it was established
+    /// as part of the transaction.
+    pub fn release(mut self) -> Result<(), Error> {
+        self.open = false;
+        DB::TransactionManager::release_savepoint(&mut self.transaction.connection, &self.name)
+    }
+
+    /// Register a callback to run after the outermost transaction commits,
+    /// it is discarded if this savepoint is rolled back to, see [`Transaction::on_commit`].
+    pub fn on_commit(&mut self, callback: impl FnOnce() + Send + 'static) {
+        self.transaction.on_commit(callback)
+    }
+
+    /// Register a callback to run after the outermost transaction is rolled back,
+    /// it is discarded if this savepoint is rolled back to, see [`Transaction::on_rollback`].
+    pub fn on_rollback(&mut self, callback: impl FnOnce() + Send + 'static) {
+        self.transaction.on_rollback(callback)
+    }
+}
+
+impl<'t, 'c, DB> Debug for Savepoint<'t, 'c, DB>
+    where
+        DB: Database,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Savepoint").field("name", &self.name).finish()
+    }
+}
+
+impl<'t, 'c, DB> Deref for Savepoint<'t, 'c, DB>
+    where
+        DB: Database,
+{
+    type Target = DB::Connection;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.transaction.connection
+    }
+}
+
+impl<'t, 'c, DB> DerefMut for Savepoint<'t, 'c, DB>
+    where
+        DB: Database,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.transaction.connection
+    }
+}
+
+impl<'t, 'c, DB> Drop for Savepoint<'t, 'c, DB>
+    where
+        DB: Database,
+{
+    fn drop(&mut self) {
+        if self.open {
+            self.transaction.callbacks().truncate(self.callbacks);
+            let conn = &mut self.transaction.connection;
+            let res = DB::TransactionManager::rollback_to_savepoint(conn, &self.name)
+                .and_then(|_| DB::TransactionManager::release_savepoint(conn, &self.name));
+            if let Err(e) = res {
+                log::warn!("error occurred while rolling back savepoint {}: {}", self.name, e);
+            }
+        }
+    }
 }
 
 impl<'c, DB> Debug for Transaction<'c, DB>
@@ -146,10 +660,55 @@ impl<'c, DB> Drop for Transaction<'c, DB>
             // connection (including if the connection is returned to a pool)
 
             DB::TransactionManager::start_rollback(&mut self.connection);
+            // the rollback may only be queued, the callbacks don't wait for it
+            let callbacks = self.callbacks().rollback();
+            run_callbacks(callbacks);
         }
     }
 }
 
+/// Fails if `options` are given for a savepoint (`depth > 0`),
+/// the characteristics of a transaction can only be set when it starts.
+#[allow(dead_code)]
+pub fn check_savepoint_options(depth: usize, options: &TransactionOptions) -> Result<(), Error> {
+    if depth > 0 && !options.is_default() {
+        return Err(Error::Configuration(
+            "transaction options can only be set on the outermost transaction, not on a savepoint".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// A savepoint name is written into the statement as is, so it must be a plain identifier.
+pub fn check_savepoint_name(name: &str) -> Result<(), Error> {
+    let mut chars = name.chars();
+    let valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(Error::Configuration(
+            format!("invalid savepoint name `{}`, expected an identifier", name).into(),
+        ));
+    }
+    Ok(())
+}
+
+// the name is quoted, so keywords can be used as well
+
+#[allow(dead_code)]
+pub fn savepoint_ansi_sql(name: &str) -> String {
+    format!("SAVEPOINT \"{}\"", name)
+}
+
+#[allow(dead_code)]
+pub fn release_savepoint_ansi_sql(name: &str) -> String {
+    format!("RELEASE SAVEPOINT \"{}\"", name)
+}
+
+#[allow(dead_code)]
+pub fn rollback_to_savepoint_ansi_sql(name: &str) -> String {
+    format!("ROLLBACK TO SAVEPOINT \"{}\"", name)
+}
+
 #[allow(dead_code)]
 pub fn begin_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
     if depth == 0 {
@@ -179,3 +738,68 @@ pub fn rollback_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use crate::error::Error;
+    use std::sync::{Arc, Mutex};
+    use crate::transaction::{check_savepoint_name, RetryPolicy, TransactionCallbacks};
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::new()
+            .max_attempts(4)
+            .initial_backoff(Duration::from_millis(10))
+            .max_backoff(Duration::from_millis(30));
+        assert_eq!(policy.backoff(1), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(20));
+        assert_eq!(policy.backoff(3), Duration::from_millis(30));
+        assert_eq!(policy.backoff(100), Duration::from_millis(30));
+
+        // only errors of concurrent transactions are retried
+        assert_eq!(policy.retry_after(1, &Error::RowNotFound), None);
+        assert_eq!(policy.retry_after(1, &Error::PoolTimedOut), None);
+    }
+
+    #[test]
+    fn test_check_savepoint_name() {
+        assert!(check_savepoint_name("import_row_1").is_ok());
+        assert!(check_savepoint_name("_sp").is_ok());
+        assert!(check_savepoint_name("").is_err());
+        assert!(check_savepoint_name("1sp").is_err());
+        assert!(check_savepoint_name("sp; drop table users").is_err());
+    }
+
+    #[test]
+    fn test_transaction_callbacks() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            Some(Box::new(move || log.lock().unwrap().push(name)) as Box<dyn FnOnce() + Send>)
+        };
+        let mut callbacks = TransactionCallbacks::default();
+        callbacks.begin();
+        callbacks.push(push("commit outer"), push("rollback outer"));
+
+        // a released savepoint hands its callbacks to the transaction
+        callbacks.begin();
+        callbacks.push(push("commit released"), None);
+        assert!(callbacks.commit().is_empty());
+
+        // a rolled back savepoint discards them
+        callbacks.begin();
+        callbacks.push(push("commit rolled back"), push("rollback rolled back"));
+        assert!(callbacks.rollback().is_empty());
+
+        // so does rolling back to a named savepoint
+        let mark = callbacks.mark();
+        callbacks.push(push("commit named"), None);
+        callbacks.truncate(mark);
+
+        for callback in callbacks.commit() {
+            callback();
+        }
+        assert_eq!(*log.lock().unwrap(), vec!["commit outer", "commit released"]);
+    }
+}
@@ -7,6 +7,8 @@ use crate::{Encode, Executor, Query};
 use crate::arguments::Arguments;
 use crate::scan::Scan;
 use crate::types::Type;
+use crate::QueryBuilder;
+use std::any::Any;
 
 
 pub trait Table {
@@ -53,16 +55,454 @@ pub trait Table {
 }
 
 
+/// A [`Table`] with a primary key, the `#[crud(id)]` fields of a `#[cdbc::crud]` struct.
+pub trait TableId: Table {
+    /// The value of the key, a tuple for a key of several columns.
+    type Id;
+
+    fn id_columns() -> &'static [&'static str];
+}
+
 pub trait CRUD<T: Table> {
     fn insert(&mut self, arg: T) -> Result<(String, u64)> {
         self.inserts(vec![arg])
     }
     fn inserts(&mut self, arg: Vec<T>) -> Result<(String, u64)> where T: Sized;
-    fn update(&mut self, arg: T, r#where: &str) -> Result<u64> {
+    fn update(&mut self, arg: T, r#where: Condition<T>) -> Result<u64> {
         self.updates(vec![arg], r#where)
     }
-    fn updates(&mut self, arg: Vec<T>, r#where: &str) -> Result<u64> where T: Sized;
-    fn find(&mut self, r#where: &str) -> Result<T> where T: Sized;
-    fn finds(&mut self, r#where: &str) -> Result<Vec<T>> where T: Sized;
-    fn delete(&mut self, r#where: &str) -> Result<u64> where;
-}
\ No newline at end of file
+    fn updates(&mut self, arg: Vec<T>, r#where: Condition<T>) -> Result<u64> where T: Sized;
+    fn find(&mut self, r#where: Condition<T>) -> Result<T> where T: Sized;
+    fn finds(&mut self, r#where: Condition<T>) -> Result<Vec<T>> where T: Sized;
+    fn delete(&mut self, r#where: Condition<T>) -> Result<u64> where;
+
+    /// The number of rows matching `where`.
+    fn count(&mut self, r#where: Condition<T>) -> Result<u64>;
+
+    /// Whether a row matches `where`.
+    fn exists(&mut self, r#where: Condition<T>) -> Result<bool>;
+
+    /// Keyset pagination, the first `limit` rows ordered by `cursor` with a value greater than
+    /// `last`(the value of the last row of the previous page, `None` for the first page).
+    fn find_after<V>(&mut self, cursor: Field<T, V>, last: Option<V>, limit: u64) -> Result<Vec<T>>
+        where T: Sized,
+              V: Clone + Send + Sync + 'static;
+
+    /// The page of the rows matching `where` in the order of [`Condition::order_by`], and the
+    /// number of all the rows matching it. Without an order the rows are ordered by the key of a
+    /// table with `#[crud(id)]` fields, a table without a key fails with
+    /// [`Error::InvalidArgument`](crate::Error::InvalidArgument).
+    ///
+    /// The number and the page are two statements, rows changed in between may be counted but
+    /// not on the page or the other way around. Run it in a transaction of a snapshot isolation
+    /// level(e.g. `repeatable read`) for both to see the same rows.
+    fn find_page(&mut self, r#where: Condition<T>, page: PageRequest) -> Result<Page<T>>;
+}
+
+/// The [`CRUD`] methods by the key of a [`TableId`], implemented for the tables with
+/// `#[crud(id)]` fields.
+pub trait CRUDById<T: TableId>: CRUD<T> {
+    /// Find the row with the key `id`, [`Error::RowNotFound`](crate::Error::RowNotFound) if there is none.
+    fn find_by_id(&mut self, id: T::Id) -> Result<T>;
+
+    /// Update the row with the key of `arg`, setting its fields that are `Some`.
+    /// [`Error::InvalidArgument`](crate::Error::InvalidArgument) if a key field of `arg` is `None`.
+    fn update_by_id(&mut self, arg: T) -> Result<u64>;
+
+    fn delete_by_id(&mut self, id: T::Id) -> Result<u64>;
+}
+
+/// The page `page_no`(from 1) of `page_size` rows of [`CRUD::find_page`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PageRequest {
+    pub page_no: u64,
+    pub page_size: u64,
+}
+
+impl PageRequest {
+    pub fn new(page_no: u64, page_size: u64) -> Self {
+        PageRequest { page_no, page_size }
+    }
+
+    /// the rows before the page, page 0 is taken as page 1
+    pub fn offset(&self) -> u64 {
+        self.page_no.max(1).saturating_sub(1).saturating_mul(self.page_size)
+    }
+}
+
+/// The rows of a [`PageRequest`] and the number of all the rows.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Page<T> {
+    pub records: Vec<T>,
+    pub total: u64,
+    pub page_no: u64,
+    pub page_size: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(page: PageRequest, total: u64, records: Vec<T>) -> Self {
+        Page {
+            records,
+            total,
+            page_no: page.page_no.max(1),
+            page_size: page.page_size,
+        }
+    }
+
+    /// the number of pages of the total rows
+    pub fn pages(&self) -> u64 {
+        if self.page_size == 0 {
+            return 0;
+        }
+        self.total.div_ceil(self.page_size)
+    }
+}
+
+/// the value of a condition, produced again for every statement the condition is pushed to
+type Value = Box<dyn Fn() -> Box<dyn Any + Send> + Send + Sync>;
+
+enum Node {
+    Raw(String),
+    Compare(usize, &'static str, Value),
+    In(usize, Vec<Value>),
+    Null(usize, &'static str),
+    /// `and`/`or` of the nodes
+    Group(&'static str, Vec<Node>),
+}
+
+/// A part of the sql of a [`Condition`], the sql or a value bound to the column of the index.
+#[doc(hidden)]
+pub enum Part<'a> {
+    Sql(&'a str),
+    Bind(usize, Box<dyn Any + Send>),
+}
+
+/// The where clause of the [`CRUD`] methods, built from the [`Field`]s of a `#[cdbc::crud]` struct:
+/// ```ignore
+/// let w = BizActivity::name().like("%tom%").and(BizActivity::age().gt(18).or(BizActivity::delete_flag().is_null()));
+/// let v: Vec<BizActivity> = CRUD::finds(&mut pool, w)?;
+/// ```
+/// The values are bound to the placeholders of the database, the columns quoted for it.
+/// [`Condition::raw`] is pasted into the sql as is, never build it from untrusted input.
+///
+/// [`Condition::order_by`] orders the rows of `find`, `finds` and `find_page`, the other methods
+/// ignore it.
+pub struct Condition<T> {
+    node: Option<Node>,
+    /// the index of the column and `true` for a descending order
+    order: Vec<(usize, bool)>,
+    table: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for Condition<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Condition<T> {
+    /// The empty condition, every row.
+    pub fn new() -> Self {
+        Condition { node: None, order: vec![], table: PhantomData }
+    }
+
+    /// The sql `sql`, not escaped.
+    pub fn raw(sql: impl Into<String>) -> Self {
+        let sql = sql.into();
+        if sql.trim().is_empty() {
+            return Self::new();
+        }
+        Self::from_node(Node::Raw(sql))
+    }
+
+    fn from_node(node: Node) -> Self {
+        Condition { node: Some(node), order: vec![], table: PhantomData }
+    }
+
+    /// `true` if the condition matches every row, whatever its order
+    pub fn is_empty(&self) -> bool {
+        self.node.is_none()
+    }
+
+    /// Order the rows by `field` after the fields ordered before.
+    pub fn order_by<V>(mut self, field: Field<T, V>) -> Self {
+        self.order.push((field.index, false));
+        self
+    }
+
+    /// Order the rows by `field` descending after the fields ordered before.
+    pub fn order_by_desc<V>(mut self, field: Field<T, V>) -> Self {
+        self.order.push((field.index, true));
+        self
+    }
+
+    /// `true` if [`Condition::order_by`] was given
+    pub fn is_ordered(&self) -> bool {
+        !self.order.is_empty()
+    }
+
+    /// `self and other`
+    pub fn and(self, other: Condition<T>) -> Self {
+        self.group("and", other)
+    }
+
+    /// `self or other`
+    pub fn or(self, other: Condition<T>) -> Self {
+        self.group("or", other)
+    }
+
+    /// the orders of `self` come before the ones of `other`
+    fn group(mut self, op: &'static str, mut other: Condition<T>) -> Self {
+        self.order.append(&mut other.order);
+        let node = match (self.node, other.node) {
+            (Some(Node::Group(o, mut nodes)), Some(other)) if o == op => {
+                nodes.push(other);
+                Some(Node::Group(op, nodes))
+            }
+            (Some(node), Some(other)) => Some(Node::Group(op, vec![node, other])),
+            (node, None) | (None, node) => node,
+        };
+        Condition { node, order: self.order, table: PhantomData }
+    }
+
+    /// Push the ` where ..` of the condition(nothing if it is empty) to `q`, `columns` are the
+    /// quoted columns of the fields, `bind` binds a value of the field of an index.
+    #[doc(hidden)]
+    pub fn push_where<'args, DB: Database>(
+        &self,
+        q: &mut QueryBuilder<'args, DB>,
+        columns: &[&str],
+        bind: impl Fn(&mut QueryBuilder<'args, DB>, usize, Box<dyn Any + Send>),
+    ) {
+        if let Some(node) = &self.node {
+            q.push(" where ");
+            node.render(columns, false, &mut |part| match part {
+                Part::Sql(sql) => {
+                    q.push(sql);
+                }
+                Part::Bind(column, value) => bind(q, column, value),
+            });
+        }
+    }
+
+    /// Push the ` order by ..` of [`Condition::order_by`] to `q`, `default` if there is none.
+    #[doc(hidden)]
+    pub fn push_order<DB: Database>(&self, q: &mut QueryBuilder<'_, DB>, columns: &[&str], default: &str) {
+        q.push(self.order_sql(columns, default));
+    }
+
+    fn order_sql(&self, columns: &[&str], default: &str) -> String {
+        if self.order.is_empty() {
+            return default.to_string();
+        }
+        let order = self
+            .order
+            .iter()
+            .map(|(column, desc)| format!("{}{}", columns[*column], if *desc { " desc" } else { "" }))
+            .collect::<Vec<_>>();
+        format!(" order by {}", order.join(","))
+    }
+}
+
+impl Node {
+    fn render(&self, columns: &[&str], nested: bool, out: &mut dyn FnMut(Part)) {
+        match self {
+            Node::Raw(sql) if nested => {
+                out(Part::Sql("("));
+                out(Part::Sql(sql));
+                out(Part::Sql(")"));
+            }
+            Node::Raw(sql) => out(Part::Sql(sql)),
+            Node::Compare(column, op, value) => {
+                out(Part::Sql(columns[*column]));
+                out(Part::Sql(op));
+                out(Part::Bind(*column, value()));
+            }
+            Node::In(column, values) => {
+                out(Part::Sql(columns[*column]));
+                out(Part::Sql(" in ("));
+                if values.is_empty() {
+                    // matches no row
+                    out(Part::Sql("NULL"));
+                }
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 {
+                        out(Part::Sql(","));
+                    }
+                    out(Part::Bind(*column, value()));
+                }
+                out(Part::Sql(")"));
+            }
+            Node::Null(column, is) => {
+                out(Part::Sql(columns[*column]));
+                out(Part::Sql(is));
+            }
+            Node::Group(op, nodes) => {
+                if nested {
+                    out(Part::Sql("("));
+                }
+                for (i, node) in nodes.iter().enumerate() {
+                    if i != 0 {
+                        out(Part::Sql(if *op == "and" { " and " } else { " or " }));
+                    }
+                    node.render(columns, true, out);
+                }
+                if nested {
+                    out(Part::Sql(")"));
+                }
+            }
+        }
+    }
+}
+
+/// A field of the table `T` with values of `V`(the `T` of an `Option<T>` field), returned by
+/// the associated fns named like its fields generated by `#[cdbc::crud]`, e.g. `BizActivity::name()`.
+pub struct Field<T, V> {
+    index: usize,
+    value: PhantomData<fn() -> (T, V)>,
+}
+
+impl<T, V> Field<T, V> {
+    /// the field of the index in the columns of the table
+    #[doc(hidden)]
+    pub fn new(index: usize) -> Self {
+        Field { index, value: PhantomData }
+    }
+
+    #[doc(hidden)]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// `field is null`
+    pub fn is_null(self) -> Condition<T> {
+        Condition::from_node(Node::Null(self.index, " is null"))
+    }
+
+    /// `field is not null`
+    pub fn is_not_null(self) -> Condition<T> {
+        Condition::from_node(Node::Null(self.index, " is not null"))
+    }
+}
+
+impl<T, V: Clone + Send + Sync + 'static> Field<T, V> {
+    fn compare(self, op: &'static str, value: V) -> Condition<T> {
+        Condition::from_node(Node::Compare(self.index, op, value_of(value)))
+    }
+
+    /// `field = value`
+    pub fn eq(self, value: impl Into<V>) -> Condition<T> {
+        self.compare(" = ", value.into())
+    }
+
+    /// `field <> value`
+    pub fn ne(self, value: impl Into<V>) -> Condition<T> {
+        self.compare(" <> ", value.into())
+    }
+
+    /// `field < value`
+    pub fn lt(self, value: impl Into<V>) -> Condition<T> {
+        self.compare(" < ", value.into())
+    }
+
+    /// `field <= value`
+    pub fn le(self, value: impl Into<V>) -> Condition<T> {
+        self.compare(" <= ", value.into())
+    }
+
+    /// `field > value`
+    pub fn gt(self, value: impl Into<V>) -> Condition<T> {
+        self.compare(" > ", value.into())
+    }
+
+    /// `field >= value`
+    pub fn ge(self, value: impl Into<V>) -> Condition<T> {
+        self.compare(" >= ", value.into())
+    }
+
+    /// `field like pattern`
+    pub fn like(self, pattern: impl Into<V>) -> Condition<T> {
+        self.compare(" like ", pattern.into())
+    }
+
+    /// `field in (values..)`, an empty list matches no row.
+    pub fn in_list<I>(self, values: I) -> Condition<T>
+        where I: IntoIterator,
+              I::Item: Into<V>,
+    {
+        let values = values.into_iter().map(|v| value_of(v.into())).collect();
+        Condition::from_node(Node::In(self.index, values))
+    }
+}
+
+fn value_of<V: Clone + Send + Sync + 'static>(value: V) -> Value {
+    Box::new(move || Box::new(value.clone()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crud::{Condition, Field, Page, PageRequest, Part};
+
+    struct Biz;
+
+    /// the sql with `?` placeholders and the values, all `i32` here
+    fn render(c: &Condition<Biz>) -> (String, Vec<(usize, i32)>) {
+        let mut sql = String::new();
+        let mut values = vec![];
+        if let Some(node) = &c.node {
+            node.render(&["\"id\"", "\"age\""], false, &mut |part| match part {
+                Part::Sql(s) => sql.push_str(s),
+                Part::Bind(column, value) => {
+                    sql.push('?');
+                    values.push((column, *value.downcast::<i32>().unwrap()));
+                }
+            });
+        }
+        (sql, values)
+    }
+
+    #[test]
+    fn test_condition() {
+        let id = || Field::<Biz, i32>::new(0);
+        let age = || Field::<Biz, i32>::new(1);
+        let c = id().eq(1).and(age().gt(18).or(age().is_null())).and(id().in_list(vec![2, 3]));
+        assert_eq!(render(&c), (
+            "\"id\" = ? and (\"age\" > ? or \"age\" is null) and \"id\" in (?,?)".to_string(),
+            vec![(0, 1), (1, 18), (0, 2), (0, 3)]
+        ));
+        // rendered again for every statement
+        assert_eq!(render(&c).1.len(), 4);
+
+        let c = Condition::raw("a = 1 or b = 2").and(id().in_list(Vec::<i32>::new())).or(Condition::new());
+        assert_eq!(render(&c).0, "(a = 1 or b = 2) and \"id\" in (NULL)");
+        assert!(Condition::<Biz>::raw(" ").and(Condition::new()).is_empty());
+    }
+
+    #[test]
+    fn test_order() {
+        let id = || Field::<Biz, i32>::new(0);
+        let age = || Field::<Biz, i32>::new(1);
+        let columns = ["\"id\"", "\"age\""];
+        let c = Condition::<Biz>::new();
+        assert!(!c.is_ordered());
+        assert_eq!(c.order_sql(&columns, " order by \"id\""), " order by \"id\"");
+        assert_eq!(Condition::<Biz>::new().order_sql(&columns, ""), "");
+
+        // kept through `and`/`or`, the left side first
+        let c = age().gt(18).order_by_desc(age()).and(id().ne(0).order_by(id()));
+        assert!(c.is_ordered());
+        assert_eq!(c.order_sql(&columns, " order by \"id\""), " order by \"age\" desc,\"id\"");
+        assert_eq!(render(&c).0, "\"age\" > ? and \"id\" <> ?");
+        let c = Condition::new().order_by(id()).or(age().is_null());
+        assert_eq!((render(&c).0.as_str(), c.order_sql(&columns, "").as_str()), ("\"age\" is null", " order by \"id\""));
+    }
+
+    #[test]
+    fn test_page() {
+        assert_eq!(PageRequest::new(3, 10).offset(), 20);
+        assert_eq!(PageRequest::new(0, 10).offset(), 0);
+        let page = Page::<Biz>::new(PageRequest::new(0, 10), 21, vec![]);
+        assert_eq!((page.page_no, page.pages()), (1, 3));
+        assert_eq!(Page::<Biz>::new(PageRequest::new(1, 0), 21, vec![]).pages(), 0);
+    }
+}
@@ -2,6 +2,10 @@ pub mod statement_cache;
 pub mod ustr;
 pub mod scan;
 pub mod crud;
+#[doc(hidden)]
+pub mod sql;
+#[doc(hidden)]
+pub mod ty_match;
 
 
 use std::fmt::{Debug, Formatter};
@@ -0,0 +1,225 @@
+//! Scanning SQL text for placeholders, outside of its literals and comments.
+
+use std::fmt::Write;
+
+pub(crate) fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_'
+}
+
+pub(crate) fn is_ident(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+/// The end of the literal quoted by `quote` that starts at `start`, a doubled quote is part of it.
+fn quoted_end(sql: &[u8], start: usize, quote: u8, backslash_escapes: bool) -> usize {
+    let mut i = start + 1;
+    while i < sql.len() {
+        if backslash_escapes && sql[i] == b'\\' {
+            i += 2;
+        } else if sql[i] == quote {
+            if sql.get(i + 1) == Some(&quote) {
+                i += 2;
+            } else {
+                return i + 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    sql.len()
+}
+
+/// The end of the `/* */` comment that starts at `start`, comments nest like they do in Postgres.
+fn comment_end(sql: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < sql.len() {
+        if sql[i] == b'/' && sql[i + 1] == b'*' {
+            depth += 1;
+            i += 2;
+        } else if sql[i] == b'*' && sql[i + 1] == b'/' {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    sql.len()
+}
+
+/// The end of the Postgres dollar-quoted string(`$$..$$` or `$tag$..$tag$`) at `start`,
+/// `None` if there is none(e.g. `$1`).
+fn dollar_quoted_end(sql: &str, start: usize) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let mut i = start + 1;
+    if i < bytes.len() && is_ident_start(bytes[i]) {
+        while i < bytes.len() && is_ident(bytes[i]) {
+            i += 1;
+        }
+    }
+    if bytes.get(i) != Some(&b'$') {
+        return None;
+    }
+    let tag = &sql[start..=i];
+    Some(match sql[i + 1..].find(tag) {
+        Some(end) => i + 1 + end + tag.len(),
+        None => sql.len(),
+    })
+}
+
+/// The end of the string literal, quoted identifier or comment that starts at `i`, `None` if
+/// there is none.
+pub fn literal_end(sql: &str, i: usize) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let prev_ident = i > 0 && is_ident(bytes[i - 1]);
+    let next = bytes.get(i + 1).copied();
+    match bytes[i] {
+        b'\'' => {
+            // Postgres escape strings E'..' take backslash escapes
+            let escape = i > 0
+                && (bytes[i - 1] == b'E' || bytes[i - 1] == b'e')
+                && !(i > 1 && is_ident(bytes[i - 2]));
+            Some(quoted_end(bytes, i, b'\'', escape))
+        }
+        b'"' | b'`' => Some(quoted_end(bytes, i, bytes[i], false)),
+        b'-' if next == Some(b'-') => Some(sql[i..].find('\n').map(|n| i + n + 1).unwrap_or(sql.len())),
+        b'/' if next == Some(b'*') => Some(comment_end(bytes, i)),
+        b'$' if !prev_ident => dollar_quoted_end(sql, i),
+        _ => None,
+    }
+}
+
+/// The end and the number(0 for `?`) of the placeholder of `holder` that starts at `i`.
+fn placeholder_at(sql: &str, i: usize, holder: &str) -> Option<(usize, usize)> {
+    let bytes = sql.as_bytes();
+    let prefix = match holder {
+        "?" => return if bytes[i] == b'?' { Some((i + 1, 0)) } else { None },
+        "p" => "@p",
+        _ => holder,
+    };
+    if !sql[i..].starts_with(prefix) || (i > 0 && (is_ident(bytes[i - 1]) || bytes[i - 1] == b'@')) {
+        return None;
+    }
+    let start = i + prefix.len();
+    let mut end = start;
+    while end < bytes.len() && bytes[end].is_ascii_digit() {
+        end += 1;
+    }
+    sql[start..end].parse().ok().map(|number| (end, number))
+}
+
+/// Write the placeholder of the `index`(starts at 1) bind argument for the
+/// [`Database::holder`](crate::database::Database::holder).
+pub(crate) fn push_placeholder(query: &mut String, holder: &str, index: usize) {
+    match holder {
+        "?" => query.push('?'),
+        "p" => {
+            write!(query, "@p{}", index).unwrap();
+        }
+        _ => {
+            write!(query, "{}{}", holder, index).unwrap();
+        }
+    }
+}
+
+/// Expand the placeholder of the `index`(starts at 1) bind argument into `len` placeholders,
+/// renumbering the later numbered placeholders. An empty list becomes `NULL`, so `x in (NULL)`
+/// matches no row.
+///
+/// `Err` if `sql` has no such placeholder, or uses the numbered placeholder more than once.
+pub(crate) fn expand_placeholder(sql: &str, holder: &str, index: usize, len: usize) -> Result<String, String> {
+    let mut out = String::with_capacity(sql.len() + len * 4);
+    let mut count = 0;
+    let mut found = false;
+    let mut last = 0;
+    let mut i = 0;
+    while i < sql.len() {
+        if let Some(end) = literal_end(sql, i) {
+            i = end;
+            continue;
+        }
+        let (end, number) = match placeholder_at(sql, i, holder) {
+            Some(placeholder) => placeholder,
+            None => {
+                i += 1;
+                continue;
+            }
+        };
+        count += 1;
+        // `?` are counted, numbered placeholders carry their index
+        let number = if number == 0 { count } else { number };
+        if number >= index {
+            out.push_str(&sql[last..i]);
+            if number == index {
+                if found {
+                    return Err(format!("`{}` is used more than once", &sql[i..end]));
+                }
+                found = true;
+                if len == 0 {
+                    out.push_str("NULL");
+                }
+                for n in 0..len {
+                    if n > 0 {
+                        out.push_str(", ");
+                    }
+                    push_placeholder(&mut out, holder, index + n);
+                }
+            } else if holder == "?" {
+                out.push('?');
+            } else {
+                push_placeholder(&mut out, holder, number + len - 1);
+            }
+            last = end;
+        }
+        i = end;
+    }
+    out.push_str(&sql[last..]);
+    if !found {
+        return Err(format!("no placeholder for the bind parameter {}", index));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::sql::{expand_placeholder, push_placeholder};
+
+    #[test]
+    fn test_push_placeholder() {
+        let mut sql = String::new();
+        for holder in ["?", "$", "p"] {
+            push_placeholder(&mut sql, holder, 1);
+            sql.push(',');
+            push_placeholder(&mut sql, holder, 2);
+            sql.push(' ');
+        }
+        assert_eq!(sql, "?,? $1,$2 @p1,@p2 ");
+    }
+
+    #[test]
+    fn test_expand_placeholder() {
+        assert_eq!(
+            expand_placeholder("select '?' from t where a = ? and id in (?) and b = ?", "?", 2, 3).unwrap(),
+            "select '?' from t where a = ? and id in (?, ?, ?) and b = ?"
+        );
+        assert_eq!(
+            expand_placeholder("select '$2' from t where a = $1 and id in ($2) and b = $3 or c = $1", "$", 2, 3).unwrap(),
+            "select '$2' from t where a = $1 and id in ($2, $3, $4) and b = $5 or c = $1"
+        );
+        assert_eq!(
+            expand_placeholder("select * from t where id in (@p1) and b = @p2", "p", 1, 0).unwrap(),
+            "select * from t where id in (NULL) and b = @p1"
+        );
+        assert_eq!(
+            expand_placeholder("select $1", "$", 2, 1).unwrap_err(),
+            "no placeholder for the bind parameter 2"
+        );
+        assert_eq!(
+            expand_placeholder("select * from t where id in ($1) or parent in ($1)", "$", 1, 2).unwrap_err(),
+            "`$1` is used more than once"
+        );
+    }
+}
@@ -0,0 +1,50 @@
+//! Compile-time helpers for the checked `query!` macros of `cdbc-macro`.
+//!
+//! The macros emit `same_as::<SqlType, _>(&arg)` (never executed) for every bind argument whose
+//! SQL type is known, so passing e.g. an `i64` to an `INT4` parameter fails to compile.
+//! References and `Option` are looked through, `String`/`str` and `Vec<u8>`/`[u8]` are the same.
+
+/// The plain type behind a bind argument.
+pub trait Strip {
+    type Inner: ?Sized;
+}
+
+impl<T: Strip + ?Sized> Strip for &'_ T {
+    type Inner = T::Inner;
+}
+
+impl<T: Strip> Strip for Option<T> {
+    type Inner = T::Inner;
+}
+
+macro_rules! impl_strip {
+    ($($ty:ty => $inner:ty),* $(,)?) => {
+        $(
+            impl Strip for $ty {
+                type Inner = $inner;
+            }
+        )*
+    };
+}
+
+impl_strip!(
+    bool => bool,
+    i8 => i8,
+    i16 => i16,
+    i32 => i32,
+    i64 => i64,
+    u8 => u8,
+    u16 => u16,
+    u32 => u32,
+    u64 => u64,
+    f32 => f32,
+    f64 => f64,
+    str => str,
+    String => str,
+    [u8] => [u8],
+    Vec<u8> => [u8],
+);
+
+/// Only compiles when `U` is `T`, a reference to it or an `Option` of it.
+#[inline(always)]
+pub fn same_as<T: ?Sized, U: Strip<Inner = T> + ?Sized>(_: &U) {}